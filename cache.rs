@@ -0,0 +1,512 @@
+//! Cache local des fichiers objets, partagé entre tous les projets de l'utilisateur.
+//!
+//! La clé d'une entrée est l'empreinte SHA-256 du source préprocessé, de l'identité du
+//! compilateur et des options de compilation. Les chemins des marqueurs de ligne du source préprocessé
+//! sont rendus relatifs au dossier du projet, pour qu'une même source ait la même clé d'un projet à l'autre. Une entrée contient le fichier objet et les
+//! diagnostics produits lors de la compilation d'origine. À la fin de chaque build, les entrées
+//! les moins récemment utilisées sont supprimées au-delà de la taille maximale et les compteurs
+//! de succès / échecs du build sont ajoutés à ceux du cache.
+
+use std::env;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+
+use crate::context::BuildContext;
+use crate::diagnostics::gcc_command;
+use crate::remote_cache::RemoteCache;
+use crate::settings::Settings;
+use crate::tr;
+
+/// Taille maximale par défaut du cache : 1 Gio.
+const DEFAULT_MAX_SIZE: u64 = 1024 * 1024 * 1024;
+
+const OBJECT_FILE_NAME: &str = "object.o";
+const DIAGNOSTICS_FILE_NAME: &str = "diagnostics.txt";
+const STATS_FILE_NAME: &str = "stats.txt";
+
+/// Numéro des fichiers temporaires, pour que deux écritures simultanées du processus ne partagent pas le même chemin.
+static NEXT_TEMPORARY_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Entrée trouvée dans le cache.
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub object: PathBuf,
+    pub diagnostics: String,
+    /// L'entrée provient du cache distant.
+    pub remote: bool,
+}
+
+/// Statistiques d'utilisation du cache.
+#[derive(Debug, Clone, Default)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub size: u64,
+    pub max_size: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Cache de compilation situé dans un dossier de l'utilisateur.
+#[derive(Debug)]
+pub struct CompileCache {
+    pub root: PathBuf,
+    pub max_size: u64,
+    pub remote: Option<RemoteCache>,
+    compiler_identity: String,
+    /// Succès et échecs du build en cours, écrits dans `stats.txt` par [`CompileCache::finish`].
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CompileCache {
+    /// Construit le cache décrit par la configuration ; `None` si `CACHE : off`.
+    pub fn from_settings(settings: &Settings) -> Option<CompileCache> {
+        if !settings.get_bool("CACHE", true) {
+            return None;
+        }
+
+        let root: PathBuf = match settings.get("CACHE_DIR") {
+            Some(directory) => PathBuf::from(directory),
+            None => default_cache_root(),
+        };
+        let max_size: u64 = settings.get("CACHE_MAX_SIZE").and_then(parse_size).unwrap_or(DEFAULT_MAX_SIZE);
+
+        let remote: Option<RemoteCache> = match RemoteCache::from_settings(settings) {
+            Some(Ok(remote)) => Some(remote),
+            Some(Err(err)) => {
+                eprintln!("{}", tr!("cache.remote_ignored", err));
+                None
+            }
+            None => None,
+        };
+
+        Some(CompileCache {
+            root,
+            max_size,
+            remote,
+            compiler_identity: compiler_identity(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        })
+    }
+
+    /// Calcule la clé d'une compilation à partir du source préprocessé et des options.
+    ///
+    /// Avec `-g`, les chemins des marqueurs de ligne sont inscrits dans les informations de débogage :
+    /// ils restent alors absolus et la clé dépend du dossier du projet.
+    pub fn key(&self, preprocessed_source: &[u8], flags: &[String], project_root: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.compiler_identity.as_bytes());
+        for flag in flags {
+            hasher.update([0u8]);
+            hasher.update(flag.as_bytes());
+        }
+        hasher.update([0u8]);
+        if flags.iter().any(|flag| flag.starts_with("-g") && flag != "-g0") {
+            hasher.update(preprocessed_source);
+        } else {
+            hasher.update(relative_line_markers(preprocessed_source, project_root));
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.root.join(&key[..2]).join(key)
+    }
+
+    /// Cherche une entrée localement puis dans le cache distant ; un succès rafraîchit sa date d'utilisation.
+    pub fn lookup(&self, context: &BuildContext, key: &str) -> Option<CacheEntry> {
+        let entry_path: PathBuf = self.entry_path(key);
+        let object: PathBuf = entry_path.join(OBJECT_FILE_NAME);
+
+        if !object.is_file() {
+            let entry: Option<CacheEntry> = self.lookup_remote(context, key);
+            self.record(entry.is_some());
+            return entry;
+        }
+
+        let diagnostics: String = fs::read_to_string(entry_path.join(DIAGNOSTICS_FILE_NAME)).unwrap_or_default();
+        if let Ok(file) = File::options().write(true).open(&object) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+
+        self.record(true);
+        Some(CacheEntry { object, diagnostics, remote: false })
+    }
+
+    /// Télécharge une entrée du cache distant et la conserve dans le cache local.
+    fn lookup_remote(&self, context: &BuildContext, key: &str) -> Option<CacheEntry> {
+        let remote: &RemoteCache = self.remote.as_ref()?;
+
+        let remote_entry = match remote.fetch(key) {
+            Ok(remote_entry) => remote_entry?,
+            Err(err) => {
+                context.write_in_logs(tr!("cache.remote_read_failed", &key[..12], err));
+                return None;
+            }
+        };
+
+        let temporary_object: PathBuf = env::temp_dir().join(format!("compilerc-{}-{}-{}.o", key, std::process::id(), temporary_id()));
+        let stored = fs::write(&temporary_object, &remote_entry.object)
+            .and_then(|_| self.store_local(key, &temporary_object, &remote_entry.diagnostics));
+        let _ = fs::remove_file(&temporary_object);
+
+        match stored {
+            Ok(_) => Some(CacheEntry {
+                object: self.entry_path(key).join(OBJECT_FILE_NAME),
+                diagnostics: remote_entry.diagnostics,
+                remote: true,
+            }),
+            Err(err) => {
+                context.write_in_logs(tr!("cache.remote_not_kept", &key[..12], err));
+                None
+            }
+        }
+    }
+
+    /// Enregistre le résultat d'une compilation réussie, localement puis dans le cache distant.
+    pub fn store(&self, context: &BuildContext, key: &str, object: &Path, diagnostics: &str) -> Result<(), io::Error> {
+        self.store_local(key, object, diagnostics)?;
+
+        if let Some(remote) = &self.remote {
+            let uploaded = fs::read(object)
+                .map_err(|err| err.to_string())
+                .and_then(|content| remote.upload(key, &content, diagnostics));
+
+            if let Err(err) = uploaded {
+                context.write_in_logs(tr!("cache.remote_upload_failed", &key[..12], err));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Enregistre une entrée dans le cache local ; la limite de taille est appliquée par [`CompileCache::finish`].
+    fn store_local(&self, key: &str, object: &Path, diagnostics: &str) -> Result<(), io::Error> {
+        let entry_path: PathBuf = self.entry_path(key);
+        let temporary_path: PathBuf = self.root.join(format!("tmp-{}-{}-{}", key, std::process::id(), temporary_id()));
+
+        // Écrit dans un dossier temporaire puis renomme, pour ne jamais exposer une entrée incomplète
+        fs::create_dir_all(&temporary_path)?;
+        fs::copy(object, temporary_path.join(OBJECT_FILE_NAME))?;
+        fs::write(temporary_path.join(DIAGNOSTICS_FILE_NAME), diagnostics)?;
+
+        fs::create_dir_all(entry_path.parent().unwrap())?;
+        if fs::rename(&temporary_path, &entry_path).is_err() {
+            // Une autre compilation a déjà rempli l'entrée
+            fs::remove_dir_all(&temporary_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Termine le build : applique la limite de taille puis ajoute ses compteurs à `stats.txt`.
+    pub fn finish(&self) -> Result<(), io::Error> {
+        self.evict()?;
+
+        let (hits, misses) = (self.hits.swap(0, Ordering::Relaxed), self.misses.swap(0, Ordering::Relaxed));
+        if hits + misses == 0 {
+            return Ok(());
+        }
+
+        let (total_hits, total_misses) = self.read_counters();
+        let stats_path: PathBuf = self.root.join(STATS_FILE_NAME);
+        let temporary_path: PathBuf = self.root.join(format!("{}-{}", STATS_FILE_NAME, std::process::id()));
+
+        fs::create_dir_all(&self.root)?;
+        fs::write(&temporary_path, format!("HITS : {}\nMISSES : {}\n", total_hits + hits, total_misses + misses))?;
+        fs::rename(&temporary_path, &stats_path)
+    }
+
+    /// Supprime les entrées les moins récemment utilisées jusqu'à repasser sous la taille maximale.
+    fn evict(&self) -> Result<(), io::Error> {
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = self.entries()?;
+        let mut total_size: u64 = entries.iter().map(|(_, size, _)| size).sum();
+
+        entries.sort_by_key(|(last_used, _, _)| *last_used);
+
+        for (_, size, entry_path) in entries {
+            if total_size <= self.max_size {
+                break;
+            }
+            fs::remove_dir_all(&entry_path)?;
+            total_size -= size;
+        }
+
+        Ok(())
+    }
+
+    /// Liste les entrées avec leur date de dernière utilisation et leur taille.
+    fn entries(&self) -> Result<Vec<(SystemTime, u64, PathBuf)>, io::Error> {
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = Vec::new();
+
+        if !self.root.is_dir() {
+            return Ok(entries);
+        }
+
+        for prefix in fs::read_dir(&self.root)?.flatten() {
+            let prefix_path: PathBuf = prefix.path();
+            let is_prefix_dir: bool = prefix_path.is_dir() && prefix.file_name().len() == 2;
+            if !is_prefix_dir {
+                continue;
+            }
+
+            for entry in fs::read_dir(&prefix_path)?.flatten() {
+                let entry_path: PathBuf = entry.path();
+                let object_metadata = match fs::metadata(entry_path.join(OBJECT_FILE_NAME)) {
+                    Ok(metadata) => metadata,
+                    Err(_) => continue,
+                };
+                let diagnostics_size: u64 = fs::metadata(entry_path.join(DIAGNOSTICS_FILE_NAME)).map(|m| m.len()).unwrap_or(0);
+
+                entries.push((
+                    object_metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    object_metadata.len() + diagnostics_size,
+                    entry_path,
+                ));
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Statistiques : nombre d'entrées, taille occupée et compteurs de succès / échecs.
+    pub fn stats(&self) -> Result<CacheStats, io::Error> {
+        let entries = self.entries()?;
+        let (hits, misses) = self.read_counters();
+
+        Ok(CacheStats {
+            entries: entries.len(),
+            size: entries.iter().map(|(_, size, _)| size).sum(),
+            max_size: self.max_size,
+            hits,
+            misses,
+        })
+    }
+
+    /// Vide le cache et remet les compteurs à zéro ; renvoie le nombre d'entrées supprimées.
+    pub fn clear(&self) -> Result<usize, io::Error> {
+        let count: usize = self.entries()?.len();
+        if self.root.exists() {
+            fs::remove_dir_all(&self.root)?;
+        }
+        Ok(count)
+    }
+
+    fn read_counters(&self) -> (u64, u64) {
+        let content: String = fs::read_to_string(self.root.join(STATS_FILE_NAME)).unwrap_or_default();
+        let counters = Settings::parse(&content);
+        let read = |key: &str| counters.get(key).and_then(|value| value.parse().ok()).unwrap_or(0);
+        (read("HITS"), read("MISSES"))
+    }
+
+    fn record(&self, hit: bool) {
+        let counter: &AtomicU64 = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn temporary_id() -> u64 {
+    NEXT_TEMPORARY_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Retire le dossier du projet des chemins des marqueurs de ligne (`# 12 "projet/src/main.c" 2`).
+fn relative_line_markers(preprocessed_source: &[u8], project_root: &str) -> Vec<u8> {
+    let prefix: Vec<u8> = format!("\"{}/", project_root.replace('\\', "/").trim_end_matches('/')).into_bytes();
+    let mut normalized: Vec<u8> = Vec::with_capacity(preprocessed_source.len());
+
+    for line in preprocessed_source.split_inclusive(|byte| *byte == b'\n') {
+        let is_marker: bool = line.starts_with(b"# ") && line.get(2).is_some_and(u8::is_ascii_digit);
+        match line.windows(prefix.len()).position(|window| window == prefix.as_slice()).filter(|_| is_marker) {
+            Some(start) => {
+                normalized.extend_from_slice(&line[..=start]);
+                normalized.extend_from_slice(&line[start + prefix.len()..]);
+            }
+            None => normalized.extend_from_slice(line),
+        }
+    }
+
+    normalized
+}
+
+/// Dossier du cache : `COMPILERC_CACHE_DIR`, sinon le dossier de cache de l'utilisateur.
+fn default_cache_root() -> PathBuf {
+    if let Ok(directory) = env::var("COMPILERC_CACHE_DIR") {
+        return PathBuf::from(directory);
+    }
+
+    if cfg!(windows) {
+        if let Ok(local_app_data) = env::var("LOCALAPPDATA") {
+            return Path::new(&local_app_data).join("CompilerC").join("cache");
+        }
+    }
+    if let Ok(xdg_cache) = env::var("XDG_CACHE_HOME") {
+        return Path::new(&xdg_cache).join("compilerc");
+    }
+    if let Ok(home) = env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
+        return Path::new(&home).join(".cache").join("compilerc");
+    }
+
+    env::temp_dir().join("compilerc-cache")
+}
+
+/// Identité du compilateur : version complète et machine cible.
+fn compiler_identity() -> String {
+    let run = |argument: &str| -> String {
+        gcc_command()
+            .arg(argument)
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            .unwrap_or_default()
+    };
+
+    format!("{}\n{}", run("--version"), run("-dumpmachine"))
+}
+
+/// Interprète une taille (`500M`, `2G`, `1024K` ou un nombre d'octets).
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value: String = value.trim().to_uppercase();
+    let value: &str = value.trim_end_matches('B').trim_end_matches('I');
+
+    let (number, multiplier) = match value.chars().last()? {
+        'K' => (&value[..value.len() - 1], 1024),
+        'M' => (&value[..value.len() - 1], 1024 * 1024),
+        'G' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+
+    number.trim().parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Cache vide dans un dossier temporaire propre au test.
+    fn test_cache(name: &str, max_size: u64) -> CompileCache {
+        let root: PathBuf = env::temp_dir().join(format!("compilerc-cache-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&root);
+
+        CompileCache { root, max_size, remote: None, compiler_identity: "gcc 13\nx86_64-linux-gnu".to_string(), hits: AtomicU64::new(0), misses: AtomicU64::new(0) }
+    }
+
+    /// Ajoute une entrée de `size` octets, utilisée pour la dernière fois il y a `age` secondes.
+    fn add_entry(cache: &CompileCache, key: &str, size: usize, age: u64) {
+        let object: PathBuf = cache.root.join(format!("{}.o", key));
+        fs::create_dir_all(&cache.root).unwrap();
+        fs::write(&object, vec![0u8; size]).unwrap();
+        cache.store_local(key, &object, "").unwrap();
+        fs::remove_file(&object).unwrap();
+
+        let last_used: SystemTime = SystemTime::now() - Duration::from_secs(age);
+        File::options().write(true).open(cache.entry_path(key).join(OBJECT_FILE_NAME)).unwrap().set_modified(last_used).unwrap();
+    }
+
+    #[test]
+    fn sizes_accept_units() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("4K"), Some(4 * 1024));
+        assert_eq!(parse_size("500M"), Some(500 * 1024 * 1024));
+        assert_eq!(parse_size(" 2 GiB "), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("2gb"), Some(2 * 1024 * 1024 * 1024));
+    }
+
+    #[test]
+    fn invalid_or_overflowing_sizes_are_refused() {
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("M"), None);
+        assert_eq!(parse_size("12T"), None);
+        assert_eq!(parse_size("-1G"), None);
+        assert_eq!(parse_size("99999999999G"), None);
+        assert_eq!(parse_size(&format!("{}K", u64::MAX)), None);
+    }
+
+    #[test]
+    fn the_key_does_not_depend_on_the_project_folder() {
+        let cache: CompileCache = test_cache("key", DEFAULT_MAX_SIZE);
+        let flags: Vec<String> = vec!["-c".to_string(), "-O2".to_string()];
+        let in_project = |root: &str| format!("# 1 \"{root}/src/main.c\"\n# 1 \"/usr/include/stdio.h\" 1 3 4\nint printf(const char *, ...);\n# 2 \"{root}/src/main.c\" 2\nint main(void) {{ return 0; }}\n");
+
+        let key: String = cache.key(in_project("/home/a/projet").as_bytes(), &flags, "/home/a/projet");
+        assert_eq!(key, cache.key(in_project("/tmp/copie").as_bytes(), &flags, "/tmp/copie/"));
+        assert_eq!(key, cache.key(in_project("C:/copie").as_bytes(), &flags, "C:\\copie"));
+
+        assert_ne!(key, cache.key(in_project("/tmp/copie").as_bytes(), &["-c".to_string(), "-O0".to_string()], "/tmp/copie"));
+        assert_ne!(key, cache.key(b"int main(void) { return 1; }\n", &flags, "/tmp/copie"));
+    }
+
+    #[test]
+    fn with_debug_information_the_key_depends_on_the_project_folder() {
+        let cache: CompileCache = test_cache("key-debug", DEFAULT_MAX_SIZE);
+        let flags: Vec<String> = vec!["-c".to_string(), "-g".to_string()];
+
+        assert_ne!(
+            cache.key(b"# 1 \"/home/a/projet/main.c\"\nint x;\n", &flags, "/home/a/projet"),
+            cache.key(b"# 1 \"/tmp/copie/main.c\"\nint x;\n", &flags, "/tmp/copie")
+        );
+    }
+
+    #[test]
+    fn only_line_markers_are_rewritten() {
+        let source: &[u8] = b"# 12 \"p/src/a.c\" 2\nconst char *s = \"p/src/a.c\";\n#pragma \"p/x\"\n";
+        assert_eq!(relative_line_markers(source, "p"), b"# 12 \"src/a.c\" 2\nconst char *s = \"p/src/a.c\";\n#pragma \"p/x\"\n");
+    }
+
+    #[test]
+    fn the_least_recently_used_entries_are_evicted() {
+        let cache: CompileCache = test_cache("evict", 250);
+        add_entry(&cache, "aa01", 100, 300);
+        add_entry(&cache, "aa02", 100, 100);
+        add_entry(&cache, "bb03", 100, 200);
+
+        cache.finish().unwrap();
+
+        assert!(!cache.entry_path("aa01").exists());
+        assert!(cache.entry_path("aa02").exists());
+        assert!(cache.entry_path("bb03").exists());
+        assert_eq!(cache.stats().unwrap().size, 200);
+        fs::remove_dir_all(&cache.root).unwrap();
+    }
+
+    #[test]
+    fn a_lookup_refreshes_the_entry() {
+        let cache: CompileCache = test_cache("refresh", 250);
+        add_entry(&cache, "aa01", 100, 300);
+        add_entry(&cache, "aa02", 100, 100);
+        add_entry(&cache, "bb03", 100, 200);
+
+        let context: BuildContext = BuildContext::new(&cache.root.to_string_lossy(), &cache.root.join("target").to_string_lossy());
+        assert!(cache.lookup(&context, "aa01").is_some());
+        assert!(cache.lookup(&context, "cc04").is_none());
+        cache.finish().unwrap();
+
+        assert!(cache.entry_path("aa01").exists());
+        assert!(!cache.entry_path("bb03").exists());
+        assert_eq!((cache.stats().unwrap().hits, cache.stats().unwrap().misses), (1, 1));
+        fs::remove_dir_all(&cache.root).unwrap();
+    }
+
+    #[test]
+    fn identical_entries_can_be_stored_at_the_same_time() {
+        let cache: CompileCache = test_cache("concurrent", DEFAULT_MAX_SIZE);
+        fs::create_dir_all(&cache.root).unwrap();
+        let object: PathBuf = cache.root.join("source.o");
+        fs::write(&object, b"object").unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| cache.store_local("aa01", &object, "warning").unwrap());
+            }
+        });
+
+        assert_eq!(fs::read(cache.entry_path("aa01").join(OBJECT_FILE_NAME)).unwrap(), b"object");
+        assert!(fs::read_dir(&cache.root).unwrap().flatten().all(|entry| !entry.file_name().to_string_lossy().starts_with("tmp-")));
+        fs::remove_dir_all(&cache.root).unwrap();
+    }
+}
@@ -0,0 +1,327 @@
+//! Nettoyage sélectif des fichiers produits dans la cible d'un projet.
+//!
+//! Chaque portée correspond à des dossiers de la cible. Sans portée, les fichiers produits par le
+//! build sont supprimés ; les logs et l'historique (`snapshots`) ne le sont que sur demande.
+//! Aucun fichier situé hors du dossier cible du projet n'est jamais supprimé.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::context::BuildContext;
+use crate::error::BuildError;
+use crate::tr;
+
+/// Portées et filtres du nettoyage.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanOptions {
+    pub objects: bool,
+    pub executables: bool,
+    pub sources: bool,
+    pub libraries: bool,
+    pub logs: bool,
+    pub snapshots: bool,
+    /// Limite les objets et exécutables à un profil.
+    pub profile: Option<String>,
+    /// Ne supprime que les fichiers plus anciens que cette durée.
+    pub older_than: Option<Duration>,
+    /// Liste les fichiers sans les supprimer.
+    pub dry_run: bool,
+}
+
+impl CleanOptions {
+    /// Toutes les portées, logs et historique compris.
+    pub fn all() -> CleanOptions {
+        CleanOptions {
+            objects: true,
+            executables: true,
+            sources: true,
+            libraries: true,
+            logs: true,
+            snapshots: true,
+            ..CleanOptions::default()
+        }
+    }
+
+    /// Dossiers de la cible concernés, relatifs au dossier cible du projet.
+    pub fn folders(&self) -> Vec<String> {
+        let no_scope: bool = !(self.objects || self.executables || self.sources || self.libraries || self.logs || self.snapshots);
+        // Sans portée : tout ce que produit le build, ou seulement les fichiers compilés du profil demandé
+        let build_outputs: bool = no_scope && self.profile.is_none();
+        let compiled_outputs: bool = no_scope && self.profile.is_some();
+
+        let profile_folder = |folder: &str| -> String {
+            match &self.profile {
+                Some(profile) => format!("{}/{}", folder, profile),
+                None => folder.to_string(),
+            }
+        };
+
+        let mut folders: Vec<String> = Vec::new();
+        if self.objects || build_outputs || compiled_outputs {
+            folders.push(profile_folder("output"));
+        }
+        if self.executables || build_outputs || compiled_outputs {
+            folders.push(profile_folder("executable"));
+        }
+        if self.sources || build_outputs {
+            folders.extend(["source".to_string(), "resources".to_string()]);
+        }
+        if self.libraries || build_outputs {
+            folders.extend(["dll".to_string(), "a".to_string()]);
+        }
+        if self.logs {
+            folders.push("logs".to_string());
+        }
+        if self.snapshots {
+            folders.extend(["snapshots".to_string(), "stats".to_string()]);
+        }
+        folders
+    }
+}
+
+/// Résultat du nettoyage.
+#[derive(Debug, Clone, Default)]
+pub struct CleanReport {
+    /// Fichiers supprimés, ou qui le seraient en simulation.
+    pub files: Vec<PathBuf>,
+    pub size: u64,
+    pub dry_run: bool,
+}
+
+/// Supprime les fichiers des portées demandées dans la cible du projet.
+pub fn clean_target(context: &BuildContext, options: &CleanOptions) -> Result<CleanReport, BuildError> {
+    let mut report = CleanReport { dry_run: options.dry_run, ..CleanReport::default() };
+    let target_path: &Path = Path::new(&context.target_path);
+
+    if !target_path.is_dir() {
+        return Ok(report);
+    }
+
+    let storage_error = |err: io::Error| BuildError::Storage(tr!("clean.failed", err));
+    let target_root: PathBuf = fs::canonicalize(target_path).map_err(storage_error)?;
+    let project_root: PathBuf = fs::canonicalize(&context.project_path).map_err(storage_error)?;
+
+    // Une cible qui contient le projet ferait supprimer ses propres sources
+    if project_root.starts_with(&target_root) {
+        return Err(BuildError::Config(tr!("clean.target_contains_project", target_root.display(), project_root.display())));
+    }
+
+    // Une durée qui remonte avant l'origine des dates ne retient aucun fichier, au lieu de les retenir tous
+    let oldest: Option<SystemTime> =
+        options.older_than.map(|age| SystemTime::now().checked_sub(age).unwrap_or(SystemTime::UNIX_EPOCH));
+    let mut folders: Vec<PathBuf> = Vec::new();
+
+    for folder in options.folders() {
+        let folder_path: PathBuf = target_path.join(&folder);
+        if !folder_path.exists() {
+            continue;
+        }
+
+        let folder_root: PathBuf = fs::canonicalize(&folder_path).map_err(storage_error)?;
+        if !folder_root.starts_with(&target_root) || folder_root == target_root {
+            return Err(BuildError::Config(tr!("clean.outside_target", folder_root.display(), target_root.display())));
+        }
+
+        collect_files(&folder_path, oldest, &mut report).map_err(storage_error)?;
+        folders.push(folder_path);
+    }
+
+    if options.dry_run {
+        return Ok(report);
+    }
+
+    for file in &report.files {
+        fs::remove_file(file).map_err(storage_error)?;
+    }
+    for folder in &folders {
+        remove_empty_directories(folder);
+    }
+
+    if Path::new(&context.logs_path).is_dir() {
+        context.write_in_logs(tr!("clean.summary", report.files.len(), report.size));
+    }
+
+    Ok(report)
+}
+
+/// Liste les fichiers d'un dossier ; les liens symboliques sont listés eux-mêmes, sans être suivis.
+fn collect_files(directory: &Path, oldest: Option<SystemTime>, report: &mut CleanReport) -> Result<(), io::Error> {
+    for entry in fs::read_dir(directory)? {
+        let entry_path: PathBuf = entry?.path();
+        let metadata = fs::symlink_metadata(&entry_path)?;
+
+        if metadata.is_dir() {
+            collect_files(&entry_path, oldest, report)?;
+            continue;
+        }
+
+        let modified: SystemTime = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        if oldest.is_some_and(|oldest| modified > oldest) {
+            continue;
+        }
+
+        report.size += metadata.len();
+        report.files.push(entry_path);
+    }
+
+    Ok(())
+}
+
+/// Supprime les dossiers devenus vides, en partant des plus profonds.
+fn remove_empty_directories(directory: &Path) {
+    if let Ok(entries) = fs::read_dir(directory) {
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                remove_empty_directories(&entry.path());
+            }
+        }
+    }
+
+    // Échoue sans conséquence si le dossier contient encore des fichiers récents
+    let _ = fs::remove_dir(directory);
+}
+
+/// Interprète une durée (`30d`, `12h`, `2w`, `45m`, `90s`).
+pub fn parse_age(value: &str) -> Option<Duration> {
+    let value: &str = value.trim();
+    let unit: char = value.chars().last()?;
+    let number: u64 = value[..value.len() - unit.len_utf8()].trim().parse().ok()?;
+
+    let seconds: u64 = match unit.to_ascii_lowercase() {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' | 'j' => 24 * 60 * 60,
+        'w' => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+
+    Some(Duration::from_secs(number.checked_mul(seconds)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dossier temporaire propre au test, vidé au préalable, avec un projet `projet` contenant une source.
+    fn test_folder(name: &str) -> PathBuf {
+        let folder: PathBuf = std::env::temp_dir().join(format!("compilerc-clean-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&folder);
+        write(&folder.join("projet/src/main.c"), "int main(void) { return 0; }");
+        folder
+    }
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn context(folder: &Path, target_root: &Path) -> BuildContext {
+        BuildContext::new(&folder.join("projet").to_string_lossy(), &target_root.to_string_lossy())
+    }
+
+    #[test]
+    fn without_scope_the_build_outputs_are_cleaned() {
+        assert_eq!(CleanOptions::default().folders(), ["output", "executable", "source", "resources", "dll", "a"]);
+    }
+
+    #[test]
+    fn a_profile_limits_the_compiled_outputs() {
+        let options = CleanOptions { profile: Some("release".to_string()), ..CleanOptions::default() };
+        assert_eq!(options.folders(), ["output/release", "executable/release"]);
+
+        let options = CleanOptions { objects: true, logs: true, profile: Some("release".to_string()), ..CleanOptions::default() };
+        assert_eq!(options.folders(), ["output/release", "logs"]);
+    }
+
+    #[test]
+    fn logs_and_snapshots_are_only_cleaned_on_request() {
+        assert_eq!(CleanOptions { snapshots: true, ..CleanOptions::default() }.folders(), ["snapshots", "stats"]);
+        assert_eq!(
+            CleanOptions::all().folders(),
+            ["output", "executable", "source", "resources", "dll", "a", "logs", "snapshots", "stats"]
+        );
+    }
+
+    #[test]
+    fn only_the_requested_scopes_are_removed() {
+        let folder: PathBuf = test_folder("scopes");
+        let context: BuildContext = context(&folder, &folder.join("build"));
+        let target: PathBuf = PathBuf::from(&context.target_path);
+        write(&target.join("output/debug/main.o"), "objet");
+        write(&target.join("logs/0001_2024-05-02_140311/build.log"), "log");
+
+        let report: CleanReport = clean_target(&context, &CleanOptions { objects: true, dry_run: true, ..CleanOptions::default() }).unwrap();
+        assert_eq!(report.files, [target.join("output/debug/main.o")]);
+        assert!(target.join("output/debug/main.o").exists());
+
+        clean_target(&context, &CleanOptions { objects: true, ..CleanOptions::default() }).unwrap();
+        assert!(!target.join("output").exists());
+        assert!(target.join("logs/0001_2024-05-02_140311/build.log").exists());
+        assert!(folder.join("projet/src/main.c").exists());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn a_target_containing_the_project_is_refused() {
+        let folder: PathBuf = test_folder("contains");
+        // La cible du projet est alors le dossier du projet lui-même
+        let context: BuildContext = context(&folder, &folder);
+
+        let result = clean_target(&context, &CleanOptions::all());
+        assert!(matches!(result, Err(BuildError::Config(_))));
+        assert!(folder.join("projet/src/main.c").exists());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_folder_leading_outside_the_target_is_refused() {
+        let folder: PathBuf = test_folder("symlink");
+        let context: BuildContext = context(&folder, &folder.join("build"));
+        let target: PathBuf = PathBuf::from(&context.target_path);
+        write(&folder.join("ailleurs/important.txt"), "à garder");
+        fs::create_dir_all(&target).unwrap();
+        std::os::unix::fs::symlink(folder.join("ailleurs"), target.join("logs")).unwrap();
+
+        let result = clean_target(&context, &CleanOptions { logs: true, ..CleanOptions::default() });
+        assert!(matches!(result, Err(BuildError::Config(_))));
+        assert!(folder.join("ailleurs/important.txt").exists());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_inside_a_folder_are_removed_without_being_followed() {
+        let folder: PathBuf = test_folder("inner-symlink");
+        let context: BuildContext = context(&folder, &folder.join("build"));
+        let target: PathBuf = PathBuf::from(&context.target_path);
+        write(&folder.join("ailleurs/important.txt"), "à garder");
+        fs::create_dir_all(target.join("dll")).unwrap();
+        std::os::unix::fs::symlink(folder.join("ailleurs"), target.join("dll/lien")).unwrap();
+
+        let report: CleanReport = clean_target(&context, &CleanOptions { libraries: true, ..CleanOptions::default() }).unwrap();
+        assert_eq!(report.files, [target.join("dll/lien")]);
+        assert!(folder.join("ailleurs/important.txt").exists());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn recent_files_are_kept_with_older_than() {
+        let folder: PathBuf = test_folder("older");
+        let context: BuildContext = context(&folder, &folder.join("build"));
+        let target: PathBuf = PathBuf::from(&context.target_path);
+        write(&target.join("output/debug/ancien.o"), "objet");
+        write(&target.join("output/debug/recent.o"), "objet");
+        let old: SystemTime = SystemTime::now() - Duration::from_secs(3 * 24 * 60 * 60);
+        fs::File::options().write(true).open(target.join("output/debug/ancien.o")).unwrap().set_modified(old).unwrap();
+
+        let options = CleanOptions { objects: true, older_than: parse_age("2d"), ..CleanOptions::default() };
+        let report: CleanReport = clean_target(&context, &options).unwrap();
+        assert_eq!(report.files, [target.join("output/debug/ancien.o")]);
+        assert!(target.join("output/debug/recent.o").exists());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,428 @@
+//! Analyse de la ligne de commande : sous-commandes, options et textes d'aide.
+//!
+//! ```text
+//! main [options] <commande> [arguments] [-- arguments de l'exécutable]
+//! ```
+
+use compilerc::clean::{CleanOptions, parse_age};
+use compilerc::console::{MessageFormat, Verbosity};
+use compilerc::init::ProjectTemplate;
+use compilerc::messages::{self, Language};
+use compilerc::{BuildError, tr};
+
+/// Dossier du projet par défaut : le dossier courant.
+pub const DEFAULT_PROJECT_PATH: &str = ".";
+/// Dossier cible par défaut.
+pub const DEFAULT_TARGET_PATH: &str = "./build";
+
+/// Sous-commande demandée.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    Build,
+    Run,
+    Test,
+    Clean(CleanOptions),
+    Check,
+    Init { template: ProjectTemplate },
+    Export { destination: String },
+    History,
+    Diff { old: u32, new: u32 },
+    Restore { id: u32 },
+    Cache { action: String },
+    Logs { action: String, run: Option<u32> },
+    Stats { compared: Option<usize> },
+    Worker { address: Option<String> },
+    Help { command: Option<String> },
+    Version,
+}
+
+/// Ligne de commande analysée.
+#[derive(Debug, Clone)]
+pub struct Cli {
+    pub command: CliCommand,
+    pub project_path: String,
+    pub target_path: String,
+    /// Profil imposé par `--profile`, prioritaire sur `settings.txt`.
+    pub profile: Option<String>,
+    pub no_cache: bool,
+    /// Affiche le rapport des durées du build.
+    pub timings: bool,
+    /// Compile avec `-ftime-report` et cumule les phases de gcc.
+    pub time_report: bool,
+    /// Niveau de détail imposé par `--quiet` ou `--verbose`.
+    pub verbosity: Option<Verbosity>,
+    /// Couleurs imposées par `--color` : `auto`, `always` ou `never`.
+    pub color: Option<String>,
+    /// Format de la sortie imposé par `--message-format`.
+    pub message_format: Option<MessageFormat>,
+    /// Langue des messages imposée par `--language`, prioritaire sur `settings.txt`.
+    pub language: Option<Language>,
+    /// Arguments placés après `--`, transmis à l'exécutable.
+    pub program_args: Vec<String>,
+}
+
+/// Description d'une sous-commande pour l'aide.
+struct CommandHelp {
+    name: &'static str,
+    /// Clé du catalogue de messages donnant la syntaxe de la commande.
+    usage: &'static str,
+    /// Clé du catalogue de messages décrivant la commande.
+    summary: &'static str,
+}
+
+const COMMANDS: [CommandHelp; 14] = [
+    CommandHelp { name: "build", usage: "help.build_usage", summary: "help.build" },
+    CommandHelp { name: "run", usage: "help.run_usage", summary: "help.run" },
+    CommandHelp { name: "test", usage: "help.test_usage", summary: "help.test" },
+    CommandHelp { name: "clean", usage: "help.clean_usage", summary: "help.clean" },
+    CommandHelp { name: "check", usage: "help.check_usage", summary: "help.check" },
+    CommandHelp { name: "init", usage: "help.init_usage", summary: "help.init" },
+    CommandHelp { name: "export", usage: "help.export_usage", summary: "help.export" },
+    CommandHelp { name: "history", usage: "help.history_usage", summary: "help.history" },
+    CommandHelp { name: "diff", usage: "help.diff_usage", summary: "help.diff" },
+    CommandHelp { name: "restore", usage: "help.restore_usage", summary: "help.restore" },
+    CommandHelp { name: "cache", usage: "help.cache_usage", summary: "help.cache" },
+    CommandHelp { name: "logs", usage: "help.logs_usage", summary: "help.logs" },
+    CommandHelp { name: "stats", usage: "help.stats_usage", summary: "help.stats" },
+    CommandHelp { name: "worker", usage: "help.worker_usage", summary: "help.worker" },
+];
+
+impl Cli {
+    /// Analyse les arguments, sans le nom du programme.
+    pub fn parse(args: &[String]) -> Result<Cli, BuildError> {
+        let mut project_path: Option<String> = None;
+        let mut target_path: Option<String> = None;
+        let mut profile: Option<String> = None;
+        let mut no_cache: bool = false;
+        let mut timings: bool = false;
+        let mut time_report: bool = false;
+        let mut verbosity: Option<Verbosity> = None;
+        let mut color: Option<String> = None;
+        let mut message_format: Option<MessageFormat> = None;
+        let mut language: Option<Language> = None;
+        let mut help: bool = false;
+        let mut version: bool = false;
+        let mut clean: CleanOptions = CleanOptions::default();
+        let mut clean_flag: Option<String> = None;
+        let mut positionals: Vec<String> = Vec::new();
+        let mut program_args: Vec<String> = Vec::new();
+
+        let mut index: usize = 0;
+        while index < args.len() {
+            let argument: &str = &args[index];
+            index += 1;
+
+            // `--option=valeur` est équivalent à `--option valeur`
+            let (flag, inline_value): (&str, Option<String>) = match argument.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (argument, None),
+            };
+
+            let mut value = |name: &str| -> Result<String, BuildError> {
+                if let Some(value) = inline_value.clone() {
+                    return Ok(value);
+                }
+                let value: Option<&String> = args.get(index);
+                index += 1;
+                value
+                    .filter(|value| !value.starts_with('-'))
+                    .cloned()
+                    .ok_or_else(|| BuildError::Usage(tr!("cli.missing_value", name)))
+            };
+
+            match flag {
+                "--" => {
+                    program_args = args[index..].to_vec();
+                    break;
+                }
+                "-p" | "--project" => project_path = Some(value("--project")?),
+                "-t" | "--target" => target_path = Some(value("--target")?),
+                "--profile" => profile = Some(value("--profile")?),
+                "--no-cache" => no_cache = true,
+                "--timings" => timings = true,
+                "--time-report" => time_report = true,
+                "-q" | "--quiet" | "-v" | "--verbose" => {
+                    let requested: Verbosity = if matches!(flag, "-q" | "--quiet") { Verbosity::Quiet } else { Verbosity::Verbose };
+                    if verbosity.is_some_and(|verbosity| verbosity != requested) {
+                        return Err(BuildError::Usage(tr!("cli.quiet_and_verbose")));
+                    }
+                    verbosity = Some(requested);
+                }
+                "--color" => {
+                    let mode: String = value("--color")?;
+                    if !matches!(mode.as_str(), "auto" | "always" | "never") {
+                        return Err(BuildError::Usage(tr!("cli.unknown_color", mode)));
+                    }
+                    color = Some(mode);
+                }
+                "--message-format" => {
+                    let format: String = value("--message-format")?;
+                    message_format = Some(MessageFormat::from_name(&format).ok_or_else(|| {
+                        BuildError::Usage(tr!("cli.unknown_message_format", format, MessageFormat::NAMES.join(", ")))
+                    })?);
+                }
+                "--language" => {
+                    let code: String = value("--language")?;
+                    language = Some(Language::from_name(&code).ok_or_else(|| {
+                        BuildError::Usage(tr!("cli.unknown_language", code, Language::NAMES.join(", ")))
+                    })?);
+                }
+                "-h" | "--help" => help = true,
+                "-V" | "--version" => version = true,
+                "--objects" | "--executables" | "--sources" | "--libraries" | "--logs" | "--all" | "-n" | "--dry-run" => {
+                    match flag {
+                        "--objects" => clean.objects = true,
+                        "--executables" => clean.executables = true,
+                        "--sources" => clean.sources = true,
+                        "--libraries" => clean.libraries = true,
+                        "--logs" => clean.logs = true,
+                        "--all" => clean = CleanOptions { older_than: clean.older_than, dry_run: clean.dry_run, ..CleanOptions::all() },
+                        _ => clean.dry_run = true,
+                    }
+                    clean_flag = Some(flag.to_string());
+                }
+                "--older-than" => {
+                    let age: String = value("--older-than")?;
+                    clean.older_than =
+                        Some(parse_age(&age).ok_or_else(|| BuildError::Usage(tr!("cli.invalid_age", age)))?);
+                    clean_flag = Some(flag.to_string());
+                }
+                _ if flag.starts_with('-') && flag.len() > 1 => {
+                    return Err(BuildError::Usage(tr!("cli.unknown_option", flag)));
+                }
+                _ => positionals.push(argument.to_string()),
+            }
+        }
+
+        let mut command: CliCommand = if version {
+            CliCommand::Version
+        } else if help {
+            CliCommand::Help { command: positionals.first().cloned() }
+        } else {
+            parse_command(&positionals)?
+        };
+
+        // Les portées de nettoyage ne s'appliquent qu'à `clean`, qui réutilise aussi `--profile`
+        if let CliCommand::Clean(options) = &mut command {
+            *options = CleanOptions { profile: profile.clone(), ..clean };
+        } else if let Some(flag) = clean_flag.filter(|_| !matches!(command, CliCommand::Help { .. } | CliCommand::Version)) {
+            return Err(BuildError::Usage(tr!("cli.clean_only", flag)));
+        }
+
+        if !program_args.is_empty() && command != CliCommand::Run {
+            return Err(BuildError::Usage(tr!("cli.run_only")));
+        }
+
+        Ok(Cli {
+            command,
+            project_path: project_path.unwrap_or_else(|| DEFAULT_PROJECT_PATH.to_string()),
+            target_path: target_path.unwrap_or_else(|| DEFAULT_TARGET_PATH.to_string()),
+            profile,
+            no_cache,
+            timings,
+            time_report,
+            verbosity,
+            color,
+            message_format,
+            language,
+            program_args,
+        })
+    }
+}
+
+/// Construit la sous-commande à partir des arguments positionnels.
+fn parse_command(positionals: &[String]) -> Result<CliCommand, BuildError> {
+    let name: &str = match positionals.first() {
+        Some(name) => name,
+        None => return Err(BuildError::Usage(tr!("cli.missing_command", help_text(None)))),
+    };
+    let parameters: &[String] = &positionals[1..];
+
+    let expect = |count: usize| -> Result<(), BuildError> {
+        if parameters.len() > count {
+            return Err(BuildError::Usage(tr!("cli.unexpected_argument", parameters[count], name)));
+        }
+        if parameters.len() < count {
+            return Err(BuildError::Usage(tr!("cli.missing_arguments", usage(name))));
+        }
+        Ok(())
+    };
+    let snapshot_id = |index: usize| -> Result<u32, BuildError> {
+        parameters[index]
+            .parse::<u32>()
+            .map_err(|_| BuildError::Usage(tr!("cli.invalid_snapshot", parameters[index])))
+    };
+
+    let command: CliCommand = match name {
+        "build" => expect(0).map(|_| CliCommand::Build)?,
+        "run" => expect(0).map(|_| CliCommand::Run)?,
+        "test" => expect(0).map(|_| CliCommand::Test)?,
+        "clean" => expect(0).map(|_| CliCommand::Clean(CleanOptions::default()))?,
+        "check" => expect(0).map(|_| CliCommand::Check)?,
+        "init" => {
+            expect(parameters.len().min(1))?;
+            let template: ProjectTemplate = match parameters.first() {
+                Some(name) => ProjectTemplate::from_name(name).ok_or_else(|| {
+                    BuildError::Usage(tr!("cli.unknown_template", name, ProjectTemplate::NAMES.join(", ")))
+                })?,
+                None => ProjectTemplate::default(),
+            };
+            CliCommand::Init { template }
+        }
+        "export" => expect(1).map(|_| CliCommand::Export { destination: parameters[0].clone() })?,
+        "history" => expect(0).map(|_| CliCommand::History)?,
+        "diff" => {
+            expect(2)?;
+            CliCommand::Diff { old: snapshot_id(0)?, new: snapshot_id(1)? }
+        }
+        "restore" => {
+            expect(1)?;
+            CliCommand::Restore { id: snapshot_id(0)? }
+        }
+        "cache" => {
+            expect(parameters.len().min(1))?;
+            let action: &str = parameters.first().map(String::as_str).unwrap_or("stats");
+            if !matches!(action, "stats" | "clear") {
+                return Err(BuildError::Usage(tr!("cli.unknown_cache_action", action)));
+            }
+            CliCommand::Cache { action: action.to_string() }
+        }
+        "logs" => {
+            let action: &str = parameters.first().map(String::as_str).unwrap_or("list");
+            match action {
+                "list" | "last-failure" => expect(parameters.len().min(1))?,
+                "tail" => expect(parameters.len().clamp(1, 2))?,
+                _ => return Err(BuildError::Usage(tr!("cli.unknown_logs_action", action))),
+            }
+            let run: Option<u32> = match parameters.get(1) {
+                Some(run) => Some(run.parse::<u32>().map_err(|_| BuildError::Usage(tr!("cli.invalid_run", run)))?),
+                None => None,
+            };
+            CliCommand::Logs { action: action.to_string(), run }
+        }
+        "stats" => {
+            expect(parameters.len().min(1))?;
+            let compared: Option<usize> = match parameters.first() {
+                Some(count) => Some(count.parse::<usize>().ok().filter(|count| *count > 0).ok_or_else(|| {
+                    BuildError::Usage(tr!("cli.invalid_build_count", count))
+                })?),
+                None => None,
+            };
+            CliCommand::Stats { compared }
+        }
+        "worker" => {
+            expect(parameters.len().min(1))?;
+            CliCommand::Worker { address: parameters.first().cloned() }
+        }
+        "help" => CliCommand::Help { command: parameters.first().cloned() },
+        _ => return Err(BuildError::Usage(tr!("cli.unknown_command", name))),
+    };
+
+    Ok(command)
+}
+
+fn usage(name: &str) -> &'static str {
+    COMMANDS.iter().find(|command| command.name == name).map(|command| messages::text(command.usage)).unwrap_or("")
+}
+
+/// Texte de l'aide générale, ou d'une sous-commande.
+pub fn help_text(command: Option<&str>) -> String {
+    if let Some(help) = command.and_then(|name| COMMANDS.iter().find(|command| command.name == name)) {
+        let mut text: String = format!("{}\n\n{}\n\n", messages::text(help.summary), tr!("help.usage", messages::text(help.usage)));
+        if help.name == "clean" {
+            text.push_str(messages::text("help.clean_options"));
+            text.push_str("\n\n");
+        }
+        text.push_str(messages::text("help.options"));
+        return text;
+    }
+
+    let usage: String = tr!("help.usage", messages::text("help.command_placeholder"));
+    let mut text: String = format!("CompilerC {}\n\n{}\n\n{}\n", version(), usage, messages::text("help.commands"));
+    for command in &COMMANDS {
+        text.push_str(&format!("  {:<22}{}\n", messages::text(command.usage), messages::text(command.summary)));
+    }
+    text.push('\n');
+    text.push_str(messages::text("help.options"));
+    text
+}
+
+/// Version du programme.
+pub fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn parse(line: &str) -> Result<Cli, BuildError> {
+        let args: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        Cli::parse(&args)
+    }
+
+    fn is_usage_error(line: &str) -> bool {
+        matches!(parse(line), Err(BuildError::Usage(_)))
+    }
+
+    #[test]
+    fn options_accept_a_separate_or_inline_value() {
+        let cli: Cli = parse("-p demo --target=out --profile release build").unwrap();
+        assert_eq!(cli.command, CliCommand::Build);
+        assert_eq!((cli.project_path.as_str(), cli.target_path.as_str()), ("demo", "out"));
+        assert_eq!(cli.profile.as_deref(), Some("release"));
+
+        let cli: Cli = parse("run").unwrap();
+        assert_eq!((cli.project_path.as_str(), cli.target_path.as_str()), (DEFAULT_PROJECT_PATH, DEFAULT_TARGET_PATH));
+    }
+
+    #[test]
+    fn unknown_options_and_commands_are_refused() {
+        assert!(is_usage_error("build --frobnicate"));
+        assert!(is_usage_error("-x build"));
+        assert!(is_usage_error("frobnicate"));
+        assert!(is_usage_error(""));
+    }
+
+    #[test]
+    fn a_missing_option_value_is_refused() {
+        assert!(is_usage_error("build -p"));
+        assert!(is_usage_error("build --target"));
+        // Une valeur ne commence pas par un tiret : `--verbose` n'est pas pris pour le dossier du projet
+        assert!(is_usage_error("-p --verbose build"));
+        assert!(is_usage_error("build --color"));
+    }
+
+    #[test]
+    fn invalid_option_values_are_refused() {
+        assert!(is_usage_error("build --color=sometimes"));
+        assert!(is_usage_error("build --message-format=xml"));
+        assert!(is_usage_error("build --language=de"));
+        assert!(is_usage_error("clean --older-than=soon"));
+        assert!(is_usage_error("clean --older-than=999999999999999w"));
+        assert!(is_usage_error("build --quiet --verbose"));
+    }
+
+    #[test]
+    fn command_arguments_are_checked() {
+        assert!(is_usage_error("build extra"));
+        assert!(is_usage_error("diff 1"));
+        assert!(is_usage_error("diff 1 two"));
+        assert!(is_usage_error("logs tail 1 2"));
+        assert_eq!(parse("diff 1 2").unwrap().command, CliCommand::Diff { old: 1, new: 2 });
+        assert_eq!(parse("logs tail 3").unwrap().command, CliCommand::Logs { action: "tail".to_string(), run: Some(3) });
+    }
+
+    #[test]
+    fn clean_scopes_and_program_arguments_belong_to_their_command() {
+        let cli: Cli = parse("clean --objects --older-than 2d -n").unwrap();
+        let CliCommand::Clean(options) = cli.command else { panic!("clean attendu") };
+        assert!(options.objects && options.dry_run && !options.logs);
+        assert_eq!(options.older_than, Some(Duration::from_secs(2 * 24 * 60 * 60)));
+
+        assert!(is_usage_error("build --objects"));
+        assert_eq!(parse("run -- -x --y").unwrap().program_args, vec!["-x", "--y"]);
+        assert!(is_usage_error("build -- -x"));
+    }
+}
@@ -0,0 +1,344 @@
+//! Affichage du build dans le terminal : progression, couleurs et niveau de détail.
+//!
+//! La progression suit les événements du build : `[12/87] Compilation de src/net/socket.c`.
+//! Dans un terminal, une ligne d'état avec une barre de progression est redessinée sur place et les
+//! avertissements et erreurs sont colorés ; hors terminal (redirection, intégration continue), chaque
+//! étape est écrite sur sa propre ligne, sans couleur.
+//!
+//! Configuration dans `settings.txt` ou sur la ligne de commande :
+//!
+//! ```text
+//! VERBOSITY : quiet       # -q : seulement les diagnostics et les erreurs ; verbose (-v) : commandes complètes
+//! COLOR : auto            # --color : auto (par défaut, désactivé par NO_COLOR), always ou never
+//! MESSAGE_FORMAT : json   # --message-format : human (par défaut), short ou json
+//! ```
+//!
+//! Avec `MESSAGE_FORMAT : json`, la sortie standard ne reçoit que les événements du build, un objet JSON
+//! par ligne (comme le journal `events.jsonl`) ; les messages destinés à l'utilisateur passent sur la
+//! sortie d'erreur. Avec `MESSAGE_FORMAT : short`, les diagnostics de gcc sont réduits à une ligne
+//! `fichier:ligne:colonne: gravité: message` par diagnostic, le chemin étant relatif à la racine du projet,
+//! et la sortie n'a ni ligne d'état ni couleurs par défaut, pour les éditeurs qui l'analysent.
+
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::events::BuildEvent;
+use crate::settings::Settings;
+use crate::timings::format_duration;
+use crate::tr;
+
+/// Largeur de la barre de progression, en caractères.
+const BAR_WIDTH: usize = 24;
+/// Largeur du terminal lorsque `COLUMNS` n'est pas défini.
+const DEFAULT_COLUMNS: usize = 80;
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const GREEN: &str = "\x1b[1;32m";
+const CYAN: &str = "\x1b[1;36m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Niveau de détail de l'affichage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Diagnostics, erreurs et résultat en échec uniquement.
+    Quiet,
+    #[default]
+    Normal,
+    /// Ajoute les lignes de commande complètes.
+    Verbose,
+}
+
+impl Verbosity {
+    /// Noms acceptés par `VERBOSITY`.
+    pub const NAMES: [&'static str; 3] = ["quiet", "normal", "verbose"];
+
+    pub fn from_name(name: &str) -> Option<Verbosity> {
+        match name.trim().to_lowercase().as_str() {
+            "quiet" => Some(Verbosity::Quiet),
+            "normal" => Some(Verbosity::Normal),
+            "verbose" => Some(Verbosity::Verbose),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        Verbosity::NAMES[*self as usize]
+    }
+}
+
+/// Format de la sortie standard.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Messages, progression et diagnostics lisibles.
+    #[default]
+    Human,
+    /// Diagnostics au format `fichier:ligne:colonne: gravité: message`, chemins relatifs au projet,
+    /// seuls sur la sortie standard ; les autres messages vont sur la sortie d'erreur.
+    Short,
+    /// Un objet JSON par événement du build, pour les éditeurs et l'intégration continue.
+    Json,
+}
+
+impl MessageFormat {
+    /// Noms acceptés par `MESSAGE_FORMAT`.
+    pub const NAMES: [&'static str; 3] = ["human", "short", "json"];
+
+    pub fn from_name(name: &str) -> Option<MessageFormat> {
+        match name.trim().to_lowercase().as_str() {
+            "human" => Some(MessageFormat::Human),
+            "short" => Some(MessageFormat::Short),
+            "json" => Some(MessageFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        MessageFormat::NAMES[*self as usize]
+    }
+}
+
+/// Avancement de la compilation.
+#[derive(Debug, Default)]
+struct Progress {
+    total: usize,
+    started: usize,
+    finished: usize,
+    /// Texte de la ligne d'état, `None` si aucune n'est affichée.
+    status: Option<String>,
+}
+
+/// Sortie du build dans le terminal, partagée entre les threads de compilation.
+#[derive(Debug, Clone)]
+pub struct Console {
+    verbosity: Verbosity,
+    format: MessageFormat,
+    colors: bool,
+    /// Ligne d'état redessinée sur place : la sortie standard est un terminal.
+    interactive: bool,
+    progress: Arc<Mutex<Progress>>,
+}
+
+impl Default for Console {
+    fn default() -> Console {
+        Console::from_settings(&Settings::default())
+    }
+}
+
+impl Console {
+    /// Lit `VERBOSITY`, `COLOR` et `MESSAGE_FORMAT` ; les couleurs et la ligne d'état exigent un terminal.
+    pub fn from_settings(settings: &Settings) -> Console {
+        let verbosity: Verbosity = settings.get("VERBOSITY").and_then(Verbosity::from_name).unwrap_or_default();
+        let format: MessageFormat = settings.get("MESSAGE_FORMAT").and_then(MessageFormat::from_name).unwrap_or_default();
+        // La ligne d'état ne doit pas se mêler aux formats analysés par d'autres outils
+        let interactive: bool = format == MessageFormat::Human && io::stdout().is_terminal();
+        let colors: bool = match settings.get("COLOR").map(str::to_lowercase).as_deref() {
+            Some("always") => true,
+            Some("never") => false,
+            _ => interactive && io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none(),
+        };
+
+        Console { verbosity, format, colors, interactive, progress: Arc::default() }
+    }
+
+    pub fn verbosity(&self) -> Verbosity {
+        self.verbosity
+    }
+
+    pub fn format(&self) -> MessageFormat {
+        self.format
+    }
+
+    /// Message d'information, masqué en mode silencieux.
+    pub fn info(&self, message: &str) {
+        if self.verbosity >= Verbosity::Normal {
+            self.print_line(message, false);
+        }
+    }
+
+    /// Détail affiché uniquement en mode détaillé.
+    pub fn detail(&self, message: &str) {
+        if self.verbosity >= Verbosity::Verbose {
+            let message: String = self.paint(DIM, message);
+            self.print_line(&message, false);
+        }
+    }
+
+    /// Résultat réussi d'une commande, en vert.
+    pub fn success(&self, message: &str) {
+        if self.verbosity >= Verbosity::Normal {
+            let message: String = self.paint(GREEN, message);
+            self.print_line(&message, false);
+        }
+    }
+
+    /// Erreur, toujours affichée, en rouge sur la sortie d'erreur.
+    pub fn error(&self, message: &str) {
+        let message: String = self.paint(RED, message);
+        self.print_line(&message, true);
+    }
+
+    /// Sortie du compilateur ou d'une commande, toujours affichée, gravités colorées.
+    pub fn diagnostics(&self, text: &str) {
+        let text: &str = text.trim_end();
+        if text.is_empty() {
+            return;
+        }
+
+        let lines: Vec<String> = text.lines().map(|line| self.color_diagnostic(line)).collect();
+        self.print_line(&lines.join("\n"), true);
+    }
+
+    /// Diagnostics canoniques du format `short`, une ligne chacun, seuls sur la sortie standard.
+    pub fn canonical_diagnostics(&self, text: &str) {
+        let text: &str = text.trim_end();
+        if text.is_empty() {
+            return;
+        }
+
+        let lines: Vec<String> = text.lines().map(|line| self.color_diagnostic(line)).collect();
+        let _progress = self.lock();
+        println!("{}", lines.join("\n"));
+    }
+
+    /// Met à jour la progression à partir d'un événement du build.
+    pub fn event(&self, event: &BuildEvent, relative_path: impl Fn(&Path) -> PathBuf) {
+        if self.format == MessageFormat::Json {
+            println!("{}", event.to_json());
+            return;
+        }
+        if self.verbosity == Verbosity::Quiet {
+            return;
+        }
+
+        match event {
+            BuildEvent::CompilePlanned { sources } => {
+                let mut progress = self.lock();
+                *progress = Progress { total: *sources, ..Progress::default() };
+            }
+            BuildEvent::CompileStarted { source } => {
+                let mut progress = self.lock();
+                progress.started += 1;
+                let message: String =
+                    tr!("console.compiling", progress.started, progress.total.max(progress.started), relative_path(source).display());
+                self.show_step(&mut progress, message);
+            }
+            BuildEvent::CompileFinished { .. } | BuildEvent::CompileFailed { .. } => {
+                let mut progress = self.lock();
+                progress.finished += 1;
+                if let Some(status) = progress.status.clone() {
+                    self.show_step(&mut progress, status);
+                }
+            }
+            BuildEvent::LinkStarted { objects } => {
+                let mut progress = self.lock();
+                self.show_step(&mut progress, tr!("console.linking", objects));
+            }
+            BuildEvent::CommandFinished { program, args, duration, .. } if self.verbosity >= Verbosity::Verbose => {
+                self.detail(&format!("  {} {} ({})", program, args.join(" "), format_duration(*duration)));
+            }
+            BuildEvent::LinkFinished { .. } | BuildEvent::RunStarted { .. } | BuildEvent::Finished { .. } => {
+                let mut progress = self.lock();
+                self.clear_status(&mut progress);
+            }
+            _ => {}
+        }
+    }
+
+    /// Étape de la progression : ligne d'état dans un terminal, ligne simple sinon.
+    fn show_step(&self, progress: &mut Progress, message: String) {
+        if !self.interactive {
+            // Hors terminal, seules les nouvelles étapes sont écrites
+            if progress.status.as_deref() != Some(message.as_str()) {
+                self.print_unlocked(&message, false);
+            }
+            progress.status = Some(message);
+            return;
+        }
+
+        progress.status = Some(message);
+        self.draw_status(progress);
+    }
+
+    /// Écrit une ligne en effaçant puis en redessinant la ligne d'état ; en JSON et au format `short`,
+    /// toujours sur la sortie d'erreur, la sortie standard étant réservée au format analysé.
+    fn print_line(&self, message: &str, to_stderr: bool) {
+        let mut progress = self.lock();
+        let redraw: bool = self.interactive && progress.status.is_some();
+
+        if redraw {
+            print!("{}", CLEAR_LINE);
+            let _ = io::stdout().flush();
+        }
+
+        self.print_unlocked(message, to_stderr);
+
+        if redraw {
+            self.draw_status(&mut progress);
+        }
+    }
+
+    /// Écrit une ligne, la progression étant déjà verrouillée par l'appelant.
+    fn print_unlocked(&self, message: &str, to_stderr: bool) {
+        if to_stderr || self.format != MessageFormat::Human {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    }
+
+    fn draw_status(&self, progress: &mut Progress) {
+        let Some(status) = &progress.status else { return };
+
+        let total: usize = progress.total.max(1);
+        let filled: usize = (progress.finished.min(total) * BAR_WIDTH) / total;
+        let bar: String = format!("[{}{}]", "=".repeat(filled), " ".repeat(BAR_WIDTH - filled));
+        let columns: usize = env::var("COLUMNS").ok().and_then(|columns| columns.parse().ok()).unwrap_or(DEFAULT_COLUMNS);
+
+        // La ligne ne doit pas passer à la ligne suivante, sinon elle ne serait plus effacée
+        let line: String = format!("{} {}", bar, status).chars().take(columns.saturating_sub(1)).collect();
+        let bar_length: usize = bar.chars().count().min(line.chars().count());
+        let (bar, text): (String, String) = (line.chars().take(bar_length).collect(), line.chars().skip(bar_length).collect());
+
+        print!("{}{}{}", CLEAR_LINE, self.paint(CYAN, &bar), text);
+        let _ = io::stdout().flush();
+    }
+
+    fn clear_status(&self, progress: &mut Progress) {
+        if progress.status.take().is_some() && self.interactive {
+            print!("{}", CLEAR_LINE);
+            let _ = io::stdout().flush();
+        }
+    }
+
+    /// Colore la gravité d'une ligne `fichier:ligne:colonne: gravité: message`.
+    fn color_diagnostic(&self, line: &str) -> String {
+        if !self.colors {
+            return line.to_string();
+        }
+
+        for (severity, color) in [("fatal error:", RED), ("error:", RED), ("warning:", YELLOW), ("note:", CYAN)] {
+            if let Some(start) = line.find(severity) {
+                let end: usize = start + severity.len();
+                return format!("{}{}{}", &line[..start], self.paint(color, &line[start..end]), &line[end..]);
+            }
+        }
+        line.to_string()
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.colors {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Progress> {
+        self.progress.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
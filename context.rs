@@ -0,0 +1,261 @@
+//! Contexte d'un build : chemins du projet et de la cible, fichier de log et configuration.
+//!
+//! Un [`BuildContext`] est créé pour chaque projet et transmis à toutes les étapes du build.
+//! Plusieurs projets peuvent ainsi être compilés dans le même processus, y compris en parallèle.
+
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use chrono::Local;
+
+use crate::console::{Console, MessageFormat};
+use crate::diagnostics::canonical_diagnostics;
+use crate::events::{BuildEvent, EventHandler};
+use crate::hooks::HookEnvironment;
+use crate::logs::{JOURNAL_FILE_NAME, LOG_FILE_NAME, next_run_id, reserve_run_directory, run_directory_name};
+use crate::messages::{Language, LanguageScope};
+use crate::report::ReportRecorder;
+use crate::settings::Settings;
+use crate::tr;
+
+/// État propre à la compilation d'un projet.
+#[derive(Debug, Clone)]
+pub struct BuildContext {
+    pub project_name: String,
+    pub project_path: String,
+    /// Dossier cible commun à tous les projets.
+    pub target_root: String,
+    /// Dossier cible du projet : `<cible>/<projet>`.
+    pub target_path: String,
+    /// Dossier des logs de toutes les exécutions : `<cible>/<projet>/logs`.
+    pub logs_path: String,
+    pub log_file: String,
+    /// Journal des événements au format JSON Lines, dans le dossier de l'exécution.
+    pub journal_file: String,
+    pub formatted_time: String,
+    pub settings: Settings,
+    pub events: EventHandler,
+    /// Événements conservés pour le rapport HTML de l'exécution.
+    pub report: ReportRecorder,
+    /// Affichage dans le terminal, créé au premier message d'après la configuration.
+    console: OnceLock<Console>,
+    /// Identifiant et dossier des logs de l'exécution en cours, réservés à la première écriture.
+    run: OnceLock<(u32, String)>,
+}
+
+impl BuildContext {
+    /// Prépare le contexte d'un projet et charge son `settings.txt`.
+    pub fn new(project_path: &str, target_root: &str) -> BuildContext {
+        // `.` ou `..` n'ont pas de nom : le nom du projet est alors celui du dossier réel
+        let project_name: String = Path::new(project_path)
+            .file_name()
+            .map(PathBuf::from)
+            .or_else(|| fs::canonicalize(project_path).ok()?.file_name().map(PathBuf::from))
+            .and_then(|file_name| file_name.to_str().map(str::to_string))
+            .unwrap_or_default();
+
+        let target_path: String = format!("{}/{}", target_root, project_name);
+        let formatted_time: String = Local::now().format("%Y-%m-%d").to_string();
+        let logs_path: String = format!("{}/logs", target_path);
+        let settings: Settings = Settings::load(project_path);
+
+        BuildContext {
+            log_file: LOG_FILE_NAME.to_string(),
+            journal_file: JOURNAL_FILE_NAME.to_string(),
+            logs_path,
+            settings,
+            events: EventHandler::default(),
+            report: ReportRecorder::default(),
+            console: OnceLock::new(),
+            run: OnceLock::new(),
+            project_name,
+            project_path: project_path.to_string(),
+            target_root: target_root.to_string(),
+            target_path,
+            formatted_time,
+        }
+    }
+
+    /// Affichage dans le terminal ; `VERBOSITY` et `COLOR` sont lus au premier appel.
+    pub fn console(&self) -> &Console {
+        self.console.get_or_init(|| Console::from_settings(&self.settings))
+    }
+
+    /// Identifiant de l'exécution en cours ; le réserve s'il ne l'est pas encore.
+    pub fn run_id(&self) -> u32 {
+        self.run().0
+    }
+
+    /// Dossier des logs de l'exécution en cours ; le réserve s'il ne l'est pas encore.
+    pub fn log_path(&self) -> &str {
+        &self.run().1
+    }
+
+    /// Indique si l'exécution a déjà réservé son dossier, c'est-à-dire écrit dans ses logs.
+    pub fn run_started(&self) -> bool {
+        self.run.get().is_some()
+    }
+
+    /// Réserve l'identifiant et le dossier de l'exécution au premier appel : deux builds lancés en même temps
+    /// n'obtiennent jamais le même dossier.
+    fn run(&self) -> &(u32, String) {
+        self.run.get_or_init(|| {
+            let started: String = Local::now().format("%Y-%m-%d_%H%M%S").to_string();
+            let logs_path: &Path = Path::new(&self.logs_path);
+
+            match reserve_run_directory(logs_path, &started) {
+                Ok(run) => run,
+                Err(err) => {
+                    eprintln!("{}", tr!("context.run_reservation_failed", err));
+                    let run_id: u32 = next_run_id(logs_path);
+                    (run_id, format!("{}/{}", self.logs_path, run_directory_name(run_id, &started)))
+                }
+            }
+        })
+    }
+
+    /// Langue des messages déclarée par la clé `LANGUAGE`, sinon celle de l'environnement.
+    pub fn language(&self) -> Language {
+        self.settings.get("LANGUAGE").and_then(Language::from_name).or_else(Language::from_environment).unwrap_or_default()
+    }
+
+    /// Passe le thread courant dans la langue du projet jusqu'à la fin de la portée renvoyée.
+    pub fn language_scope(&self) -> LanguageScope {
+        LanguageScope::enter(self.language())
+    }
+
+    /// Profil de compilation déclaré par la clé `PROFILE` (par défaut `debug`).
+    pub fn profile(&self) -> String {
+        self.settings.get("PROFILE").unwrap_or("debug").to_string()
+    }
+
+    /// Dossier recevant les fichiers objets du profil : `output/<profil>`.
+    pub fn output_path(&self) -> String {
+        format!("{}/output/{}", self.target_path, self.profile())
+    }
+
+    /// Chemin de l'exécutable produit par l'édition de liens : `executable/<profil>/main`.
+    pub fn executable_path(&self) -> String {
+        format!("{}/executable/{}/main{}", self.target_path, self.profile(), env::consts::EXE_SUFFIX)
+    }
+
+    /// Dossier recevant les sources générées pour les ressources intégrées.
+    pub fn resources_path(&self) -> String {
+        format!("{}/resources", self.target_path)
+    }
+
+    /// Chemin d'un fichier relatif au projet, ou au dossier cible pour les fichiers générés.
+    pub fn relative_path(&self, file: &Path) -> PathBuf {
+        if let Ok(relative_path) = file.strip_prefix(&self.project_path) {
+            return relative_path.to_path_buf();
+        }
+        if let Ok(relative_path) = file.strip_prefix(&self.target_path) {
+            return relative_path.to_path_buf();
+        }
+        file.file_name().map(PathBuf::from).unwrap_or_default()
+    }
+
+    /// Chemin d'un fichier cité par le compilateur, relatif à la racine du projet ; une copie du dossier
+    /// `source` de la cible renvoie au fichier original. Le chemin est inchangé s'il est hors du projet.
+    pub fn project_relative_path(&self, file: &Path) -> PathBuf {
+        let source_copies: PathBuf = Path::new(&self.target_path).join("source");
+        for root in [source_copies.as_path(), Path::new(&self.project_path)] {
+            if let Ok(relative_path) = file.strip_prefix(root) {
+                return relative_path.to_path_buf();
+            }
+            // Chemin absolu, ou écrit autrement que le dossier du projet
+            if let (Ok(file), Ok(root)) = (fs::canonicalize(file), fs::canonicalize(root)) {
+                if let Ok(relative_path) = file.strip_prefix(root) {
+                    return relative_path.to_path_buf();
+                }
+            }
+        }
+        file.to_path_buf()
+    }
+
+    /// Fichier objet produit pour une source : `output/<profil>/<chemin relatif>.o`.
+    pub fn object_path(&self, c_file: &Path) -> PathBuf {
+        let mut output_file: PathBuf = Path::new(&self.output_path()).join(self.relative_path(c_file));
+        output_file.set_extension("o");
+        output_file
+    }
+
+    /// Variables transmises aux hooks, avec des chemins absolus : les hooks s'exécutent depuis le dossier du projet.
+    pub fn hook_environment(&self) -> HookEnvironment {
+        HookEnvironment {
+            project_name: self.project_name.clone(),
+            project_path: absolute_path(&self.project_path),
+            target_path: absolute_path(&self.target_path),
+            log_path: absolute_path(self.log_path()),
+            profile: self.profile(),
+            output_path: absolute_path(&self.output_path()),
+            executable_path: absolute_path(&self.executable_path()),
+        }
+    }
+
+    /// Écrit un événement dans le journal JSON, le conserve pour le rapport, l'affiche puis le transmet au gestionnaire du build.
+    pub fn emit(&self, event: BuildEvent) {
+        // Une ligne complète par écriture : les threads de compilation ne mélangent pas leurs lignes
+        let line: String = format!("{}\n", event.to_json());
+        match self.open_log_file(&self.journal_file) {
+            Ok(mut file) => {
+                if let Err(err) = file.write_all(line.as_bytes()) {
+                    eprintln!("{}", tr!("context.journal_write_failed", err));
+                }
+            }
+            Err(err) => eprintln!("{}", tr!("context.journal_open_failed", err)),
+        }
+
+        self.report.record(&event);
+        self.console().event(&event, |path| self.relative_path(path));
+        self.events.emit(event);
+    }
+
+    /// Affiche une sortie du compilateur ; au format `short`, seuls ses diagnostics réécrits sous leur forme canonique
+    /// vont sur la sortie standard, les autres lignes sur la sortie d'erreur.
+    pub fn diagnostics(&self, text: &str) {
+        let console: &Console = self.console();
+        if console.format() == MessageFormat::Short {
+            let (canonical, others) =
+                canonical_diagnostics(text, |file| self.project_relative_path(Path::new(file)).to_string_lossy().to_string());
+            console.canonical_diagnostics(&canonical);
+            console.diagnostics(&others);
+        } else {
+            console.diagnostics(text);
+        }
+    }
+
+    /// Ajoute un message au fichier de log du projet.
+    pub fn write_in_logs(&self, log_message: String) {
+        let mut file = match self.open_log_file(&self.log_file) {
+            Ok(f) => f,
+            Err(err) => {
+                eprintln!("{}", tr!("context.log_open_failed", err));
+                return;
+            }
+        };
+
+        if let Err(err) = writeln!(file, "{}", log_message) {
+            eprintln!("{}", tr!("context.log_write_failed", err));
+        }
+    }
+
+    /// Ouvre en ajout un fichier du dossier de l'exécution, en créant le dossier au besoin.
+    fn open_log_file(&self, file_name: &str) -> io::Result<File> {
+        fs::create_dir_all(self.log_path())?;
+        OpenOptions::new().create(true).append(true).open(Path::new(self.log_path()).join(file_name))
+    }
+}
+
+/// Chemin absolu, résolu par le système de fichiers s'il existe déjà (hooks lancés avant le build).
+/// Sous Windows, `canonicalize` donne un chemin `\\?\` que `cmd` n'accepte pas : le chemin est seulement rendu absolu.
+fn absolute_path(path: &str) -> String {
+    let resolved: io::Result<PathBuf> = if cfg!(windows) { Err(io::ErrorKind::Unsupported.into()) } else { fs::canonicalize(path) };
+    resolved
+        .or_else(|_| std::path::absolute(path))
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
@@ -0,0 +1,163 @@
+//! Diagnostics de gcc : découpage des lignes `fichier:ligne:colonne: gravité: message`.
+//!
+//! Les diagnostics découpés sont repris par le rapport HTML et par le flux d'événements JSON
+//! (`--message-format=json`), où chaque événement `diagnostics` porte la liste de ses positions.
+//! Avec `--message-format=short`, ils sont réécrits au format reconnu par la liste quickfix de Vim et
+//! par le problem matcher `$gcc` de VS Code, avec des chemins relatifs à la racine du projet.
+//!
+//! gcc est toujours lancé par [`gcc_command`], sans traduction : un gcc en français écrirait
+//! `attention :` au lieu de `warning:` et ses diagnostics ne seraient plus reconnus.
+
+use std::fmt;
+use std::process::Command;
+
+use crate::events::json_string;
+
+/// Diagnostic de gcc situé dans un fichier (`main.c:12:5: warning: ...`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: usize,
+    /// Colonne, 0 quand gcc ne la précise pas.
+    pub column: usize,
+    /// `error`, `fatal-error`, `warning` ou `note`.
+    pub severity: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Objet JSON du diagnostic.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"file\":{},\"line\":{},\"column\":{},\"severity\":{},\"message\":{}}}",
+            json_string(&self.file),
+            self.line,
+            self.column,
+            json_string(&self.severity),
+            json_string(&self.message)
+        )
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    /// Forme canonique `fichier:ligne:colonne: gravité: message`, sans la colonne si gcc ne l'a pas donnée.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:", self.file, self.line)?;
+        if self.column > 0 {
+            write!(f, "{}:", self.column)?;
+        }
+        write!(f, " {}: {}", self.severity.replace('-', " "), self.message)
+    }
+}
+
+/// Commande `gcc` dont les messages ne sont pas traduits (`LC_ALL=C`), quelle que soit la langue de l'utilisateur.
+pub fn gcc_command() -> Command {
+    let mut command: Command = Command::new("gcc");
+    command.env("LC_ALL", "C");
+    command
+}
+
+/// Réécrit une sortie de gcc au format canonique, un diagnostic par ligne, le chemin de chaque fichier
+/// passant par `file_path`. Renvoie les diagnostics canoniques puis les autres messages, conservés tels quels
+/// (erreurs de l'éditeur de liens...) ; les lignes de contexte sont retirées.
+pub fn canonical_diagnostics(text: &str, file_path: impl Fn(&str) -> String) -> (String, String) {
+    let mut canonical_lines: Vec<String> = Vec::new();
+    let mut other_lines: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        match parse_diagnostic(line) {
+            Some(diagnostic) => canonical_lines.push(Diagnostic { file: file_path(&diagnostic.file), ..diagnostic }.to_string()),
+            None if !is_context_line(line) => other_lines.push(line),
+            None => {}
+        }
+    }
+    (canonical_lines.join("\n"), other_lines.join("\n"))
+}
+
+/// Diagnostics situés d'une sortie de gcc, dans l'ordre ; les autres lignes sont ignorées.
+pub fn parse_diagnostics(text: &str) -> Vec<Diagnostic> {
+    text.lines().filter_map(parse_diagnostic).collect()
+}
+
+/// Découpe une ligne `fichier:ligne:colonne: gravité: message` ; la colonne est facultative.
+pub fn parse_diagnostic(line: &str) -> Option<Diagnostic> {
+    // Le fichier peut contenir `:` (lecteur Windows) : on cherche le premier `:<ligne>:` numérique
+    let mut search_start: usize = 0;
+    while let Some(offset) = line[search_start..].find(':') {
+        let separator: usize = search_start + offset;
+        let mut fields = line[separator + 1..].splitn(3, ':');
+        let line_number: Option<usize> = fields.next().and_then(|field| field.parse().ok());
+
+        if let Some(line_number) = line_number {
+            let second: &str = fields.next().unwrap_or_default();
+            let (column, rest): (usize, String) = match second.parse::<usize>() {
+                Ok(column) => (column, fields.next().unwrap_or_default().to_string()),
+                Err(_) => (0, [second, fields.next().unwrap_or_default()].join(":")),
+            };
+            let (severity, message) = rest.trim().split_once(':')?;
+            let severity: &str = severity.trim();
+            if !["error", "warning", "note", "fatal error"].contains(&severity) {
+                return None;
+            }
+
+            return Some(Diagnostic {
+                file: line[..separator].to_string(),
+                line: line_number,
+                column,
+                severity: severity.replace(' ', "-"),
+                message: message.trim().to_string(),
+            });
+        }
+
+        search_start = separator + 1;
+    }
+    None
+}
+
+/// Ligne de contexte de gcc accompagnant un diagnostic : extrait de source, curseur ou fonction concernée.
+pub fn is_context_line(line: &str) -> bool {
+    let trimmed: &str = line.trim_start();
+    let digits: &str = trimmed.trim_start_matches(|c: char| c.is_ascii_digit());
+
+    trimmed.is_empty()
+        || digits.trim_start().starts_with('|')
+        || line.contains(": In function")
+        || line.contains(": At top level:")
+        || trimmed.starts_with("In file included from")
+        || trimmed.starts_with("from ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GCC_OUTPUT: &str = "build/demo/source/src/main.c: In function 'main':
+build/demo/source/src/main.c:4:9: warning: unused variable 'x' [-Wunused-variable]
+    4 |     int x;
+      |         ^
+build/demo/source/src/main.c:5:12: error: 'y' undeclared (first use in this function)
+src/util.h:2: note: declared here
+cc1: some warnings being treated as errors";
+
+    #[test]
+    fn diagnostics_are_rewritten_one_per_line() {
+        let (canonical, others) = canonical_diagnostics(GCC_OUTPUT, |file| file.trim_start_matches("build/demo/source/").to_string());
+        assert_eq!(
+            canonical,
+            "src/main.c:4:9: warning: unused variable 'x' [-Wunused-variable]\n\
+             src/main.c:5:12: error: 'y' undeclared (first use in this function)\n\
+             src/util.h:2: note: declared here"
+        );
+        assert_eq!(others, "cc1: some warnings being treated as errors");
+    }
+
+    #[test]
+    fn a_diagnostic_is_split_into_its_fields() {
+        let diagnostic: Diagnostic = parse_diagnostic("C:/projet/main.c:12:5: fatal error: stdio.h: No such file").unwrap();
+        assert_eq!((diagnostic.file.as_str(), diagnostic.line, diagnostic.column), ("C:/projet/main.c", 12, 5));
+        assert_eq!((diagnostic.severity.as_str(), diagnostic.message.as_str()), ("fatal-error", "stdio.h: No such file"));
+        assert_eq!(diagnostic.to_string(), "C:/projet/main.c:12:5: fatal error: stdio.h: No such file");
+
+        assert!(parse_diagnostic("main.c:(.text+0xa): undefined reference to `g'").is_none());
+        assert!(parse_diagnostic("    4 |     int x;").is_none());
+    }
+}
@@ -0,0 +1,81 @@
+//! Erreurs du build et codes de sortie associés.
+//!
+//! Chaque étape renvoie une [`BuildError`] remontée jusqu'à `main()`, qui l'affiche dans une
+//! ligne de résumé et termine le processus avec le code de sortie correspondant :
+//!
+//! | Code | Erreur       |
+//! |------|--------------|
+//! | 0    | succès       |
+//! | 2    | `Usage`      |
+//! | 3    | `Config`     |
+//! | 4    | `Discovery`  |
+//! | 5    | `Compile`    |
+//! | 6    | `Link`       |
+//! | 7    | `Run`        |
+//! | 8    | `Hook`       |
+//! | 9    | `Storage`    |
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::tr;
+
+/// Erreur interrompant le build ou une commande.
+#[derive(Debug)]
+pub enum BuildError {
+    /// Ligne de commande invalide.
+    Usage(String),
+    /// Configuration invalide (`settings.txt`, ressources, sorties en conflit).
+    Config(String),
+    /// Projet introuvable ou vide.
+    Discovery(String),
+    /// Une ou plusieurs sources n'ont pas pu être compilées.
+    Compile(Vec<(PathBuf, String)>),
+    /// L'édition de liens a échoué.
+    Link(String),
+    /// L'exécutable n'a pas pu être lancé ou s'est terminé en erreur.
+    Run(String),
+    /// Un hook de `settings.txt` a échoué.
+    Hook(String),
+    /// Lecture ou écriture impossible dans l'historique ou le cache.
+    Storage(String),
+}
+
+impl BuildError {
+    /// Code de sortie du processus pour cette erreur.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            BuildError::Usage(_) => 2,
+            BuildError::Config(_) => 3,
+            BuildError::Discovery(_) => 4,
+            BuildError::Compile(_) => 5,
+            BuildError::Link(_) => 6,
+            BuildError::Run(_) => 7,
+            BuildError::Hook(_) => 8,
+            BuildError::Storage(_) => 9,
+        }
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Usage(message) => write!(f, "{}", tr!("error.usage", message)),
+            BuildError::Config(message) => write!(f, "{}", tr!("error.config", message)),
+            BuildError::Discovery(message) => write!(f, "{}", tr!("error.discovery", message)),
+            BuildError::Compile(failures) => {
+                write!(f, "{}", tr!("error.compile", failures.len()))?;
+                for (source_file, _) in failures {
+                    write!(f, "\n\t{}", source_file.display())?;
+                }
+                Ok(())
+            }
+            BuildError::Link(message) => write!(f, "{}", tr!("error.link", message)),
+            BuildError::Run(message) => write!(f, "{}", tr!("error.run", message)),
+            BuildError::Hook(message) => write!(f, "{}", tr!("error.hook", message)),
+            BuildError::Storage(message) => write!(f, "{}", tr!("error.storage", message)),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
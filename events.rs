@@ -0,0 +1,201 @@
+//! Événements émis pendant un build, pour suivre sa progression depuis un outil externe.
+//!
+//! Un gestionnaire est enregistré par [`Project::on_event`](crate::Project::on_event) ; il est appelé
+//! depuis les threads de compilation et doit donc être `Send + Sync`.
+//!
+//! Chaque événement est aussi écrit au format JSON Lines dans le dossier de l'exécution
+//! (`logs/<id>_<date>_<heure>/events.jsonl`), une ligne par événement :
+//!
+//! ```text
+//! {"time":"2024-05-02T14:03:11.482+02:00","event":"command_finished","program":"gcc","args":["-c","main.c"],"duration_ms":41.207,"status":0,"success":true}
+//! ```
+//!
+//! Avec `--message-format=json`, les mêmes lignes sont écrites sur la sortie standard.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{Local, SecondsFormat};
+
+use crate::diagnostics::{Diagnostic, parse_diagnostics};
+
+/// Étape franchie par le build.
+#[derive(Debug, Clone)]
+pub enum BuildEvent {
+    /// Fichier retenu par la recherche, avec son extension (`c`, `h`, `dll`, `a` ou `o`).
+    FileDiscovered { path: PathBuf, kind: &'static str },
+    /// Recherche des fichiers du projet terminée.
+    ScanFinished { sources: usize, headers: usize, objects: usize, libraries: usize },
+    /// Compilation des sources commencée, avec le nombre de sources à compiler.
+    CompilePlanned { sources: usize },
+    /// Compilation d'une source commencée.
+    CompileStarted { source: PathBuf },
+    /// Source compilée, ou récupérée depuis le cache.
+    CompileFinished { source: PathBuf, object: PathBuf, cached: bool, duration: Duration },
+    /// Échec de compilation d'une source.
+    CompileFailed { source: PathBuf, message: String },
+    /// Édition de liens commencée.
+    LinkStarted { objects: usize },
+    /// Exécutable produit.
+    LinkFinished { executable: PathBuf, duration: Duration },
+    /// Commande externe terminée ; `status` vaut `None` si elle n'a pas pu être lancée ou a été interrompue.
+    CommandFinished { program: String, args: Vec<String>, duration: Duration, status: Option<i32>, success: bool },
+    /// Avertissements ou erreurs du compilateur pour une source.
+    Diagnostics { source: PathBuf, text: String },
+    /// Fichier produit par le build (objet ou exécutable).
+    ArtifactProduced { path: PathBuf, size: u64 },
+    /// Instantané du build enregistré dans l'historique.
+    SnapshotCreated { id: u32 },
+    /// Lancement de l'exécutable produit.
+    RunStarted { executable: PathBuf },
+    /// Fin de l'exécution de la commande, avec son code de sortie.
+    Finished { success: bool, exit_code: i32, message: String, duration: Duration },
+}
+
+impl BuildEvent {
+    /// Construit l'événement de fin d'une commande à partir de la commande lancée.
+    pub fn command_finished(command: &Command, duration: Duration, status: Option<&ExitStatus>) -> BuildEvent {
+        BuildEvent::CommandFinished {
+            program: command.get_program().to_string_lossy().to_string(),
+            args: command.get_args().map(|arg| arg.to_string_lossy().to_string()).collect(),
+            duration,
+            status: status.and_then(ExitStatus::code),
+            success: status.is_some_and(ExitStatus::success),
+        }
+    }
+
+    /// Construit l'événement d'un fichier produit, avec sa taille actuelle.
+    pub fn artifact(path: &Path) -> BuildEvent {
+        BuildEvent::ArtifactProduced {
+            path: path.to_path_buf(),
+            size: path.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+        }
+    }
+
+    /// Nom de l'événement dans le journal JSON.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuildEvent::FileDiscovered { .. } => "file_discovered",
+            BuildEvent::ScanFinished { .. } => "scan_finished",
+            BuildEvent::CompilePlanned { .. } => "compile_planned",
+            BuildEvent::CompileStarted { .. } => "compile_started",
+            BuildEvent::CompileFinished { .. } => "compile_finished",
+            BuildEvent::CompileFailed { .. } => "compile_failed",
+            BuildEvent::LinkStarted { .. } => "link_started",
+            BuildEvent::LinkFinished { .. } => "link_finished",
+            BuildEvent::CommandFinished { .. } => "command_finished",
+            BuildEvent::Diagnostics { .. } => "diagnostics",
+            BuildEvent::ArtifactProduced { .. } => "artifact_produced",
+            BuildEvent::SnapshotCreated { .. } => "snapshot_created",
+            BuildEvent::RunStarted { .. } => "run_started",
+            BuildEvent::Finished { .. } => "finished",
+        }
+    }
+
+    /// Objet JSON d'une ligne du journal, horodaté.
+    pub fn to_json(&self) -> String {
+        let path = |path: &PathBuf| json_string(&path.to_string_lossy());
+        let duration_ms = |duration: &Duration| format!("{:.3}", duration.as_secs_f64() * 1000.0);
+
+        let fields: Vec<(&str, String)> = match self {
+            BuildEvent::FileDiscovered { path: file, kind } => vec![("path", path(file)), ("kind", json_string(kind))],
+            BuildEvent::ScanFinished { sources, headers, objects, libraries } => vec![
+                ("sources", sources.to_string()),
+                ("headers", headers.to_string()),
+                ("objects", objects.to_string()),
+                ("libraries", libraries.to_string()),
+            ],
+            BuildEvent::CompilePlanned { sources } => vec![("sources", sources.to_string())],
+            BuildEvent::CompileStarted { source } => vec![("source", path(source))],
+            BuildEvent::CompileFinished { source, object, cached, duration } => vec![
+                ("source", path(source)),
+                ("object", path(object)),
+                ("cached", cached.to_string()),
+                ("duration_ms", duration_ms(duration)),
+            ],
+            BuildEvent::CompileFailed { source, message } => vec![("source", path(source)), ("message", json_string(message))],
+            BuildEvent::LinkStarted { objects } => vec![("objects", objects.to_string())],
+            BuildEvent::LinkFinished { executable, duration } => vec![("executable", path(executable)), ("duration_ms", duration_ms(duration))],
+            BuildEvent::CommandFinished { program, args, duration, status, success } => vec![
+                ("program", json_string(program)),
+                ("args", format!("[{}]", args.iter().map(|arg| json_string(arg)).collect::<Vec<String>>().join(","))),
+                ("duration_ms", duration_ms(duration)),
+                ("status", status.map(|code| code.to_string()).unwrap_or_else(|| "null".to_string())),
+                ("success", success.to_string()),
+            ],
+            BuildEvent::Diagnostics { source, text } => vec![
+                ("source", path(source)),
+                ("text", json_string(text)),
+                ("diagnostics", format!("[{}]", parse_diagnostics(text).iter().map(Diagnostic::to_json).collect::<Vec<String>>().join(","))),
+            ],
+            BuildEvent::ArtifactProduced { path: file, size } => vec![("path", path(file)), ("size", size.to_string())],
+            BuildEvent::SnapshotCreated { id } => vec![("id", id.to_string())],
+            BuildEvent::RunStarted { executable } => vec![("executable", path(executable))],
+            BuildEvent::Finished { success, exit_code, message, duration } => vec![
+                ("success", success.to_string()),
+                ("exit_code", exit_code.to_string()),
+                ("message", json_string(message)),
+                ("duration_ms", duration_ms(duration)),
+            ],
+        };
+
+        let mut json: String = format!(
+            "{{\"time\":{},\"event\":{}",
+            json_string(&Local::now().to_rfc3339_opts(SecondsFormat::Millis, false)),
+            json_string(self.name())
+        );
+        for (key, value) in fields {
+            json.push_str(&format!(",{}:{}", json_string(key), value));
+        }
+        json.push('}');
+        json
+    }
+}
+
+/// Chaîne JSON entre guillemets, avec les caractères spéciaux échappés.
+pub fn json_string(value: &str) -> String {
+    let mut json: String = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Signature des fonctions recevant les événements.
+pub type EventCallback = dyn Fn(&BuildEvent) + Send + Sync;
+
+/// Fonction appelée à chaque événement.
+#[derive(Clone, Default)]
+pub struct EventHandler(Option<Arc<EventCallback>>);
+
+impl EventHandler {
+    pub fn new(handler: impl Fn(&BuildEvent) + Send + Sync + 'static) -> EventHandler {
+        EventHandler(Some(Arc::new(handler)))
+    }
+
+    /// Transmet un événement au gestionnaire, s'il y en a un.
+    pub fn emit(&self, event: BuildEvent) {
+        if let Some(handler) = &self.0 {
+            handler(&event);
+        }
+    }
+}
+
+impl fmt::Debug for EventHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() { "EventHandler(Some)" } else { "EventHandler(None)" })
+    }
+}
@@ -0,0 +1,169 @@
+//! Commandes utilisateur exécutées à des étapes fixes du build.
+//!
+//! Les commandes sont déclarées dans `settings.txt` (une ligne par commande) :
+//!
+//! ```text
+//! HOOK_PRE_COLLECT : python tools/gen_version.py
+//! HOOK_POST_LINK : strip build/demo/executable/main
+//! ```
+//!
+//! Chaque commande reçoit des variables d'environnement décrivant le build en cours.
+//! Une commande qui échoue interrompt le build.
+
+use std::process::{Command, Output};
+use std::time::Instant;
+
+use crate::context::BuildContext;
+use crate::events::BuildEvent;
+use crate::tr;
+
+/// Étapes du build auxquelles des commandes peuvent être rattachées.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookPhase {
+    PreCollect,
+    PostCompile,
+    PostLink,
+    PostRun,
+}
+
+impl HookPhase {
+    /// Clé de `settings.txt` déclarant les commandes de l'étape.
+    fn settings_key(self) -> &'static str {
+        match self {
+            HookPhase::PreCollect => "HOOK_PRE_COLLECT",
+            HookPhase::PostCompile => "HOOK_POST_COMPILE",
+            HookPhase::PostLink => "HOOK_POST_LINK",
+            HookPhase::PostRun => "HOOK_POST_RUN",
+        }
+    }
+
+    /// Nom de l'étape transmis dans `COMPILERC_PHASE`.
+    pub fn name(self) -> &'static str {
+        match self {
+            HookPhase::PreCollect => "pre-collect",
+            HookPhase::PostCompile => "post-compile",
+            HookPhase::PostLink => "post-link",
+            HookPhase::PostRun => "post-run",
+        }
+    }
+}
+
+/// Valeurs exposées aux commandes sous forme de variables d'environnement.
+#[derive(Debug, Clone, Default)]
+pub struct HookEnvironment {
+    pub project_name: String,
+    pub project_path: String,
+    pub target_path: String,
+    pub log_path: String,
+    pub profile: String,
+    pub output_path: String,
+    pub executable_path: String,
+}
+
+impl HookEnvironment {
+    /// Variables `COMPILERC_*` transmises aux commandes, pour l'étape `phase`.
+    pub fn variables(&self, phase: &str) -> Vec<(&'static str, String)> {
+        vec![
+            ("COMPILERC_PHASE", phase.to_string()),
+            ("COMPILERC_PROJECT_NAME", self.project_name.clone()),
+            ("COMPILERC_PROJECT_PATH", self.project_path.clone()),
+            ("COMPILERC_TARGET_PATH", self.target_path.clone()),
+            ("COMPILERC_LOG_PATH", self.log_path.clone()),
+            ("COMPILERC_PROFILE", self.profile.clone()),
+            ("COMPILERC_OUTPUT_PATH", self.output_path.clone()),
+            ("COMPILERC_EXECUTABLE", self.executable_path.clone()),
+        ]
+    }
+}
+
+/// Exécute dans l'ordre les commandes d'une étape ; la première commande en échec interrompt la suite.
+pub fn run_hooks(context: &BuildContext, phase: HookPhase) -> Result<(), String> {
+    let environment: HookEnvironment = context.hook_environment();
+
+    for hook in context.settings.get_all(phase.settings_key()) {
+        context.write_in_logs(tr!("hooks.started", phase.name(), hook));
+
+        let mut command: Command = shell_command(&hook);
+        command.envs(environment.variables(phase.name())).current_dir(&environment.project_path);
+
+        let start_time: Instant = Instant::now();
+        let output = command.output();
+        context.emit(BuildEvent::command_finished(&command, start_time.elapsed(), output.as_ref().ok().map(|output| &output.status)));
+        let output: Output = match output {
+            Ok(output) => output,
+            Err(err) => {
+                let message: String = tr!("hooks.launch_failed", phase.name(), hook, err);
+                context.write_in_logs(message.clone());
+                return Err(message);
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if !stdout.trim().is_empty() {
+            context.console().info(stdout.trim_end());
+        }
+        if !stderr.trim().is_empty() {
+            context.console().diagnostics(&stderr);
+        }
+
+        // L'échec est écrit dans le log avec la sortie complète, même si l'appelant ne fait rien de l'erreur
+        if !output.status.success() {
+            let message: String = tr!("hooks.failed", phase.name(), hook, output.status);
+            let hook_output: String = format!("{}{}", stdout, stderr);
+            context.write_in_logs(tr!("hooks.failed_log", message, hook_output.trim_end()));
+            return Err(message);
+        }
+
+        if !stdout.trim().is_empty() {
+            context.write_in_logs(tr!("hooks.stdout", stdout.trim_end()));
+        }
+        if !stderr.trim().is_empty() {
+            context.write_in_logs(tr!("hooks.stderr", stderr.trim_end()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Construit la commande passant par l'interpréteur du système.
+pub fn shell_command(hook: &str) -> Command {
+    if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", hook]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", hook]);
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn a_failed_hook_is_written_in_the_log() {
+        let folder: PathBuf = std::env::temp_dir().join(format!("compilerc-hooks-{}-failed", std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(folder.join("projet")).unwrap();
+        fs::write(folder.join("projet/settings.txt"), "HOOK_POST_LINK : echo étape; echo problème >&2; exit 3\nHOOK_POST_LINK : echo jamais\n").unwrap();
+        let context: BuildContext = BuildContext::new(&folder.join("projet").to_string_lossy(), &folder.join("build").to_string_lossy());
+        let _language = context.language_scope();
+
+        let err: String = run_hooks(&context, HookPhase::PostLink).unwrap_err();
+        assert!(err.contains("post-link") && err.contains("exit status: 3"), "{}", err);
+
+        let log: String = fs::read_to_string(PathBuf::from(context.log_path()).join(&context.log_file)).unwrap();
+        assert!(log.contains(&err), "{}", log);
+        assert!(log.contains("étape") && log.contains("problème"), "{}", log);
+        assert!(!log.contains("echo jamais"), "{}", log);
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,316 @@
+//! Règles d'exclusion appliquées lors de la recherche des fichiers du projet.
+//!
+//! Les règles proviennent de trois sources, appliquées dans cet ordre :
+//! la clé `IGNORE` de `settings.txt`, le fichier `.compilercignore` (syntaxe gitignore)
+//! et les directives `// EXCLUDE:` présentes dans les sources C.
+//! Comme pour gitignore, la dernière règle correspondante l'emporte et `!` réintègre un fichier.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::settings::Settings;
+use crate::tr;
+
+/// Nom du fichier d'exclusion recherché à la racine du projet.
+pub const IGNORE_FILE_NAME: &str = ".compilercignore";
+
+/// Origine d'une règle, utilisée dans les logs.
+#[derive(Debug, Clone)]
+pub enum RuleSource {
+    Settings,
+    IgnoreFile(usize),
+    Directive(PathBuf),
+}
+
+/// Règle d'exclusion au format gitignore.
+#[derive(Debug, Clone)]
+pub struct IgnoreRule {
+    pub pattern: String,
+    pub source: RuleSource,
+    glob: String,
+    negated: bool,
+    directory_only: bool,
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    /// Analyse un motif ; les lignes vides et les commentaires ne produisent aucune règle.
+    fn parse(line: &str, source: RuleSource) -> Option<IgnoreRule> {
+        let line = line.trim_end();
+        if line.trim().is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let mut glob: &str = line.trim_start();
+        let negated: bool = glob.starts_with('!');
+        if negated {
+            glob = &glob[1..];
+        }
+        let glob: String = glob.trim_start_matches("\\").replace('\\', "/");
+
+        let directory_only: bool = glob.ends_with('/');
+        let glob: &str = glob.trim_end_matches('/');
+
+        // Un motif contenant un '/' (autre que final) est relatif à la racine du projet
+        let anchored: bool = glob.contains('/');
+        let glob: &str = glob.trim_start_matches('/');
+
+        if glob.is_empty() {
+            return None;
+        }
+
+        Some(IgnoreRule {
+            pattern: line.trim().to_string(),
+            source,
+            glob: glob.to_string(),
+            negated,
+            directory_only,
+            anchored,
+        })
+    }
+
+    /// Indique si la règle s'applique à un chemin relatif au projet.
+    fn matches(&self, relative_path: &str, is_dir: bool) -> bool {
+        if self.directory_only && !is_dir {
+            return false;
+        }
+
+        if self.anchored {
+            glob_match(&self.glob, relative_path)
+        } else {
+            let file_name: &str = relative_path.rsplit('/').next().unwrap_or(relative_path);
+            glob_match(&self.glob, file_name)
+        }
+    }
+}
+
+impl fmt::Display for IgnoreRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            RuleSource::Settings => write!(f, "{}", tr!("ignore.settings_rule", self.pattern)),
+            RuleSource::IgnoreFile(line) => write!(f, "{}", tr!("ignore.file_rule", self.pattern, IGNORE_FILE_NAME, line)),
+            RuleSource::Directive(file) => write!(f, "{}", tr!("ignore.directive_rule", self.pattern, file.display())),
+        }
+    }
+}
+
+/// Ensemble ordonné des règles d'exclusion d'un projet.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    root: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    /// Charge les règles de `settings.txt` et de `.compilercignore`.
+    pub fn load(project_path: &str, settings: &Settings) -> IgnoreRules {
+        let mut rules: Vec<IgnoreRule> = settings
+            .get_list("IGNORE")
+            .iter()
+            .filter_map(|pattern| IgnoreRule::parse(pattern, RuleSource::Settings))
+            .collect();
+
+        if let Ok(content) = fs::read_to_string(Path::new(project_path).join(IGNORE_FILE_NAME)) {
+            rules.extend(
+                content
+                    .lines()
+                    .enumerate()
+                    .filter_map(|(index, line)| IgnoreRule::parse(line, RuleSource::IgnoreFile(index + 1))),
+            );
+        }
+
+        IgnoreRules { root: PathBuf::from(project_path), rules }
+    }
+
+    /// Ajoute les directives `// EXCLUDE:` relevées dans les sources (fichier source, motif).
+    pub fn add_directives(&mut self, directives: Vec<(PathBuf, String)>) {
+        for (source_file, pattern) in directives {
+            if let Some(rule) = IgnoreRule::parse(&pattern, RuleSource::Directive(source_file)) {
+                self.rules.push(rule);
+            }
+        }
+    }
+
+    /// Renvoie la règle qui exclut le fichier (ou le dossier), ou `None` s'il doit être conservé.
+    pub fn excluded_by(&self, file: &Path, is_dir: bool) -> Option<&IgnoreRule> {
+        if self.rules.is_empty() {
+            return None;
+        }
+
+        let relative_path: String = file
+            .strip_prefix(&self.root)
+            .unwrap_or(file)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        // Le fichier est exclu si lui-même ou l'un de ses dossiers parents correspond à une règle
+        let components: Vec<&str> = relative_path.split('/').filter(|c| !c.is_empty()).collect();
+        let mut candidates: Vec<(String, bool)> = Vec::new();
+        for index in 1..=components.len() {
+            candidates.push((components[..index].join("/"), is_dir || index < components.len()));
+        }
+
+        for (candidate, is_dir) in candidates {
+            let mut decision: Option<&IgnoreRule> = None;
+            for rule in &self.rules {
+                if rule.matches(&candidate, is_dir) {
+                    decision = if rule.negated { None } else { Some(rule) };
+                }
+            }
+            if decision.is_some() {
+                return decision;
+            }
+        }
+
+        None
+    }
+}
+
+/// Compare un chemin à un motif glob (`*`, `**`, `?`, `[a-z]`, `[!a]`).
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_match_from(&pattern, &text)
+}
+
+fn glob_match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            // `**/` correspond à zéro ou plusieurs dossiers, `**` seul à n'importe quelle suite
+            let rest: &[char] = if pattern.get(2) == Some(&'/') { &pattern[3..] } else { &pattern[2..] };
+            (0..=text.len()).any(|index| {
+                (index == 0 || text[index - 1] == '/' || pattern.get(2) != Some(&'/'))
+                    && glob_match_from(rest, &text[index..])
+            })
+        }
+        Some('*') => {
+            let rest: &[char] = &pattern[1..];
+            for index in 0..=text.len() {
+                if glob_match_from(rest, &text[index..]) {
+                    return true;
+                }
+                if index < text.len() && text[index] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => !text.is_empty() && text[0] != '/' && glob_match_from(&pattern[1..], &text[1..]),
+        Some('[') => match (text.first(), pattern.iter().position(|&c| c == ']')) {
+            (Some(&c), Some(end)) if end > 1 && c != '/' => {
+                class_match(&pattern[1..end], c) && glob_match_from(&pattern[end + 1..], &text[1..])
+            }
+            (Some(&c), _) => c == '[' && glob_match_from(&pattern[1..], &text[1..]),
+            (None, _) => false,
+        },
+        Some(&p) => !text.is_empty() && text[0] == p && glob_match_from(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Teste un caractère contre le contenu d'une classe `[...]`.
+fn class_match(class: &[char], c: char) -> bool {
+    let (negated, class) = match class.first() {
+        Some('!') | Some('^') => (true, &class[1..]),
+        _ => (false, class),
+    };
+
+    let mut found: bool = false;
+    let mut index: usize = 0;
+    while index < class.len() {
+        if index + 2 < class.len() && class[index + 1] == '-' {
+            if class[index] <= c && c <= class[index + 2] {
+                found = true;
+            }
+            index += 3;
+        } else {
+            if class[index] == c {
+                found = true;
+            }
+            index += 1;
+        }
+    }
+
+    found != negated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ignore_rules(patterns: &[&str]) -> IgnoreRules {
+        IgnoreRules {
+            root: PathBuf::from("/projet"),
+            rules: patterns.iter().filter_map(|pattern| IgnoreRule::parse(pattern, RuleSource::Settings)).collect(),
+        }
+    }
+
+    fn excluded(rules: &IgnoreRules, relative_path: &str) -> bool {
+        rules.excluded_by(&Path::new("/projet").join(relative_path), false).is_some()
+    }
+
+    #[test]
+    fn double_star_matches_any_number_of_directories() {
+        let rules: IgnoreRules = ignore_rules(&["src/**/*.c", "**/generated"]);
+        assert!(excluded(&rules, "src/main.c"));
+        assert!(excluded(&rules, "src/a/b/main.c"));
+        assert!(!excluded(&rules, "lib/main.c"));
+        assert!(excluded(&rules, "generated/table.c"));
+        assert!(excluded(&rules, "lib/x/generated/table.c"));
+    }
+
+    #[test]
+    fn single_star_and_question_mark_stay_in_one_component() {
+        assert!(glob_match("*.c", "main.c"));
+        assert!(!glob_match("*.c", "src/main.c"));
+        assert!(glob_match("test_?.c", "test_1.c"));
+        assert!(!glob_match("a?b", "a/b"));
+        assert!(glob_match("[a-c]x.c", "bx.c"));
+        assert!(!glob_match("[!a-c]x.c", "bx.c"));
+    }
+
+    #[test]
+    fn last_matching_rule_wins_and_negation_keeps_the_file() {
+        let rules: IgnoreRules = ignore_rules(&["*.c", "!keep.c"]);
+        assert!(excluded(&rules, "other.c"));
+        assert!(!excluded(&rules, "keep.c"));
+        assert!(!excluded(&rules, "src/keep.c"));
+
+        let rules: IgnoreRules = ignore_rules(&["!keep.c", "*.c"]);
+        assert!(excluded(&rules, "keep.c"));
+    }
+
+    #[test]
+    fn negation_does_not_reinclude_a_file_of_an_excluded_directory() {
+        let rules: IgnoreRules = ignore_rules(&["build/", "!build/keep.c"]);
+        assert!(excluded(&rules, "build/keep.c"));
+    }
+
+    #[test]
+    fn a_pattern_with_a_slash_is_anchored_to_the_project_root() {
+        let rules: IgnoreRules = ignore_rules(&["/main.c", "legacy/*.c"]);
+        assert!(excluded(&rules, "main.c"));
+        assert!(!excluded(&rules, "src/main.c"));
+        assert!(excluded(&rules, "legacy/old.c"));
+        assert!(!excluded(&rules, "src/legacy/old.c"));
+
+        let rules: IgnoreRules = ignore_rules(&["main.c"]);
+        assert!(excluded(&rules, "src/main.c"));
+    }
+
+    #[test]
+    fn a_trailing_slash_only_matches_directories() {
+        let rules: IgnoreRules = ignore_rules(&["gen/"]);
+        assert!(!excluded(&rules, "gen"));
+        assert!(excluded(&rules, "gen/table.c"));
+        assert!(rules.excluded_by(Path::new("/projet/gen"), true).is_some());
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_not_rules() {
+        assert!(IgnoreRule::parse("# commentaire", RuleSource::Settings).is_none());
+        assert!(IgnoreRule::parse("   ", RuleSource::Settings).is_none());
+        assert!(IgnoreRule::parse("!", RuleSource::Settings).is_none());
+    }
+}
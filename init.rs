@@ -0,0 +1,360 @@
+//! Création d'un nouveau projet C à partir d'un modèle.
+//!
+//! Tous les modèles partagent la même organisation :
+//!
+//! ```text
+//! src/        sources du projet
+//! include/    en-têtes publics, inclus par "../include/<nom>.h"
+//! tests/      tests, compilés à part par les commandes TEST (exclus du build par .compilercignore)
+//! settings.txt, .compilercignore, .gitignore
+//! ```
+//!
+//! Les modèles de bibliothèque lient un exemple (`examples/main.c`) et produisent la bibliothèque
+//! dans `output/<profil>` par un hook `HOOK_POST_LINK`, à côté des programmes de test.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::ignore::IGNORE_FILE_NAME;
+use crate::settings::SETTINGS_FILE_NAME;
+use crate::tr;
+
+/// Modèles de projet proposés par `init`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProjectTemplate {
+    #[default]
+    Executable,
+    StaticLibrary,
+    SharedLibrary,
+    Tests,
+}
+
+impl ProjectTemplate {
+    /// Noms acceptés en ligne de commande.
+    pub const NAMES: [&'static str; 4] = ["executable", "static", "shared", "tests"];
+
+    /// Retrouve un modèle à partir de son nom.
+    pub fn from_name(name: &str) -> Option<ProjectTemplate> {
+        match name.to_lowercase().as_str() {
+            "executable" | "exe" => Some(ProjectTemplate::Executable),
+            "static" | "lib" => Some(ProjectTemplate::StaticLibrary),
+            "shared" | "dylib" => Some(ProjectTemplate::SharedLibrary),
+            "tests" | "test" => Some(ProjectTemplate::Tests),
+            _ => None,
+        }
+    }
+
+    /// Nom du modèle.
+    pub fn name(self) -> &'static str {
+        match self {
+            ProjectTemplate::Executable => "executable",
+            ProjectTemplate::StaticLibrary => "static",
+            ProjectTemplate::SharedLibrary => "shared",
+            ProjectTemplate::Tests => "tests",
+        }
+    }
+
+    /// Fichiers du modèle, avec `@NAME@` et `@GUARD@` à remplacer par le nom du module,
+    /// `@OUTPUT@` par la variable du dossier de sortie et `@SHARED@` par l'extension des bibliothèques partagées.
+    fn files(self) -> Vec<(&'static str, &'static str)> {
+        let mut files: Vec<(&'static str, &'static str)> = vec![
+            (".gitignore", GITIGNORE_TEMPLATE),
+            (IGNORE_FILE_NAME, COMPILERCIGNORE_TEMPLATE),
+            ("include/@NAME@.h", HEADER_TEMPLATE),
+            ("src/@NAME@.c", MODULE_TEMPLATE),
+        ];
+
+        match self {
+            ProjectTemplate::Executable => files.extend([
+                (SETTINGS_FILE_NAME, EXECUTABLE_SETTINGS_TEMPLATE),
+                ("src/main.c", MAIN_TEMPLATE),
+                ("tests/test_@NAME@.c", TEST_TEMPLATE),
+            ]),
+            ProjectTemplate::StaticLibrary => files.extend([
+                (SETTINGS_FILE_NAME, STATIC_SETTINGS_TEMPLATE),
+                ("examples/main.c", MAIN_TEMPLATE),
+                ("tests/test_@NAME@.c", TEST_TEMPLATE),
+            ]),
+            ProjectTemplate::SharedLibrary => files.extend([
+                (SETTINGS_FILE_NAME, SHARED_SETTINGS_TEMPLATE),
+                ("examples/main.c", MAIN_TEMPLATE),
+                ("tests/test_@NAME@.c", TEST_TEMPLATE),
+            ]),
+            ProjectTemplate::Tests => files.extend([
+                (SETTINGS_FILE_NAME, TESTS_SETTINGS_TEMPLATE),
+                ("src/main.c", MAIN_TEMPLATE),
+                ("tests/test.h", TEST_HEADER_TEMPLATE),
+                ("tests/test_@NAME@.c", TEST_SUITE_TEMPLATE),
+                ("tests/test_@NAME@_limits.c", TEST_LIMITS_TEMPLATE),
+            ]),
+        }
+
+        files
+    }
+}
+
+const GITIGNORE_TEMPLATE: &str = "# Dossier cible par défaut de compilerc
+/build/
+*.o
+*.a
+*.so
+*.dll
+*.exe
+";
+
+const COMPILERCIGNORE_TEMPLATE: &str = "# Les tests ont leur propre main() : ils sont compilés par les commandes TEST
+tests/
+build/
+";
+
+const HEADER_TEMPLATE: &str = "#ifndef @GUARD@_H
+#define @GUARD@_H
+
+int @NAME@_add(int a, int b);
+
+#endif
+";
+
+const MODULE_TEMPLATE: &str = "#include \"../include/@NAME@.h\"
+
+int @NAME@_add(int a, int b) {
+    return a + b;
+}
+";
+
+const MAIN_TEMPLATE: &str = "#include <stdio.h>
+#include \"../include/@NAME@.h\"
+
+int main(int argc, char *argv[]) {
+    printf(\"Bonjour ! 2 + 3 = %d\\n\", @NAME@_add(2, 3));
+    return 0;
+}
+";
+
+const TEST_TEMPLATE: &str = "#include <stdio.h>
+#include \"../include/@NAME@.h\"
+
+int main(void) {
+    if (@NAME@_add(2, 3) != 5) {
+        fprintf(stderr, \"@NAME@_add(2, 3) != 5\\n\");
+        return 1;
+    }
+    return 0;
+}
+";
+
+const TEST_HEADER_TEMPLATE: &str = "#ifndef TEST_H
+#define TEST_H
+
+#include <stdio.h>
+
+static int test_failures = 0;
+
+/* Vérifie une condition sans interrompre le test */
+#define CHECK(condition) \\
+    do { \\
+        if (!(condition)) { \\
+            fprintf(stderr, \"%s:%d: échec : %s\\n\", __FILE__, __LINE__, #condition); \\
+            test_failures++; \\
+        } \\
+    } while (0)
+
+#define TEST_RESULT() (test_failures == 0 ? 0 : 1)
+
+#endif
+";
+
+const TEST_SUITE_TEMPLATE: &str = "#include \"test.h\"
+#include \"../include/@NAME@.h\"
+
+int main(void) {
+    CHECK(@NAME@_add(2, 3) == 5);
+    CHECK(@NAME@_add(-2, 2) == 0);
+    CHECK(@NAME@_add(0, 0) == 0);
+    return TEST_RESULT();
+}
+";
+
+const TEST_LIMITS_TEMPLATE: &str = "#include <limits.h>
+#include \"test.h\"
+#include \"../include/@NAME@.h\"
+
+int main(void) {
+    CHECK(@NAME@_add(INT_MAX, 0) == INT_MAX);
+    CHECK(@NAME@_add(INT_MIN, 0) == INT_MIN);
+    return TEST_RESULT();
+}
+";
+
+const EXECUTABLE_SETTINGS_TEMPLATE: &str = "# Configuration du build, une ligne CLE : valeur par option
+# RESOURCE : assets/logo.png
+# IGNORE : legacy/*.c
+PROFILE : debug
+TEST : gcc -Wall -o \"@OUTPUT@/test_@NAME@\" tests/test_@NAME@.c src/@NAME@.c && \"@OUTPUT@/test_@NAME@\"
+";
+
+const STATIC_SETTINGS_TEMPLATE: &str = "# Configuration du build, une ligne CLE : valeur par option
+# L'exemple examples/main.c est lié ; la bibliothèque est archivée après l'édition de liens
+PROFILE : debug
+HOOK_POST_LINK : ar rcs \"@OUTPUT@/lib@NAME@.a\" \"@OUTPUT@/src/@NAME@.o\"
+TEST : gcc -Wall -o \"@OUTPUT@/test_@NAME@\" tests/test_@NAME@.c src/@NAME@.c && \"@OUTPUT@/test_@NAME@\"
+";
+
+const SHARED_SETTINGS_TEMPLATE: &str = "# Configuration du build, une ligne CLE : valeur par option
+# L'exemple examples/main.c est lié ; la bibliothèque partagée est produite après l'édition de liens
+PROFILE : debug
+HOOK_POST_LINK : gcc -shared -fPIC -o \"@OUTPUT@/lib@NAME@@SHARED@\" src/@NAME@.c
+TEST : gcc -Wall -o \"@OUTPUT@/test_@NAME@\" tests/test_@NAME@.c src/@NAME@.c && \"@OUTPUT@/test_@NAME@\"
+";
+
+const TESTS_SETTINGS_TEMPLATE: &str = "# Configuration du build, une ligne CLE : valeur par option
+# Chaque fichier tests/test_*.c est un programme de test lancé par `main test`
+PROFILE : debug
+TEST : gcc -Wall -o \"@OUTPUT@/test_@NAME@\" tests/test_@NAME@.c src/@NAME@.c && \"@OUTPUT@/test_@NAME@\"
+TEST : gcc -Wall -o \"@OUTPUT@/test_@NAME@_limits\" tests/test_@NAME@_limits.c src/@NAME@.c && \"@OUTPUT@/test_@NAME@_limits\"
+";
+
+/// Dossier de sortie dans les commandes générées, avec la syntaxe du shell qui lance les hooks et les tests
+/// (`cmd /C` sous Windows, `sh -c` ailleurs).
+const OUTPUT_VARIABLE: &str = if cfg!(windows) { "%COMPILERC_OUTPUT_PATH%" } else { "$COMPILERC_OUTPUT_PATH" };
+
+/// Extension des bibliothèques partagées de la plateforme.
+const SHARED_LIBRARY_EXTENSION: &str = if cfg!(windows) { ".dll" } else { ".so" };
+
+/// Nom de module réservé : `src/main.c` contient déjà le programme des modèles.
+const RESERVED_MODULE_NAME: &str = "main";
+
+/// Crée l'arborescence d'un modèle dans le dossier du projet ; renvoie les fichiers créés.
+///
+/// Rien n'est écrit si l'un des fichiers du modèle existe déjà.
+pub fn init_project(project_path: &str, template: ProjectTemplate) -> Result<Vec<PathBuf>, io::Error> {
+    let files: Vec<(PathBuf, String)> = render_files(project_path, template, &module_name(project_path))?;
+
+    if let Some((existing, _)) = files.iter().find(|(file_path, _)| file_path.exists()) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            tr!("init.already_initialized", existing.display()),
+        ));
+    }
+
+    let mut created: Vec<PathBuf> = Vec::new();
+    for (file_path, content) in files {
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&file_path, content)?;
+        created.push(file_path);
+    }
+
+    Ok(created)
+}
+
+/// Fichiers du modèle pour un module, avec leur chemin et leur contenu ; deux fichiers ne peuvent pas avoir le même chemin.
+fn render_files(project_path: &str, template: ProjectTemplate, name: &str) -> Result<Vec<(PathBuf, String)>, io::Error> {
+    let guard: String = name.to_uppercase();
+
+    let files: Vec<(PathBuf, String)> = template
+        .files()
+        .into_iter()
+        .map(|(file_name, content)| {
+            let file_path: PathBuf = Path::new(project_path).join(file_name.replace("@NAME@", name));
+            let content: String = content
+                .replace("@NAME@", name)
+                .replace("@GUARD@", &guard)
+                .replace("@OUTPUT@", OUTPUT_VARIABLE)
+                .replace("@SHARED@", SHARED_LIBRARY_EXTENSION);
+            (file_path, content)
+        })
+        .collect();
+
+    for (index, (file_path, _)) in files.iter().enumerate() {
+        if files[..index].iter().any(|(other, _)| other == file_path) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, tr!("init.duplicate_file", file_path.display())));
+        }
+    }
+
+    Ok(files)
+}
+
+/// Nom du module principal, dérivé du nom du dossier et utilisable comme identifiant C.
+///
+/// Un dossier `main` donne le module `main_lib`, pour ne pas remplacer le programme `src/main.c`.
+fn module_name(project_path: &str) -> String {
+    let directory: PathBuf = fs::canonicalize(project_path).unwrap_or_else(|_| PathBuf::from(project_path));
+    let directory_name: String = directory.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+
+    let mut name: String = directory_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert_str(0, "projet");
+    }
+    if name == RESERVED_MODULE_NAME {
+        name.push_str("_lib");
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered_names(template: ProjectTemplate, name: &str) -> Vec<String> {
+        render_files("projet", template, name)
+            .unwrap()
+            .into_iter()
+            .map(|(file_path, _)| file_path.strip_prefix("projet").unwrap().to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn module_names_are_c_identifiers() {
+        assert_eq!(module_name("/tmp/Mon-Projet"), "mon_projet");
+        assert_eq!(module_name("/tmp/2048"), "projet2048");
+        assert_eq!(module_name("/tmp/main"), "main_lib");
+        assert_eq!(module_name("/tmp/Main"), "main_lib");
+    }
+
+    #[test]
+    fn an_executable_project_has_its_module_and_its_main() {
+        assert_eq!(
+            rendered_names(ProjectTemplate::Executable, "calcul"),
+            [".gitignore", IGNORE_FILE_NAME, "include/calcul.h", "src/calcul.c", SETTINGS_FILE_NAME, "src/main.c", "tests/test_calcul.c"]
+        );
+
+        let files: Vec<(PathBuf, String)> = render_files("projet", ProjectTemplate::Executable, "calcul").unwrap();
+        let module: &str = &files[3].1;
+        assert!(module.contains("int calcul_add(int a, int b)"));
+        assert!(files.iter().all(|(_, content)| !content.contains('@')));
+    }
+
+    #[test]
+    fn a_project_named_main_keeps_both_files() {
+        let name: String = module_name("/tmp/main");
+        for template in [ProjectTemplate::Executable, ProjectTemplate::StaticLibrary, ProjectTemplate::SharedLibrary, ProjectTemplate::Tests] {
+            let names: Vec<String> = rendered_names(template, &name);
+            assert!(names.contains(&"src/main_lib.c".to_string()));
+            assert!(names.contains(&"include/main_lib.h".to_string()));
+        }
+
+        let files: Vec<(PathBuf, String)> = render_files("projet", ProjectTemplate::Executable, &name).unwrap();
+        let main: &str = &files.iter().find(|(file_path, _)| file_path.ends_with("src/main.c")).unwrap().1;
+        assert!(main.contains("main_lib_add(2, 3)"));
+    }
+
+    #[test]
+    fn duplicate_destinations_are_refused() {
+        let err: io::Error = render_files("projet", ProjectTemplate::Executable, "main").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn the_shared_library_has_the_platform_extension() {
+        let files: Vec<(PathBuf, String)> = render_files("projet", ProjectTemplate::SharedLibrary, "calcul").unwrap();
+        let settings: &str = &files.iter().find(|(file_path, _)| file_path.ends_with(SETTINGS_FILE_NAME)).unwrap().1;
+        assert!(settings.contains(&format!("libcalcul{}", SHARED_LIBRARY_EXTENSION)));
+    }
+}
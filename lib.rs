@@ -0,0 +1,41 @@
+//! # CompilerC
+//!
+//! Orchestration de la compilation de projets C avec gcc : recherche des fichiers, synchronisation
+//! de la cible, cache, compilation distribuée, édition de liens, historique des builds.
+//!
+//! [`Project`] enchaîne les étapes ; [`BuildEvent`] permet de suivre leur progression.
+
+extern crate chrono;
+extern crate tokio;
+
+pub mod cache;
+pub mod clean;
+pub mod console;
+pub mod context;
+pub mod diagnostics;
+pub mod error;
+pub mod events;
+pub mod hooks;
+pub mod ignore;
+pub mod init;
+pub mod logs;
+pub mod messages;
+pub mod pipeline;
+pub mod project;
+pub mod remote_cache;
+pub mod report;
+pub mod resources;
+pub mod scanner;
+pub mod settings;
+pub mod snapshots;
+pub mod stats;
+pub mod sync;
+pub mod timings;
+pub mod worker;
+
+pub use context::BuildContext;
+pub use error::BuildError;
+pub use events::BuildEvent;
+pub use project::Project;
+pub use scanner::ProjectInventory;
+pub use settings::Settings;
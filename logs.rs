@@ -0,0 +1,364 @@
+//! Logs des exécutions : un dossier par exécution dans `<cible>/<projet>/logs`.
+//!
+//! Chaque dossier est nommé `<id>_<date>_<heure>` (`0012_2024-05-02_140311`) et contient le log texte
+//! (`build.log`), le journal des événements (`events.jsonl`) et le rapport HTML (`report.html`).
+//! Le résultat d'une exécution est lu dans l'événement `finished` de son journal.
+//!
+//! Les exécutions les plus anciennes sont supprimées au début de chaque build selon les clés de
+//! `settings.txt` :
+//!
+//! ```text
+//! LOG_KEEP_RUNS : 50      # nombre d'exécutions conservées (par défaut 50)
+//! LOG_MAX_AGE : 30d       # âge maximal
+//! LOG_MAX_SIZE : 200M     # taille totale maximale du dossier des logs
+//! ```
+
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::cache::parse_size;
+use crate::clean::parse_age;
+use crate::settings::Settings;
+
+/// Nom du log texte d'une exécution.
+pub const LOG_FILE_NAME: &str = "build.log";
+/// Nom du journal des événements d'une exécution.
+pub const JOURNAL_FILE_NAME: &str = "events.jsonl";
+
+/// Nombre d'exécutions conservées par défaut.
+const DEFAULT_KEEP_RUNS: usize = 50;
+
+/// Résultat d'une exécution, d'après son journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    Success,
+    Failure { exit_code: i32 },
+    /// Exécution en cours, ou interrompue avant la fin.
+    Unfinished,
+}
+
+/// Exécution enregistrée dans le dossier des logs.
+#[derive(Debug, Clone)]
+pub struct RunLog {
+    pub id: u32,
+    pub path: PathBuf,
+    /// Date et heure de début (`2024-05-02 14:03:11`), lues dans le nom du dossier.
+    pub started: String,
+    pub size: u64,
+    pub modified: SystemTime,
+    pub status: RunStatus,
+}
+
+impl RunLog {
+    /// Chemin du log texte de l'exécution.
+    pub fn log_file(&self) -> PathBuf {
+        self.path.join(LOG_FILE_NAME)
+    }
+}
+
+/// Politique de conservation des exécutions.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    pub max_runs: Option<usize>,
+    pub max_age: Option<Duration>,
+    pub max_size: Option<u64>,
+}
+
+impl RetentionPolicy {
+    /// Lit `LOG_KEEP_RUNS`, `LOG_MAX_AGE` et `LOG_MAX_SIZE` ; `LOG_KEEP_RUNS : 0` conserve toutes les exécutions.
+    pub fn from_settings(settings: &Settings) -> RetentionPolicy {
+        let max_runs: usize = settings.get("LOG_KEEP_RUNS").and_then(|value| value.parse().ok()).unwrap_or(DEFAULT_KEEP_RUNS);
+
+        RetentionPolicy {
+            max_runs: if max_runs == 0 { None } else { Some(max_runs) },
+            max_age: settings.get("LOG_MAX_AGE").and_then(parse_age),
+            max_size: settings.get("LOG_MAX_SIZE").and_then(parse_size),
+        }
+    }
+}
+
+/// Nom du dossier d'une exécution.
+pub fn run_directory_name(id: u32, started: &str) -> String {
+    format!("{:04}_{}", id, started)
+}
+
+/// Identifiant de la prochaine exécution : le plus grand identifiant existant plus un.
+pub fn next_run_id(logs_path: &Path) -> u32 {
+    list_runs(logs_path).last().map(|run| run.id + 1).unwrap_or(1)
+}
+
+/// Réserve l'identifiant et le dossier de la prochaine exécution ; renvoie l'identifiant et le chemin du dossier.
+///
+/// Le nom du dossier dépend de l'heure de début : deux builds concurrents ne se départagent donc pas sur le
+/// dossier lui-même, mais sur un verrou `<id>.lock` créé avec `create_new`. Celui qui obtient le verrou
+/// vérifie que l'identifiant est toujours libre, crée son dossier avec `create_dir` puis libère le verrou ;
+/// l'autre essaie l'identifiant suivant.
+pub fn reserve_run_directory(logs_path: &Path, started: &str) -> io::Result<(u32, String)> {
+    fs::create_dir_all(logs_path)?;
+    let mut id: u32 = next_run_id(logs_path);
+
+    loop {
+        let lock: PathBuf = logs_path.join(format!("{:04}.lock", id));
+        match OpenOptions::new().write(true).create_new(true).open(&lock) {
+            Ok(_) => {}
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                id += 1;
+                continue;
+            }
+            Err(err) => return Err(err),
+        }
+
+        let taken: bool = list_runs(logs_path).iter().any(|run| run.id == id);
+        let run_path: String = format!("{}/{}", logs_path.display(), run_directory_name(id, started));
+        let created: io::Result<()> = if taken { Ok(()) } else { fs::create_dir(&run_path) };
+        let _ = fs::remove_file(&lock);
+
+        match created {
+            Ok(()) if !taken => return Ok((id, run_path)),
+            Ok(()) => id += 1,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => id += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Liste les exécutions, de la plus ancienne à la plus récente ; les autres fichiers du dossier sont ignorés.
+pub fn list_runs(logs_path: &Path) -> Vec<RunLog> {
+    let entries = match fs::read_dir(logs_path) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut runs: Vec<RunLog> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| read_run(&entry.path()))
+        .collect();
+
+    runs.sort_by_key(|run| run.id);
+    runs
+}
+
+/// Dernière exécution terminée en échec.
+pub fn last_failure(logs_path: &Path) -> Option<RunLog> {
+    list_runs(logs_path).into_iter().rev().find(|run| matches!(run.status, RunStatus::Failure { .. }))
+}
+
+/// Dernières lignes d'un fichier.
+pub fn tail(file: &Path, lines: usize) -> io::Result<Vec<String>> {
+    let content: String = fs::read_to_string(file)?;
+    let all_lines: Vec<&str> = content.lines().collect();
+    let start: usize = all_lines.len().saturating_sub(lines);
+    Ok(all_lines[start..].iter().map(|line| line.to_string()).collect())
+}
+
+/// Supprime les exécutions au-delà de la politique, en commençant par les plus anciennes ; renvoie les exécutions supprimées.
+///
+/// L'exécution `current_id` n'est jamais supprimée.
+pub fn apply_retention(logs_path: &Path, policy: &RetentionPolicy, current_id: u32) -> io::Result<Vec<RunLog>> {
+    let runs: Vec<RunLog> = list_runs(logs_path).into_iter().filter(|run| run.id != current_id).collect();
+    let oldest: Option<SystemTime> = policy.max_age.and_then(|age| SystemTime::now().checked_sub(age));
+
+    // L'exécution courante compte dans le nombre d'exécutions conservées
+    let excess: usize = policy.max_runs.map(|max_runs| (runs.len() + 1).saturating_sub(max_runs)).unwrap_or(0);
+    let mut total_size: u64 = runs.iter().map(|run| run.size).sum();
+    let mut removed: Vec<RunLog> = Vec::new();
+
+    for (index, run) in runs.into_iter().enumerate() {
+        let too_many: bool = index < excess;
+        let too_old: bool = oldest.is_some_and(|oldest| run.modified < oldest);
+        let too_large: bool = policy.max_size.is_some_and(|max_size| total_size > max_size);
+
+        if too_many || too_old || too_large {
+            fs::remove_dir_all(&run.path)?;
+            total_size -= run.size;
+            removed.push(run);
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Lit une exécution à partir de son dossier ; `None` si le nom ne correspond pas à une exécution.
+fn read_run(path: &Path) -> Option<RunLog> {
+    let directory_name: String = path.file_name()?.to_string_lossy().to_string();
+    let (id, started) = directory_name.split_once('_')?;
+    let id: u32 = id.parse().ok()?;
+
+    let mut size: u64 = 0;
+    let mut modified: SystemTime = SystemTime::UNIX_EPOCH;
+    for entry in fs::read_dir(path).ok()?.flatten() {
+        if let Ok(metadata) = entry.metadata() {
+            size += metadata.len();
+            modified = modified.max(metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH));
+        }
+    }
+
+    Some(RunLog {
+        id,
+        path: path.to_path_buf(),
+        started: format_started(started),
+        size,
+        modified,
+        status: read_status(&path.join(JOURNAL_FILE_NAME)),
+    })
+}
+
+/// Résultat enregistré par l'événement `finished` du journal.
+fn read_status(journal: &Path) -> RunStatus {
+    let content: String = fs::read_to_string(journal).unwrap_or_default();
+    let finished: Option<&str> = content.lines().rev().find(|line| line.contains("\"event\":\"finished\""));
+
+    match finished {
+        Some(line) if line.contains("\"success\":true") => RunStatus::Success,
+        Some(line) => {
+            let exit_code: i32 = line
+                .split_once("\"exit_code\":")
+                .and_then(|(_, rest)| rest.split([',', '}']).next())
+                .and_then(|code| code.trim().parse().ok())
+                .unwrap_or(1);
+            RunStatus::Failure { exit_code }
+        }
+        None => RunStatus::Unfinished,
+    }
+}
+
+/// Met en forme la date d'un nom de dossier : `2024-05-02_140311` devient `2024-05-02 14:03:11`.
+fn format_started(started: &str) -> String {
+    match started.split_once('_') {
+        Some((date, time)) if time.len() == 6 && time.chars().all(|c| c.is_ascii_digit()) => {
+            format!("{} {}:{}:{}", date, &time[0..2], &time[2..4], &time[4..6])
+        }
+        _ => started.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dossier des logs temporaire propre au test, vidé au préalable.
+    fn test_logs(name: &str) -> PathBuf {
+        let logs: PathBuf = std::env::temp_dir().join(format!("compilerc-logs-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&logs);
+        fs::create_dir_all(&logs).unwrap();
+        logs
+    }
+
+    /// Ajoute une exécution de `size` octets, modifiée pour la dernière fois il y a `age` secondes.
+    fn add_run(logs: &Path, id: u32, size: usize, age: u64, journal: &str) {
+        let run: PathBuf = logs.join(run_directory_name(id, "2024-05-02_140311"));
+        fs::create_dir_all(&run).unwrap();
+        fs::write(run.join(LOG_FILE_NAME), vec![b'x'; size]).unwrap();
+        fs::write(run.join(JOURNAL_FILE_NAME), journal).unwrap();
+
+        let modified: SystemTime = SystemTime::now() - Duration::from_secs(age);
+        for file in [LOG_FILE_NAME, JOURNAL_FILE_NAME] {
+            fs::File::options().write(true).open(run.join(file)).unwrap().set_modified(modified).unwrap();
+        }
+    }
+
+    fn ids(logs: &Path) -> Vec<u32> {
+        list_runs(logs).iter().map(|run| run.id).collect()
+    }
+
+    #[test]
+    fn runs_are_read_from_their_folder() {
+        let logs: PathBuf = test_logs("read");
+        add_run(&logs, 2, 10, 0, "{\"event\":\"finished\",\"success\":false,\"exit_code\":4}\n");
+        add_run(&logs, 1, 10, 0, "{\"event\":\"finished\",\"success\":true}\n");
+        add_run(&logs, 3, 10, 0, "");
+        fs::write(logs.join("notes.txt"), "").unwrap();
+        fs::create_dir_all(logs.join("autre")).unwrap();
+
+        let runs: Vec<RunLog> = list_runs(&logs);
+        assert_eq!(runs.iter().map(|run| run.id).collect::<Vec<u32>>(), [1, 2, 3]);
+        assert_eq!(runs[0].started, "2024-05-02 14:03:11");
+        assert_eq!(runs.iter().map(|run| run.status).collect::<Vec<RunStatus>>(), [RunStatus::Success, RunStatus::Failure { exit_code: 4 }, RunStatus::Unfinished]);
+        assert_eq!(last_failure(&logs).unwrap().id, 2);
+        assert_eq!(next_run_id(&logs), 4);
+        fs::remove_dir_all(&logs).unwrap();
+    }
+
+    #[test]
+    fn reserved_directories_get_distinct_ids() {
+        let logs: PathBuf = test_logs("reserve");
+        add_run(&logs, 1, 10, 0, "");
+        // Verrou laissé par un build interrompu : l'identifiant est sauté
+        fs::write(logs.join("0002.lock"), "").unwrap();
+
+        let (id, path) = reserve_run_directory(&logs, "2024-05-02_150000").unwrap();
+        assert_eq!(id, 3);
+        assert!(Path::new(&path).is_dir());
+        assert!(!logs.join("0003.lock").exists());
+
+        // Des builds lancés à des heures différentes ne partagent pas non plus leur identifiant
+        let mut reserved: Vec<u32> = std::thread::scope(|scope| {
+            let builds: Vec<_> = (0..8).map(|index| {
+                let logs: &Path = &logs;
+                scope.spawn(move || reserve_run_directory(logs, &format!("2024-05-02_1600{:02}", index)).unwrap().0)
+            }).collect();
+            builds.into_iter().map(|build| build.join().unwrap()).collect()
+        });
+        reserved.sort();
+        assert_eq!(reserved, [4, 5, 6, 7, 8, 9, 10, 11]);
+        assert_eq!(ids(&logs), [1, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+        fs::remove_dir_all(&logs).unwrap();
+    }
+
+    #[test]
+    fn retention_keeps_the_latest_runs() {
+        let logs: PathBuf = test_logs("count");
+        for id in 1..=5 {
+            add_run(&logs, id, 10, 0, "");
+        }
+
+        // L'exécution courante compte parmi les exécutions conservées et n'est jamais supprimée
+        let policy = RetentionPolicy { max_runs: Some(3), ..RetentionPolicy::default() };
+        let removed: Vec<RunLog> = apply_retention(&logs, &policy, 5).unwrap();
+        assert_eq!(removed.iter().map(|run| run.id).collect::<Vec<u32>>(), [1, 2]);
+        assert_eq!(ids(&logs), [3, 4, 5]);
+        fs::remove_dir_all(&logs).unwrap();
+    }
+
+    #[test]
+    fn retention_removes_old_runs() {
+        let logs: PathBuf = test_logs("age");
+        add_run(&logs, 1, 10, 3 * 24 * 60 * 60, "");
+        add_run(&logs, 2, 10, 60, "");
+        add_run(&logs, 3, 10, 4 * 24 * 60 * 60, "");
+
+        let policy = RetentionPolicy { max_age: parse_age("2d"), ..RetentionPolicy::default() };
+        apply_retention(&logs, &policy, 3).unwrap();
+        assert_eq!(ids(&logs), [2, 3]);
+        fs::remove_dir_all(&logs).unwrap();
+    }
+
+    #[test]
+    fn retention_limits_the_total_size() {
+        let logs: PathBuf = test_logs("size");
+        for id in 1..=4 {
+            add_run(&logs, id, 100, 0, "");
+        }
+
+        // Sans compter l'exécution courante, 3 exécutions de 100 octets et plus : les plus anciennes partent
+        let policy = RetentionPolicy { max_size: parse_size("150"), ..RetentionPolicy::default() };
+        apply_retention(&logs, &policy, 4).unwrap();
+        assert_eq!(ids(&logs), [3, 4]);
+        fs::remove_dir_all(&logs).unwrap();
+    }
+
+    #[test]
+    fn the_policy_is_read_from_the_settings() {
+        let policy: RetentionPolicy = RetentionPolicy::from_settings(&Settings::parse("LOG_MAX_AGE : 30d\nLOG_MAX_SIZE : 2M"));
+        assert_eq!(policy.max_runs, Some(DEFAULT_KEEP_RUNS));
+        assert_eq!(policy.max_age, Some(Duration::from_secs(30 * 24 * 60 * 60)));
+        assert_eq!(policy.max_size, Some(2 * 1024 * 1024));
+
+        let policy: RetentionPolicy = RetentionPolicy::from_settings(&Settings::parse("LOG_KEEP_RUNS : 0\nLOG_MAX_SIZE : 99999999999G"));
+        assert_eq!(policy.max_runs, None);
+        assert_eq!(policy.max_size, None);
+    }
+}
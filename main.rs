@@ -1,635 +1,428 @@
-//! # Projet de Vérification des Fichiers d'un Projet Rust
+//! # CompilerC
 //!
-//! Ce programme en Rust parcourt un répertoire et vérifie la cohérence des fichiers du projet.
+//! Interface en ligne de commande de la bibliothèque `compilerc` : compile, teste et nettoie un projet C,
+//! consulte l'historique des builds, gère le cache et lance un worker de compilation.
 
-extern crate chrono;
-extern crate tokio;
-
-use std::collections::HashSet;
-use std::fs::{self, File, OpenOptions, metadata};
-use std::io::{self, BufRead, Write};
+use std::env;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Output, Stdio};
-use std::time::{SystemTime, Duration};
-use std::sync::{Arc, Mutex};
-use std::{thread, env};
-
-use chrono::Local;
-
-/// Représente les types de fichiers que nous recherchons.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum FileType {
-    C,
-    H,
-    DLL,
-    A,
-    O,
-}
+use std::time::{Duration, SystemTime};
 
-// Déclarer les variables globales
-static mut FORMATTED_TIME: Option<String> = None;
-static mut LOG_PATH: Option<String> = None;
-static mut PROJECT_PATH: Option<String> = None;
-static mut PROJECT_NAME: Option<String> = None;
-static mut TARGET_PATH: Option<String> = None;
-static mut LOG_FILE: Option<String> = None;
-
-struct FileCollections {
-    c_files: Vec<PathBuf>,
-    h_files: Vec<PathBuf>,
-    dll_files: Vec<PathBuf>,
-    a_files: Vec<PathBuf>,
-    o_files: Vec<PathBuf>,
-}
+use compilerc::cache::{CacheStats, CompileCache};
+use compilerc::clean::CleanReport;
+use compilerc::console::{Console, MessageFormat};
+use compilerc::init::init_project;
+use compilerc::logs::{RunLog, RunStatus, last_failure, list_runs, tail};
+use compilerc::messages::{Language, LanguageScope};
+use compilerc::report::write_report;
+use compilerc::settings::Settings;
+use compilerc::snapshots::{Snapshot, SnapshotDiff, diff_snapshots, list_snapshots, load_snapshot, restore_snapshot};
+use compilerc::stats::{BuildRecord, Comparison, DEFAULT_COMPARED_BUILDS, compare, load_history};
+use compilerc::worker::{DEFAULT_WORKER_ADDRESS, WORKER_TOKEN_VARIABLE, run_worker};
+use compilerc::{BuildContext, BuildError, BuildEvent, Project, ProjectInventory, tr};
 
-/// Fonction principale du programme.
-#[tokio::main]
-async fn main() {
+mod cli;
 
-    let args: Vec<String> = env::args().collect();
-    let project_path = args[1].clone(); // Le projet à compiler
-    let target_path = args[2].clone();  // Destinations des fichiers compilés
-    let path = Path::new(&project_path);
+use cli::{Cli, CliCommand, help_text, version};
 
-    let mut project_name = String::new(); // Initialisation à une chaîne vide par défaut
+/// Nombre de lignes affichées par `logs tail`.
+const TAIL_LINES: usize = 20;
 
-    // Utilise la méthode file_name pour obtenir la dernière partie du chemin
-    if let Some(file_name) = path.file_name() {
-        if let Some(file_name_str) = file_name.to_str() {
-            project_name = file_name_str.to_string();
-        }
-    }
-    
-    unsafe { 
-
-        PROJECT_NAME            = Some(project_name.to_string()); 
-        PROJECT_PATH            = Some(project_path.clone()); 
-        TARGET_PATH             = Some(format!("{}/{}", target_path.clone(), project_name.to_string() )); 
-        FORMATTED_TIME          = Some(get_date());
-        LOG_PATH                = Some(format!("{}/logs/", TARGET_PATH.as_ref().unwrap()));
-        LOG_FILE                = Some(format!("{}_{}.log", PROJECT_NAME.as_ref().unwrap(), FORMATTED_TIME.as_ref().unwrap()));
-
-    }
-
-    let project_path: String    = get_project_path();
-    let _target_path: String     = get_target_path();
-
-    let mut file_collections = FileCollections {
-        c_files: Vec::new(),
-        h_files: Vec::new(),
-        dll_files: Vec::new(),
-        a_files: Vec::new(),
-        o_files: Vec::new(),
-    };
+/// Fonction principale du programme.
+#[tokio::main]
+async fn main() {
 
+    let args: Vec<String> = env::args().skip(1).collect();
     let start_time: SystemTime = SystemTime::now();
 
-    create_directories();
-
-    collect_files(&project_path, FileType::C, &mut file_collections.c_files);
-    collect_files(&project_path, FileType::H, &mut file_collections.h_files);
-    collect_files(&project_path, FileType::DLL, &mut file_collections.dll_files);
-    collect_files(&project_path, FileType::A, &mut file_collections.a_files);
-    collect_files(&project_path, FileType::O, &mut file_collections.o_files);
-    
-    println!("before before o_file : ");
-    for o in &mut file_collections.o_files {
-        println!("{}", o.display());
-    }
-
-
-    let unique_library_files: HashSet<String> = update_library_list(&mut file_collections.c_files);
-    let total_files: usize = file_collections.c_files.len() + file_collections.h_files.len() + file_collections.dll_files.len() + file_collections.a_files.len() + file_collections.o_files.len() + unique_library_files.len();
-
-    copy_files_to_directory(&mut file_collections.h_files, "source");
-    copy_files_to_directory(&mut file_collections.c_files,  "source");
-    copy_files_to_directory(&mut file_collections.o_files,  "output");
-    copy_files_to_directory(&mut file_collections.dll_files,  "dll");
-    copy_files_to_directory(&mut file_collections.a_files,  "a");
+    let cli: Result<Cli, BuildError> = Cli::parse(&args);
+    let message_format: Option<MessageFormat> = cli.as_ref().ok().and_then(|cli| cli.message_format);
 
-    collect_files(&target_path, FileType::O, &mut file_collections.o_files);
-    collect_files(&target_path, FileType::H, &mut file_collections.h_files);
+    // La langue vaut pour tout le processus : elle est fixée une seule fois, avant d'ouvrir le projet
+    // Langue des messages hors des opérations du projet, qui prennent celle de sa clé `LANGUAGE`
+    let _language = LanguageScope::enter(startup_language(cli.as_ref().ok()));
 
-    println!("before o_file : ");
-    for o in &mut file_collections.o_files {
-        println!("{}", o.display());
-    }
-
-    file_collections.c_files = build_source(&file_collections.c_files).await;
-
-    println!("after o_file : ");
-    for o in &mut file_collections.o_files {
-        println!("{}", &o.display());
-    }
-    
-    collect_files(&project_path, FileType::C, &mut file_collections.c_files);
-    
-    // Divise unique_library_files en quatre listes en fonction de l'extension
-    let (expected_files_for_c, expected_files_for_h, expected_files_for_dll, expected_files_for_a, expected_files_for_o) =
-        split_files_by_extension(&unique_library_files);
-
-    // Vérifie si les fichiers inclus sont présents dans les listes c_files, h_files, dll_files et a_files
-    check_all_files("C", &mut file_collections.c_files, &expected_files_for_c);
-    check_all_files("H", &mut file_collections.h_files, &expected_files_for_h);
-    check_all_files("DLL", &mut file_collections.dll_files, &expected_files_for_dll);
-    check_all_files("A", &mut file_collections.a_files, &expected_files_for_a);
-    check_all_files("O", &mut file_collections.o_files, &expected_files_for_o);
-
-    let mut elapsed_files_secs: u64 = 0;    let mut elapsed_files_millis: u32 = 0;
-    let mut elapsed_compile_secs: u64 = 0;  let mut elapsed_compile_millis: u32 = 0;
-
-    if let Ok(elapsed_time) = start_time.elapsed() {    
-        (elapsed_files_secs, elapsed_files_millis) = extract_seconds_and_millis(elapsed_time);
-    }
-
-    let include_paths: Vec<String>  = extract_unique_paths(&mut file_collections.h_files);
-    let library_paths: Vec<String>  = extract_unique_paths(&mut file_collections.dll_files);
-    let libraries: Vec<String>      = extract_unique_file_names(&mut file_collections.dll_files);
+    let (project, result): (Option<Project>, Result<(), BuildError>) = match cli {
+        Ok(cli) => match run_without_project(&cli) {
+            Some(result) => (None, result),
+            None => match open_project(&cli) {
+                Ok(project) => {
+                    let result: Result<(), BuildError> = run(&project, &cli).await;
+                    (Some(project), result)
+                }
+                Err(err) => (None, Err(err)),
+            },
+        },
+        Err(err) => (None, Err(err)),
+    };
 
-    build_execute(file_collections.o_files, include_paths, library_paths, libraries).await;
+    let elapsed_time: Duration = start_time.elapsed().unwrap_or_default();
+    let (elapsed_secs, elapsed_millis): (u64, u32) = (elapsed_time.as_secs(), elapsed_time.subsec_millis());
 
-    if let Ok(elapsed_time) = start_time.elapsed() {
-        (elapsed_compile_secs, elapsed_compile_millis) = extract_seconds_and_millis(elapsed_time);
-    }
-
-    write_in_logs(
-        format!(
-            "Temps d'exécution : {}.{:03} secondes\nNombre de fichiers traités : {}\n\nTemps d'exécution Total : {}.{:03} secondes", 
-            elapsed_files_secs, elapsed_files_millis, 
-            total_files, 
-            elapsed_compile_secs, elapsed_compile_millis
-        )
-    );
-
-    println!("Time : {}", 
-        format!(
-            "Temps d'exécution : {}.{:03} secondes Nombre de fichiers traités : {}\n\nTemps d'exécution Total : {}.{:03} secondes", 
-            elapsed_files_secs, elapsed_files_millis, 
-            total_files, 
-            elapsed_compile_secs, elapsed_compile_millis
-        )
-    );
-
-    execute_main();
+    // Ligne de résumé finale, reprise dans le log de l'exécution si la commande en a écrit un
+    let summary: String = match &result {
+        Ok(_) => tr!("main.result_success", format!("{}.{:03}", elapsed_secs, elapsed_millis)),
+        Err(err) => tr!("main.result_failure", format!("{}.{:03}", elapsed_secs, elapsed_millis), err.exit_code(), err),
+    };
+    let exit_code: i32 = result.as_ref().err().map(BuildError::exit_code).unwrap_or(0);
 
-}
+    let finished: BuildEvent = BuildEvent::Finished {
+        success: result.is_ok(),
+        exit_code,
+        message: result.as_ref().err().map(BuildError::to_string).unwrap_or_default(),
+        duration: elapsed_time,
+    };
+    let console: Option<&Console> = project.as_ref().map(|project| project.context().console());
 
-fn get_log_path() -> String {
-    unsafe {  
-        match &LOG_PATH {
-            Some(value) => return value.to_string(),
-            None => {
-                return "".to_string();
-            }
-        }
-    } 
-}
+    if let Some(context) = project.as_ref().map(Project::context).filter(|context| context.run_started()) {
+        context.write_in_logs(summary.clone());
+        context.emit(finished);
 
-fn get_log_file() -> String {
-    unsafe {  
-        match &LOG_FILE {
-            Some(value) => return value.to_string(),
-            None => {
-                return "".to_string();
+        if context.settings.get_bool("HTML_REPORT", true) {
+            match write_report(context) {
+                Ok(report) if result.is_err() => eprintln!("{}", tr!("main.report", report.display())),
+                Ok(_) => {}
+                Err(err) => eprintln!("{}", tr!("main.report_failed", err)),
             }
         }
-    } 
-}
+    } else if console.map(Console::format).or(message_format) == Some(MessageFormat::Json) {
+        // Sans exécution enregistrée, le flux JSON se termine tout de même par son résultat
+        println!("{}", finished.to_json());
+    }
 
-fn get_project_path() -> String {
-    unsafe {  
-        match &PROJECT_PATH {
-            Some(value) => return value.to_string(),
-            None => {
-                return "".to_string();
+    match (result, console) {
+        (Ok(_), Some(console)) => console.success(&summary),
+        (Ok(_), None) => {}
+        (Err(_), console) => {
+            match console {
+                Some(console) => console.error(&summary),
+                None => eprintln!("{}", summary),
             }
+            std::process::exit(exit_code);
         }
-    } 
-}
+    }
 
-fn get_formatted_time() -> String {
-    unsafe {  
-        match &FORMATTED_TIME {
-            Some(value) => return value.to_string(),
-            None => {
-                return "".to_string();
-            }
-        }
-    } 
 }
 
-fn get_target_path() -> String {
-    unsafe {  
-        match &TARGET_PATH {
-            Some(value) => return value.to_string(),
-            None => {
-                return "".to_string();
-            }
-        }
-    }
+/// Langue de l'aide et du résumé : `--language`, sinon la clé `LANGUAGE` du projet désigné, sinon l'environnement.
+fn startup_language(cli: Option<&Cli>) -> Language {
+    cli.and_then(|cli| cli.language.or_else(|| Settings::load(&cli.project_path).get("LANGUAGE").and_then(Language::from_name)))
+        .or_else(Language::from_environment)
+        .unwrap_or_default()
 }
 
-/// Formate la date actuelle.
-fn get_date() -> String {
-    let local_time = Local::now();
-    local_time.format("%Y-%m-%d").to_string()
-}
+/// Commandes qui n'ouvrent pas de projet : aide, version, création de projet et worker.
+fn run_without_project(cli: &Cli) -> Option<Result<(), BuildError>> {
 
-/// Obtient la liste des fichiers à exclure.
-fn _get_exclude_list(c_files: &[PathBuf]) -> Vec<String> {
-    let mut exclude_list = Vec::new();
-
-    for c_file in c_files {
-        if let Ok(file) = File::open(c_file) {
-            let reader = io::BufReader::new(file);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    if line.starts_with("// EXCLUDE:") {
-                        let excluded_file = line.trim_start_matches("// EXCLUDE:").trim().to_string();
-                        exclude_list.push(excluded_file);
-                    }
+    let result: Result<(), BuildError> = match &cli.command {
+        CliCommand::Help { command } => {
+            println!("{}", help_text(command.as_deref()));
+            Ok(())
+        }
+        CliCommand::Version => {
+            println!("CompilerC {}", version());
+            Ok(())
+        }
+        CliCommand::Init { template } => match init_project(&cli.project_path, *template) {
+            Ok(created) => {
+                for file in &created {
+                    println!("{}", tr!("main.created", file.display()));
                 }
+                println!("{}", tr!("main.initialized", cli.project_path, template.name()));
+                Ok(())
             }
+            Err(err) => Err(BuildError::Storage(tr!("main.init_failed", err))),
+        },
+        CliCommand::Worker { address } => {
+            let address: &str = address.as_deref().unwrap_or(DEFAULT_WORKER_ADDRESS);
+            let token: Option<String> = env::var(WORKER_TOKEN_VARIABLE).ok().filter(|token| !token.is_empty());
+            run_worker(address, token).map_err(BuildError::Config)
         }
-    }
+        _ => return None,
+    };
 
-    exclude_list
-}
+    Some(result)
 
-fn extract_seconds_and_millis(elapsed_time: Duration) -> (u64, u32) {
-    (elapsed_time.as_secs(), elapsed_time.subsec_millis())
 }
 
-/// Collecte les fichiers avec une extension spécifiée.
-fn collect_files(root_path: &str, file_type: FileType, target_collection: &mut Vec<PathBuf>) {
-    match explore_directory(root_path, file_type) {
-        Ok(files) => {
-            target_collection.extend(files);
-        }
-        Err(err) => {
-            eprintln!("Erreur lors de la collecte des fichiers {:?} : {}", file_type, err);
-        }
-    }
-}
+/// Ouvre le projet désigné par la ligne de commande, en appliquant les options de configuration.
+fn open_project(cli: &Cli) -> Result<Project, BuildError> {
 
-/// Parcours le contenu des fichiers ".c" en parallèle pour extraire les lignes contenant "#include ".
-fn update_library_list(c_files: &[PathBuf]) -> HashSet<String> {
-    let unique_lines_mutex: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
-    let mut handles: Vec<thread::JoinHandle<()>> = vec![];
-
-    for file_path in c_files {
-        let unique_lines_mutex: Arc<Mutex<HashSet<String>>> = Arc::clone(&unique_lines_mutex);
-
-        // Clonage du chemin de fichier pour que chaque thread possède sa propre copie
-        let file_path: PathBuf = file_path.clone();
-
-        let handle: thread::JoinHandle<()> = thread::spawn(move || {
-            if let Ok(file) = File::open(&file_path) {
-                let reader: io::BufReader<File> = io::BufReader::new(file);
-                for line in reader.lines() {
-                    if let Ok(line) = line {
-                        if line.contains("#include ") {
-                            put_library(&line, &unique_lines_mutex);
-                        }
-                    }
-                }
-            }
-        });
+    let mut project: Project = Project::open(&cli.project_path, &cli.target_path).map_err(|err| match err {
+        BuildError::Discovery(message) => BuildError::Discovery(tr!("main.project_hint", message)),
+        err => err,
+    })?;
 
-        handles.push(handle);
+    if let Some(profile) = &cli.profile {
+        project.settings_mut().set("PROFILE", profile);
     }
-
-    for handle in handles {
-        handle.join().unwrap();
+    if cli.no_cache {
+        project.settings_mut().set("CACHE", "off");
     }
-
-    Arc::try_unwrap(unique_lines_mutex).unwrap().into_inner().unwrap()
-}
-
-/// Traite une ligne contenant "#include " en extrayant le texte inclus.
-fn put_library(line: &str, unique_lines_mutex: &Arc<Mutex<HashSet<String>>>) {
-    // Trouve les positions des guillemets
-    let start_quote = line.find('"');
-    let end_quote = line.rfind('"');
-
-    // Si les guillemets sont présents, stocke le texte entre eux
-    if let (Some(start), Some(end)) = (start_quote, end_quote) {
-        let include_text = line[start + 1..end].to_string();
-        let mut unique_library_files = unique_lines_mutex.lock().unwrap();
-        unique_library_files.insert(include_text);
-    } else {
-        // Sinon, recherche les symboles '<' et '>'
-        let start_bracket = line.find('<');
-        let end_bracket = line.rfind('>');
-
-        // Si les symboles sont présents, stocke le texte entre eux
-        if let (Some(start), Some(end)) = (start_bracket, end_bracket) {
-            let include_text = line[start + 1..end].to_string();
-            let mut unique_library_files = unique_lines_mutex.lock().unwrap();
-            unique_library_files.insert(include_text);
-        }
+    if cli.timings {
+        project.settings_mut().set("TIMINGS", "on");
     }
-}
-
-
-/// Vérifie si les fichiers inclus sont présents dans la liste de fichiers et log les avertissements si nécessaire.
-fn check_all_files(file_type: &str, file_list: &[PathBuf], expected_files: &[PathBuf]) {
-    // Convertit la liste de fichiers en HashSet pour une recherche plus rapide
-    let file_set: HashSet<_> = file_list.iter().collect();
-
-    // Liste des fichiers manquants pour cette extension
-    let mut missing_files: Vec<&PathBuf> = Vec::new();
-
-    // Vérifie si chaque fichier inclus est présent dans la liste
-    for include_file in expected_files {
-        if !file_set.contains(include_file) {
-            missing_files.push(include_file);
-        }
+    if cli.time_report {
+        project.settings_mut().set("TIME_REPORT", "on");
     }
-
-    // Construit le message d'avertissement
-    if !missing_files.is_empty() {
-        let missing_files_str: Vec<_> = missing_files
-            .iter()
-            .map(|path_buf| path_buf.to_string_lossy().to_string())
-            .collect();
-
-        let formatted_time = get_formatted_time();
-        let current_path: PathBuf = std::env::current_dir().expect("Impossible d'obtenir le répertoire actuel");
-
-        let current_folder_name: Option<&str> = current_path.file_name().and_then(|n| n.to_str());
-        let current_folder_name_str: String = current_folder_name.unwrap_or_default().to_string();
-
-        // Construit le message de log complet
-        let log_message = format!(
-            "Project Name : {}\nDate actuelle : {}\nType de fichiers analysés : {}\n\nFichiers attendus :\n\t{:?}\nFichiers trouvés :\n\t{:?}\n",
-            current_folder_name_str,
-            formatted_time,
-            file_type,
-            missing_files_str.join(", "),
-            expected_files
-        );
-
-        write_in_logs(log_message);
+    if let Some(verbosity) = cli.verbosity {
+        project.settings_mut().set("VERBOSITY", verbosity.name());
     }
-}
-
-/// Parcours le contenu des fichiers ".c" en parallèle pour extraire les lignes contenant "#include ".
-fn explore_directory(root_path: &str, file_type: FileType) -> Result<Vec<PathBuf>, io::Error> {
-    let mut result = Vec::new();
-    if let Ok(entries) = fs::read_dir(root_path) {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let entry_path = entry.path();
-                if entry_path.is_file() && entry_path.extension().is_some() {
-                    let file_extension = entry_path.extension().unwrap().to_string_lossy().to_lowercase();
-                    if file_extension == file_type_to_extension(file_type) {
-                        result.push(entry_path.clone());
-                    }
-                } else if entry_path.is_dir() {
-                    result.extend(explore_directory(&entry_path.to_string_lossy(), file_type)?);
-                }
-            }
-        }
+    if let Some(color) = &cli.color {
+        project.settings_mut().set("COLOR", color);
+    }
+    if let Some(language) = cli.language {
+        project.settings_mut().set("LANGUAGE", language.code());
+    }
+    if let Some(message_format) = cli.message_format {
+        project.settings_mut().set("MESSAGE_FORMAT", message_format.name());
     }
-    Ok(result)
-}
-
 
+    Ok(project)
 
-fn write_in_logs(log_message: String) {
+}
 
-    let log_path = format!("{}/{}", get_log_path(), get_log_file());
+/// Lance la commande demandée sur le projet.
+async fn run(project: &Project, cli: &Cli) -> Result<(), BuildError> {
 
-    let mut file = match OpenOptions::new().create(true).append(true).open(&log_path) {
-        Ok(f) => f,
-        Err(err) => {
-            eprintln!("Erreur lors de l'ouverture ou de la création du fichier de log : {}", err);
-            return;
+    match &cli.command {
+        CliCommand::Build => {
+            let inventory: ProjectInventory = project.scan()?;
+            let executable: PathBuf = project.build(inventory).await?;
+            project.context().console().info(&tr!("main.executable", executable.display()));
+            Ok(())
         }
-    };
-
-    if let Err(err) = writeln!(file, "{}", log_message) {
-        eprintln!("Erreur lors de l'écriture dans le fichier de log : {}", err);
-    }
-
-}
-
-/// Divise les lignes uniques en quatre listes en fonction de l'extension.
-fn split_files_by_extension(unique_library_files: &HashSet<String>) -> (Vec<PathBuf>, Vec<PathBuf>, Vec<PathBuf>, Vec<PathBuf>, Vec<PathBuf>) {
-    let mut c_files = Vec::new();
-    let mut h_files = Vec::new();
-    let mut dll_files = Vec::new();
-    let mut a_files = Vec::new();
-    let mut o_files = Vec::new();
-
-    for file in unique_library_files {
-        let file_extension = Path::new(&file)
-            .extension()
-            .map(|ext| ext.to_string_lossy())
-            .unwrap_or_default();
-
-        match file_extension.to_lowercase().as_str() {
-            "c" => c_files.push(PathBuf::from(file)),
-            "h" => h_files.push(PathBuf::from(file)),
-            "dll" => dll_files.push(PathBuf::from(file)),
-            "a" => a_files.push(PathBuf::from(file)),
-            "o" => o_files.push(PathBuf::from(file)),
-            _ => (),
+        CliCommand::Run => {
+            let inventory: ProjectInventory = project.scan()?;
+            project.build(inventory).await?;
+            project.run(&cli.program_args)
+        }
+        CliCommand::Test => {
+            let inventory: ProjectInventory = project.scan()?;
+            project.build(inventory).await?;
+            project.test()
         }
+        CliCommand::Check => {
+            let inventory: ProjectInventory = project.scan()?;
+            project.check(&inventory)
+        }
+        CliCommand::Clean(options) => {
+            let report: CleanReport = project.clean(options)?;
+            let (label, summary): (&str, &str) = if report.dry_run { ("main.would_remove", "main.clean_dry_run") } else { ("main.removed", "main.clean_summary") };
+            for file in &report.files {
+                println!("{}", tr!(label, file.display()));
+            }
+            println!("{}", tr!(summary, report.files.len(), report.size));
+            Ok(())
+        }
+        CliCommand::Export { destination } => {
+            for file in project.export(destination)? {
+                println!("{}", tr!("main.exported", file.display()));
+            }
+            Ok(())
+        }
+        CliCommand::Cache { action } => run_cache_command(project.context(), action),
+        CliCommand::Logs { action, run } => run_logs_command(project.context(), action, *run),
+        CliCommand::Stats { compared } => run_stats_command(project.context(), compared.unwrap_or(DEFAULT_COMPARED_BUILDS)),
+        command => run_snapshot_command(project.context(), command),
     }
 
-    (c_files, h_files, dll_files, a_files, o_files)
 }
 
-/// Mappe les types de fichiers aux extensions correspondantes.
-fn file_type_to_extension(file_type: FileType) -> &'static str {
-    match file_type {
-        FileType::C => "c",
-        FileType::H => "h",
-        FileType::DLL => "dll",
-        FileType::A => "a",
-        FileType::O => "o",
-    }
-}
+/// Compare le dernier build aux builds précédents du même profil et signale les régressions.
+fn run_stats_command(context: &BuildContext, compared: usize) -> Result<(), BuildError> {
+    let history: Vec<BuildRecord> = load_history(Path::new(&context.target_path))
+        .map_err(|err| BuildError::Storage(tr!("main.stats_read_failed", err)))?;
 
-/// Extrait les chemins uniques des fichiers.
-fn extract_unique_paths(paths: &[PathBuf]) -> Vec<String> {
-    let unique_paths: HashSet<_> = paths.iter().flat_map(|path| path.parent().map(|p| p.to_str().unwrap().to_string())).collect();
-    unique_paths.into_iter().collect()
-}
+    let current: &BuildRecord = match history.last() {
+        Some(current) => current,
+        None => {
+            println!("{}", tr!("main.no_build"));
+            return Ok(());
+        }
+    };
 
-fn extract_unique_file_names(paths: &[PathBuf]) -> Vec<String> {
-    let unique_names: HashSet<_> = paths
+    let previous: Vec<&BuildRecord> = history[..history.len() - 1]
         .iter()
-        .filter_map(|path| path.file_name().and_then(|n| n.to_str()).map(|s| s.to_string()))
+        .rev()
+        .filter(|record| record.profile == current.profile)
+        .take(compared)
         .collect();
-    unique_names.into_iter().collect()
-}
-
-fn create_directories() {
-
-    let path: String = format!("{}", get_target_path());
-
-    let directory_paths: Vec<String> = [
-
-        format!("{}/executable", path),
-        format!("{}/source", path),
-        format!("{}/output", path),
-        format!("{}/dll", path),
-        format!("{}/a", path),
-        format!("{}", get_log_path()),
-
-    ].to_vec();
 
-    for directory_path in directory_paths {
-
-        if !Path::new(&directory_path).exists() {
-            if let Err(err) = fs::create_dir_all(&directory_path) {
-                eprintln!("Erreur lors de la création du dossier '{}': {}", directory_path, err);
+    println!("{}\n", tr!("main.stats_header", current.id, current.date, current.profile, previous.len()));
+    println!("  {:<24}{:>14}{:>14}{:>10}", tr!("main.stats_measure"), tr!("main.stats_current"), tr!("main.stats_previous"), tr!("main.stats_change"));
+
+    let comparisons: Vec<Comparison> = compare(current, &previous);
+    for comparison in &comparisons {
+        let value = |value: f64| -> String {
+            match comparison.unit {
+                "ms" => format!("{:.1} ms", value),
+                "o" => format!("{:.0} {}", value, tr!("main.bytes_unit")),
+                _ if value.fract() == 0.0 => format!("{:.0}", value),
+                _ => format!("{:.1}", value),
             }
-        }
+        };
+        let change: String = comparison.change().map(|change| format!("{:+.1} %", change)).unwrap_or_else(|| "-".to_string());
+        let previous_value: String = if previous.is_empty() { "-".to_string() } else { value(comparison.previous) };
+
+        println!(
+            "  {:<24}{:>14}{:>14}{:>10}{}",
+            comparison.label,
+            value(comparison.current),
+            previous_value,
+            change,
+            if comparison.regression { format!("  {}", tr!("main.regression")) } else { String::new() }
+        );
+    }
 
+    let regressions: Vec<&str> = comparisons.iter().filter(|comparison| comparison.regression).map(|comparison| comparison.label).collect();
+    if regressions.is_empty() {
+        println!("\n{}", tr!("main.no_regression"));
+    } else {
+        println!("\n{}", tr!("main.regressions", regressions.len(), regressions.join(", ")));
     }
 
+    Ok(())
 }
 
-fn copy_files_to_directory(files: &[PathBuf], destination_folder: &str) {
-    let destination_path = format!("{}/{}", get_target_path(), destination_folder);
-
-    for file in files {
-        let file_name = file.file_name().and_then(|n| n.to_str()).unwrap_or_default();
-        let destination_file_path = format!("{}/{}", destination_path, file_name);
-        // Vérifie si le fichier existe déjà dans le dossier de destination
-        if !metadata(&destination_file_path).is_ok() {
-            // Copie le fichier vers le dossier de destination
-            if let Err(err) = fs::copy(&file, &destination_file_path) {
-                eprintln!("Erreur lors de la copie du fichier '{}' : {}", file_name, err);
-            }
+/// Exécute une commande de consultation des logs (`list`, `last-failure`, `tail`).
+fn run_logs_command(context: &BuildContext, action: &str, run: Option<u32>) -> Result<(), BuildError> {
+    let runs: Vec<RunLog> = list_runs(Path::new(&context.logs_path));
+    let print_log = |run: &RunLog, lines: usize| -> Result<(), BuildError> {
+        let content: Vec<String> = tail(&run.log_file(), lines)
+            .map_err(|err| BuildError::Storage(tr!("main.log_read_failed", run.log_file().display(), err)))?;
+        println!("{}", tr!("main.run_header", run.id, run.started, status_label(run.status)));
+        for line in content {
+            println!("{}", line);
         }
-    }
-}
-
-fn execute_main() {
-
-    let target_path = get_target_path();
-    
-    let path = format!("{}{}", target_path, "\\executable\\main");
-
-    let mut command = Command::new(path.clone());
+        Ok(())
+    };
 
-    match command.status() {
-        Ok(status) => {
-            if status.success() {
-                println!("\nExécutable '{}' a été exécuté avec succès.", path);
+    match action {
+        "list" => {
+            if runs.is_empty() {
+                println!("{}", tr!("main.no_runs"));
             }
+            for run in &runs {
+                println!("{:>4}  {}  {:<18}  {}", run.id, run.started, status_label(run.status), tr!("main.bytes", run.size));
+            }
+            Ok(())
         }
-        Err(err) => {
-            eprintln!("Erreur lors de l'exécution de l'exécutable '{}': {}", path, err);
-        }
-    }
-}
-
-async fn compile_source_to_output(c_files: &[PathBuf]) -> Result<Vec<PathBuf>, io::Error> {
-    let target_path: String = get_target_path();
-    let mut output_files: Vec<PathBuf> = Vec::new();
-
-    for c_file in c_files {
-        
-        let path: String = format!("{}{}", target_path, "\\output");
-        let mut output_file: PathBuf = PathBuf::from(path);
-
-        output_file.push(c_file.file_name().unwrap());
-        output_file.set_extension("o");
-
-        let c_file_str: String = c_file.to_str().unwrap().replace("\\", "/");
-        let output_file_str: String = output_file.to_str().unwrap().replace("\\", "/");
-
-        let compile_result = compile_single_source_to_output(&c_file_str, &output_file_str);
-        match compile_result {
-            Ok(_) => output_files.push(output_file),
-            Err(err) => eprintln!("Erreur lors de la compilation du fichier {:?}: {}", c_file, err),
+        "last-failure" => match last_failure(Path::new(&context.logs_path)) {
+            Some(run) => print_log(&run, usize::MAX),
+            None => {
+                println!("{}", tr!("main.no_failed_run"));
+                Ok(())
+            }
+        },
+        "tail" => {
+            let selected: Option<&RunLog> = match run {
+                Some(id) => runs.iter().find(|candidate| candidate.id == id),
+                None => runs.last(),
+            };
+            match selected {
+                Some(run) => print_log(run, TAIL_LINES),
+                None => Err(BuildError::Storage(match run {
+                    Some(id) => tr!("main.run_not_found", id),
+                    None => tr!("main.no_runs_error"),
+                })),
+            }
         }
+        _ => Err(BuildError::Usage(tr!("cli.unknown_logs_action", action))),
     }
-
-    Ok(output_files)
 }
 
-fn compile_single_source_to_output(source_file: &str, output_file: &str) -> Result<(), String> {
-    let output = Command::new("gcc")
-        .args(&[source_file, "-c", "-o", output_file])
-        .output()
-        .map_err(|err| format!("Erreur lors de la compilation : {}", err))?;
-
-    if output.status.success() {
-        Ok(())
-    } else {
-        Err(format!(
-            "La compilation a échoué. Erreur : {}\nSortie de la commande : {}",
-            String::from_utf8_lossy(&output.stderr),
-            String::from_utf8_lossy(&output.stdout),
-        ))
+/// Libellé du résultat d'une exécution.
+fn status_label(status: RunStatus) -> String {
+    match status {
+        RunStatus::Success => tr!("main.status_success"),
+        RunStatus::Failure { exit_code } => tr!("main.status_failure", exit_code),
+        RunStatus::Unfinished => tr!("main.status_unfinished"),
     }
 }
 
-async fn compile_output_to_executable(o_files: Vec<PathBuf>, include_paths: Vec<String>, library_paths: Vec<String>, libraries: Vec<String>) -> Result<Vec<u8>, std::io::Error> {
-
-    let mut command: Command = Command::new("gcc");
-    let target_path: String = get_target_path();
-    let path_exe: String = format!("{}{}", target_path, "\\executable\\main.exe");
-
-    command.args(&["-o", &path_exe]).args(o_files);
-
-    for include_path in &include_paths {
-        command.args(&["-I", include_path]);
-    }
-
-    // Ajouter les chemins des bibliothèques (-L)
-    for library_path in &library_paths {
-        command.args(&["-L", library_path]);
-    }
-
-    // Ajouter les bibliothèques à lier (-l)
-    for library in &libraries {
-
-        let library_name = 
-            if library.ends_with(".dll") {  &library[3..library.len() - 4] } 
-            else {  library };
-
-        command.args(&["-l", library_name]);
-    }
-
-    command.args(&["-lm", "-Wall"]);
-    
-    write_in_logs(
-        format!(
-            "Commande réalisée pour l'exécution du projet : \n\t{:?}\n", 
-            command
-        )
-    );
-
-    command.stdout(Stdio::piped()).stderr(Stdio::piped());
-
-    let child: std::process::Child = command.spawn()?;
-    let output: Output = child.wait_with_output()?;
-
-    println!("\nSortie de la commande :{}", String::from_utf8_lossy(&output.stdout));
+/// Exécute une commande de l'historique des instantanés (`history`, `diff`, `restore`).
+fn run_snapshot_command(context: &BuildContext, command: &CliCommand) -> Result<(), BuildError> {
+    let target_path: String = context.target_path.clone();
+    let target_path: &Path = Path::new(&target_path);
+    let load = |id: u32| -> Result<Snapshot, BuildError> { load_snapshot(target_path, id).map_err(|err| BuildError::Storage(err.to_string())) };
+
+    match command {
+        CliCommand::History => match list_snapshots(target_path) {
+            Ok(snapshots) if snapshots.is_empty() => println!("{}", tr!("main.no_snapshots")),
+            Ok(snapshots) => {
+                for snapshot in snapshots {
+                    let executable: Option<&str> = snapshot
+                        .files
+                        .iter()
+                        .find(|file| file.path.starts_with("executable"))
+                        .map(|file| &file.hash[..12]);
+
+                    println!(
+                        "{:>4}  {}  {}",
+                        snapshot.id,
+                        snapshot.date,
+                        tr!("main.snapshot_line", snapshot.files.len(), snapshot.total_size(), executable.unwrap_or("-"))
+                    );
+                }
+            }
+            Err(err) => return Err(BuildError::Storage(tr!("main.history_read_failed", err))),
+        },
+        CliCommand::Diff { old, new } => {
+            let diff: SnapshotDiff = diff_snapshots(&load(*old)?, &load(*new)?);
+
+            for (label, files) in [("+", &diff.added), ("-", &diff.removed), ("~", &diff.modified)] {
+                for file in files {
+                    println!("{} {}", label, file.display());
+                }
+            }
+            println!("{}", tr!("main.diff_summary", diff.added.len(), diff.removed.len(), diff.modified.len()));
+        }
+        CliCommand::Restore { id } => {
+            let snapshot: Snapshot = load(*id)?;
 
-    if !output.status.success() {
-        eprintln!("Erreur lors de l'exécution du main, Erreur, la commande a échoué :\n{}", String::from_utf8_lossy(&output.stderr));
+            match restore_snapshot(target_path, &snapshot) {
+                Ok(_) => {
+                    context.write_in_logs(tr!("main.restored_log", snapshot.id, snapshot.files.len()));
+                    println!("{}", tr!("main.restored", snapshot.id, snapshot.date, target_path.display()));
+                }
+                Err(err) => return Err(BuildError::Storage(tr!("main.restore_failed", err))),
+            }
+        }
+        _ => (),
     }
 
-    Ok(output.stdout)
+    Ok(())
 }
 
-async fn build_source(c_files: &[PathBuf]) -> Vec<PathBuf> {
-    if let Ok(output) = compile_source_to_output(c_files).await { return output  }
-    Vec::<PathBuf>::new()
-}
+/// Exécute une commande du cache de compilation (`stats`, `clear`).
+fn run_cache_command(context: &BuildContext, command: &str) -> Result<(), BuildError> {
+    let cache: CompileCache = match CompileCache::from_settings(&context.settings) {
+        Some(cache) => cache,
+        None => {
+            println!("{}", tr!("main.cache_disabled"));
+            return Ok(());
+        }
+    };
 
+    match command {
+        "stats" => match cache.stats() {
+            Ok(stats) => {
+                let CacheStats { entries, size, max_size, hits, misses } = stats;
+                let hit_rate: f64 = if hits + misses > 0 { hits as f64 * 100.0 / (hits + misses) as f64 } else { 0.0 };
 
-async fn build_execute(o_files: Vec<PathBuf>, include_paths: Vec<String>, library_paths: Vec<String>, libraries: Vec<String>) {
+                println!("{}", tr!("main.cache_directory", cache.root.display()));
+                println!("{}", tr!("main.cache_entries", entries));
+                println!("{}", tr!("main.cache_size", size, max_size));
+                println!("{}", tr!("main.cache_hits", hits, misses, format!("{:.1}", hit_rate)));
+            }
+            Err(err) => return Err(BuildError::Storage(tr!("main.cache_read_failed", err))),
+        },
+        "clear" => match cache.clear() {
+            Ok(count) => println!("{}", tr!("main.cache_cleared", count)),
+            Err(err) => return Err(BuildError::Storage(tr!("main.cache_clear_failed", err))),
+        },
+        _ => return Err(BuildError::Usage(tr!("cli.unknown_cache_action", command))),
+    }
 
-    let _ = compile_output_to_executable(o_files, include_paths, library_paths, libraries).await;
-    
+    Ok(())
 }
@@ -0,0 +1,897 @@
+//! Catalogue des messages affichés, écrits dans les logs et dans les rapports, en français et en anglais.
+//!
+//! Chaque message est désigné par une clé et ses paramètres `{}` sont remplacés dans l'ordre :
+//!
+//! ```
+//! use compilerc::tr;
+//! let message: String = tr!("clean.summary", 3, 1024);
+//! ```
+//!
+//! La langue est celle du projet : chaque opération d'un [`Project`](crate::Project) s'exécute dans la langue
+//! de sa clé `LANGUAGE` (`fr` ou `en`, voir [`BuildContext::language`](crate::BuildContext::language)), y compris
+//! dans les threads qu'elle lance. Hors d'une opération, ce sont les variables d'environnement `LC_ALL`,
+//! `LC_MESSAGES` puis `LANG` qui décident ; le français reste la langue par défaut.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Langue des messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    French,
+    English,
+}
+
+impl Language {
+    /// Codes acceptés par `LANGUAGE`.
+    pub const NAMES: [&'static str; 2] = ["fr", "en"];
+
+    /// Lit un code de langue (`fr`, `en`) ou une locale (`fr_FR.UTF-8`, `en_US`).
+    pub fn from_name(name: &str) -> Option<Language> {
+        let name: String = name.trim().to_lowercase();
+        if name.starts_with("fr") {
+            Some(Language::French)
+        } else if name.starts_with("en") {
+            Some(Language::English)
+        } else {
+            None
+        }
+    }
+
+    /// Code de la langue, utilisé aussi pour l'attribut `lang` des rapports HTML.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::French => "fr",
+            Language::English => "en",
+        }
+    }
+
+    /// Langue des variables d'environnement `LC_ALL`, `LC_MESSAGES` puis `LANG`.
+    pub fn from_environment() -> Option<Language> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|variable| env::var(variable).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Language::from_name(&value))
+    }
+
+    fn catalog(&self) -> &'static HashMap<&'static str, &'static str> {
+        static FRENCH_CATALOG: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+        static ENGLISH_CATALOG: OnceLock<HashMap<&str, &str>> = OnceLock::new();
+
+        match self {
+            Language::French => FRENCH_CATALOG.get_or_init(|| FRENCH.iter().copied().collect()),
+            Language::English => ENGLISH_CATALOG.get_or_init(|| ENGLISH.iter().copied().collect()),
+        }
+    }
+}
+
+thread_local! {
+    /// Langue de l'opération en cours sur ce thread, posée par [`LanguageScope`].
+    static ACTIVE_LANGUAGE: Cell<Option<Language>> = const { Cell::new(None) };
+}
+
+/// Langue des messages : celle de l'opération en cours sur ce thread, sinon celle de l'environnement.
+pub fn language() -> Language {
+    static ENVIRONMENT_LANGUAGE: OnceLock<Language> = OnceLock::new();
+    ACTIVE_LANGUAGE.get().unwrap_or_else(|| *ENVIRONMENT_LANGUAGE.get_or_init(|| Language::from_environment().unwrap_or_default()))
+}
+
+/// Langue des messages du thread courant jusqu'à la fin de la portée ; la langue précédente est ensuite rétablie.
+///
+/// Un thread lancé pendant la portée ne l'hérite pas : il doit entrer lui-même dans la langue de son parent.
+#[must_use]
+#[derive(Debug)]
+pub struct LanguageScope {
+    previous: Option<Language>,
+}
+
+impl LanguageScope {
+    pub fn enter(language: Language) -> LanguageScope {
+        LanguageScope { previous: ACTIVE_LANGUAGE.replace(Some(language)) }
+    }
+}
+
+impl Drop for LanguageScope {
+    fn drop(&mut self) {
+        ACTIVE_LANGUAGE.set(self.previous);
+    }
+}
+
+/// Texte d'un message dans la langue courante ; la clé elle-même si elle est inconnue.
+pub fn text(key: &str) -> &str {
+    language().catalog().get(key).copied().or_else(|| Language::French.catalog().get(key).copied()).unwrap_or(key)
+}
+
+/// Message de la langue courante avec ses paramètres `{}` remplacés dans l'ordre.
+pub fn format_message(key: &str, arguments: &[&dyn Display]) -> String {
+    let template: &str = text(key);
+    let mut message: String = String::with_capacity(template.len());
+    let mut arguments = arguments.iter();
+    let mut rest: &str = template;
+
+    while let Some(start) = rest.find("{}") {
+        message.push_str(&rest[..start]);
+        match arguments.next() {
+            Some(argument) => message.push_str(&argument.to_string()),
+            None => message.push_str("{}"),
+        }
+        rest = &rest[start + 2..];
+    }
+    message.push_str(rest);
+    message
+}
+
+/// Message traduit à partir de sa clé, suivie de ses paramètres éventuels.
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::messages::text($key).to_string()
+    };
+    ($key:expr, $($argument:expr),+ $(,)?) => {
+        $crate::messages::format_message($key, &[$(&$argument as &dyn ::std::fmt::Display),+])
+    };
+}
+
+const FRENCH: &[(&str, &str)] = &[
+    ("error.usage", "utilisation incorrecte : {}"),
+    ("error.config", "configuration invalide : {}"),
+    ("error.discovery", "recherche des fichiers impossible : {}"),
+    ("error.link", "édition de liens échouée : {}"),
+    ("error.run", "exécution échouée : {}"),
+    ("error.hook", "hook en échec : {}"),
+    ("error.storage", "stockage inaccessible : {}"),
+    ("error.compile", "compilation échouée pour {} fichier(s)"),
+
+    ("timings.compile", "Compilation : {} source(s) en {} ({} depuis le cache)"),
+    ("timings.link", "Édition de liens : {}"),
+    ("timings.slowest", "Sources les plus lentes :"),
+    ("timings.cached", "cache"),
+    ("timings.phases", "Phases du compilateur (-ftime-report, temps réel cumulé) :"),
+
+    ("stats.compile", "Compilation"),
+    ("stats.link", "Édition de liens"),
+    ("stats.total", "Durée totale"),
+    ("stats.warnings", "Avertissements"),
+    ("stats.executable_size", "Taille de l'exécutable"),
+    ("stats.objects_size", "Taille des objets"),
+    ("stats.sources", "Sources"),
+    ("stats.cached", "Sources en cache"),
+
+    ("cache.remote_ignored", "Cache distant ignoré : {}"),
+    ("cache.remote_read_failed", "Cache distant : lecture de {} impossible : {}"),
+    ("cache.remote_not_kept", "Entrée distante {} non conservée : {}"),
+    ("cache.remote_upload_failed", "Envoi au cache distant impossible pour {} : {}"),
+
+    ("clean.failed", "nettoyage impossible : {}"),
+    ("clean.target_contains_project", "nettoyage refusé : la cible '{}' contient le projet '{}'"),
+    ("clean.outside_target", "nettoyage refusé : '{}' est en dehors de la cible '{}'"),
+    ("clean.summary", "Nettoyage de la cible : {} fichier(s), {} octets supprimés"),
+
+    ("context.journal_write_failed", "Erreur lors de l'écriture dans le journal des événements : {}"),
+    ("context.journal_open_failed", "Erreur lors de l'ouverture ou de la création du journal des événements : {}"),
+    ("context.log_open_failed", "Erreur lors de l'ouverture ou de la création du fichier de log : {}"),
+    ("context.log_write_failed", "Erreur lors de l'écriture dans le fichier de log : {}"),
+    ("context.run_reservation_failed", "réservation du dossier de l'exécution impossible : {}"),
+
+    ("hooks.started", "Hook {} : {}"),
+    ("hooks.launch_failed", "impossible de lancer le hook {} '{}' : {}"),
+    ("hooks.stdout", "Sortie du hook :\n{}"),
+    ("hooks.stderr", "Erreurs du hook :\n{}"),
+    ("hooks.failed", "le hook {} '{}' a échoué ({})"),
+    ("hooks.failed_log", "Échec : {}\nSortie du hook :\n{}"),
+
+    ("ignore.settings_rule", "IGNORE '{}' de settings.txt"),
+    ("ignore.file_rule", "'{}' de {} (ligne {})"),
+    ("ignore.directive_rule", "// EXCLUDE: '{}' dans {}"),
+
+    ("resources.up_to_date", "Ressource à jour : {}"),
+    ("resources.unreadable", "Ressource illisible '{}' : {}"),
+    ("resources.generated", "Ressource générée : {} -> {} ({} octets)"),
+    ("resources.symbol_collision", "'{}' et '{}' produisent le même symbole C '{}' : renommez l'un des fichiers"),
+
+    ("scanner.project_unreachable", "dossier du projet inaccessible ({})"),
+    ("scanner.symlink_loop", "lien symbolique vers un dossier déjà parcouru ({}), boucle ignorée"),
+    ("scanner.unresolvable", "chemin impossible à résoudre ({})"),
+    ("scanner.unreadable_directory", "dossier illisible ({})"),
+    ("scanner.unreadable_entry", "entrée illisible ({})"),
+    ("scanner.unreachable_file", "fichier inaccessible ({})"),
+
+    ("snapshots.not_found", "instantané {} introuvable ({})"),
+    ("snapshots.invalid_manifest", "manifeste de l'instantané {} invalide"),
+    ("snapshots.missing_content", "contenu de '{}' absent du stockage ({})"),
+
+    ("sync.summary", "Synchronisation de {} : {} ajouté(s), {} mis à jour, {} supprimé(s)"),
+
+    ("project.missing", "le dossier du projet '{}' n'existe pas"),
+    ("project.target_not_directory", "la cible '{}' n'est pas un dossier"),
+
+    ("console.compiling", "[{}/{}] Compilation de {}"),
+    ("console.linking", "Édition de liens de {} objet(s)"),
+
+    ("init.already_initialized", "'{}' existe déjà, le projet est déjà initialisé"),
+    ("init.duplicate_file", "Le modèle écrit deux fois '{}'"),
+
+    ("pipeline.logs_removed", "Logs : {} exécution(s) ancienne(s) supprimée(s)"),
+    ("pipeline.logs_removal_failed", "Erreur lors de la suppression des anciens logs : {}"),
+    ("pipeline.skipped", "Ignoré : {} (règle {})"),
+    ("pipeline.scan_error", "Erreur lors de la recherche des fichiers : {}"),
+    ("pipeline.no_sources", "aucun fichier .c ou .o trouvé dans '{}'"),
+    ("pipeline.resources_failed", "génération des ressources impossible : {}"),
+    ("pipeline.sync_disabled", "Synchronisation des sources désactivée (SYNC : off)"),
+    ("pipeline.snapshot_created", "Instantané {} enregistré ({} fichiers)"),
+    ("pipeline.snapshot_failed", "Erreur lors de l'enregistrement de l'instantané : {}"),
+    ("pipeline.build_times_log", "Temps d'exécution : {} secondes\nNombre de fichiers traités : {}\n\nTemps d'exécution Total : {} secondes"),
+    ("pipeline.build_times", "{} fichier(s) traité(s) en {} secondes, build terminé en {} secondes"),
+    ("pipeline.record_saved", "Mesures du build {} enregistrées"),
+    ("pipeline.record_failed", "Erreur lors de l'enregistrement des mesures du build : {}"),
+    ("pipeline.timings", "Durées du build :\n{}"),
+    ("pipeline.gcc_not_found", "gcc introuvable : {}"),
+    ("pipeline.syntax_error", "Erreur de syntaxe dans {} :\n{}"),
+    ("pipeline.check_summary", "{} fichier(s) vérifié(s), {} en erreur"),
+    ("pipeline.test_passed", "test {} ... ok"),
+    ("pipeline.test_failed", "test {} ... ÉCHEC ({})"),
+    ("pipeline.test_summary", "{} test(s) : {} réussi(s), {} en échec"),
+    ("pipeline.tests_failed", "tests en échec : {}"),
+    ("pipeline.no_executable", "aucun exécutable dans '{}', lancez d'abord la commande build"),
+    ("pipeline.export_failed", "export vers '{}' impossible : {}"),
+    ("pipeline.exported", "Export de {} fichier(s) vers {}"),
+    ("pipeline.file_skipped", "Fichier ignoré : {} (règle {})"),
+    ("pipeline.missing_files", "Project Name : {}\nDate actuelle : {}\nType de fichiers analysés : {}\n\nFichiers attendus :\n\t{}\nFichiers trouvés :\n\t{}\n"),
+    ("pipeline.no_current_dir", "Impossible d'obtenir le répertoire actuel"),
+    ("pipeline.create_directory_failed", "Erreur lors de la création du dossier '{}' : {}"),
+    ("pipeline.sync_summary", "{} : {} ajouté(s), {} mis à jour, {} supprimé(s)"),
+    ("pipeline.sync_failed", "Erreur lors de la synchronisation de '{}' : {}"),
+    ("pipeline.collision", "\t{} et {} -> {}"),
+    ("pipeline.collisions", "plusieurs fichiers produisent le même fichier objet :\n{}"),
+    ("pipeline.run_duration_log", "Durée d'exécution de '{}' : {}"),
+    ("pipeline.run_duration", "Durée d'exécution : {}"),
+    ("pipeline.run_succeeded", "Exécutable '{}' exécuté avec succès."),
+    ("pipeline.run_failed", "'{}' s'est terminé en erreur ({})"),
+    ("pipeline.run_launch_failed", "impossible de lancer '{}' : {}"),
+    ("pipeline.create_failed", "création de '{}' impossible : {}"),
+    ("pipeline.compile_failed", "Erreur lors de la compilation de {} :"),
+    ("pipeline.compile_failed_log", "Erreur lors de la compilation du fichier {} : {}"),
+    ("pipeline.cache_copy_failed", "Erreur lors de la copie depuis le cache : {}"),
+    ("pipeline.remote_cache", "cache distant"),
+    ("pipeline.local_cache", "cache"),
+    ("pipeline.cache_hit", "Cache : {} récupéré depuis le {} ({})"),
+    ("pipeline.worker_object_failed", "Erreur lors de l'écriture de l'objet reçu : {}"),
+    ("pipeline.worker_compiled", "Worker {} : {} compilé"),
+    ("pipeline.worker_compile_failed", "La compilation a échoué sur le worker {}. Erreur : {}"),
+    ("pipeline.worker_unavailable", "Worker {} indisponible pour {}, compilation locale : {}"),
+    ("pipeline.compile_error", "Erreur lors de la compilation : {}"),
+    ("pipeline.compile_output", "La compilation a échoué. Erreur :\n{}\nSortie de la commande : {}"),
+    ("pipeline.cache_store_failed", "Erreur lors de l'écriture dans le cache : {}"),
+    ("pipeline.warnings", "Avertissements pour {} :\n{}"),
+    ("pipeline.link_command", "Commande réalisée pour l'exécution du projet : \n\t{}\n"),
+    ("pipeline.link_failed", "Erreur lors de l'édition de liens :"),
+    ("pipeline.link_status", "l'édition de liens a échoué ({})"),
+    ("pipeline.non_utf8_path", "chemin non UTF-8, impossible à transmettre à gcc : {}"),
+    ("pipeline.snapshots_removed", "Instantanés : {} instantané(s) ancien(s) supprimé(s)"),
+    ("pipeline.snapshots_removal_failed", "Erreur lors de la suppression des anciens instantanés : {}"),
+    ("pipeline.cache_finish_failed", "Erreur lors de la mise à jour du cache : {}"),
+
+    ("main.result_success", "Résultat : succès en {} secondes"),
+    ("main.result_failure", "Résultat : échec en {} secondes (code {}) - {}"),
+    ("main.report", "Rapport : {}"),
+    ("main.report_failed", "Erreur lors de l'écriture du rapport HTML : {}"),
+    ("main.created", "Créé : {}"),
+    ("main.initialized", "Projet initialisé dans '{}' (modèle {})"),
+    ("main.init_failed", "création du projet impossible : {}"),
+    ("main.project_hint", "{} (dossier du projet : option --project)"),
+    ("main.executable", "Exécutable : {}"),
+    ("main.would_remove", "Serait supprimé : {}"),
+    ("main.removed", "Supprimé : {}"),
+    ("main.clean_summary", "{} fichier(s), {} octets"),
+    ("main.clean_dry_run", "{} fichier(s), {} octets (simulation, rien n'a été supprimé)"),
+    ("main.exported", "Exporté : {}"),
+    ("main.stats_read_failed", "lecture des mesures impossible : {}"),
+    ("main.no_build", "Aucun build enregistré, lancez d'abord la commande build."),
+    ("main.stats_header", "Build {} du {} (profil {}), comparé à la moyenne de {} build(s) précédent(s)"),
+    ("main.stats_measure", "Mesure"),
+    ("main.stats_current", "Actuel"),
+    ("main.stats_previous", "Précédents"),
+    ("main.stats_change", "Écart"),
+    ("main.bytes_unit", "o"),
+    ("main.regression", "régression"),
+    ("main.no_regression", "Aucune régression."),
+    ("main.regressions", "{} régression(s) : {}"),
+    ("main.log_read_failed", "lecture de '{}' impossible : {}"),
+    ("main.run_header", "Exécution {} du {} ({})"),
+    ("main.no_runs", "Aucune exécution enregistrée."),
+    ("main.bytes", "{} octets"),
+    ("main.no_failed_run", "Aucune exécution en échec."),
+    ("main.run_not_found", "exécution {} introuvable"),
+    ("main.no_runs_error", "aucune exécution enregistrée"),
+    ("main.status_success", "succès"),
+    ("main.status_failure", "échec (code {})"),
+    ("main.status_unfinished", "inachevée"),
+    ("main.no_snapshots", "Aucun instantané enregistré."),
+    ("main.snapshot_line", "{} fichiers  {} octets  exécutable {}"),
+    ("main.history_read_failed", "lecture de l'historique impossible : {}"),
+    ("main.diff_summary", "{} ajouté(s), {} supprimé(s), {} modifié(s)"),
+    ("main.restored_log", "Instantané {} restauré ({} fichiers)"),
+    ("main.restored", "Instantané {} du {} restauré dans {}"),
+    ("main.restore_failed", "restauration impossible : {}"),
+    ("main.cache_disabled", "Cache de compilation désactivé (CACHE : off)."),
+    ("main.cache_directory", "Dossier : {}"),
+    ("main.cache_entries", "Entrées : {}"),
+    ("main.cache_size", "Taille : {} / {} octets"),
+    ("main.cache_hits", "Succès : {}  Échecs : {}  ({} %)"),
+    ("main.cache_read_failed", "lecture du cache impossible : {}"),
+    ("main.cache_cleared", "Cache vidé : {} entrée(s) supprimée(s)."),
+    ("main.cache_clear_failed", "impossible de vider le cache : {}"),
+
+    ("cli.unknown_logs_action", "action de logs inconnue '{}' (list, last-failure, tail)"),
+    ("cli.unknown_cache_action", "action de cache inconnue '{}' (stats, clear)"),
+    ("cli.missing_value", "l'option {} attend une valeur"),
+    ("cli.quiet_and_verbose", "les options --quiet et --verbose sont incompatibles"),
+    ("cli.unknown_color", "mode de couleur inconnu '{}' (auto, always, never)"),
+    ("cli.invalid_age", "durée invalide '{}' (exemples : 30d, 12h, 2w)"),
+    ("cli.unknown_option", "option inconnue '{}' (voir main --help)"),
+    ("cli.clean_only", "l'option {} n'est acceptée que par 'clean'"),
+    ("cli.run_only", "les arguments après '--' ne sont acceptés que par 'run'"),
+    ("cli.missing_command", "commande manquante\n\n{}"),
+    ("cli.unexpected_argument", "argument inattendu '{}' pour '{}'"),
+    ("cli.missing_arguments", "arguments manquants, utilisation : main {}"),
+    ("cli.invalid_snapshot", "identifiant d'instantané invalide '{}'"),
+    ("cli.unknown_template", "modèle de projet inconnu '{}' ({})"),
+    ("cli.invalid_run", "identifiant d'exécution invalide '{}'"),
+    ("cli.invalid_build_count", "nombre de builds invalide '{}'"),
+    ("cli.unknown_command", "commande inconnue '{}' (voir main --help)"),
+    ("cli.unknown_message_format", "format de sortie inconnu '{}' ({})"),
+    ("cli.unknown_language", "langue inconnue '{}' ({})"),
+
+    ("help.build", "compile et lie le projet"),
+    ("help.run", "compile le projet puis lance l'exécutable"),
+    ("help.test", "compile le projet puis lance les tests (clé TEST de settings.txt)"),
+    ("help.clean", "supprime les fichiers produits dans la cible"),
+    ("help.check", "vérifie la syntaxe des sources sans produire de fichier"),
+    ("help.init", "crée un projet C (executable, static, shared, tests)"),
+    ("help.export", "copie l'exécutable et ses bibliothèques dans un dossier"),
+    ("help.history", "liste les instantanés des builds réussis"),
+    ("help.diff", "compare deux instantanés"),
+    ("help.restore", "remet la cible dans l'état d'un instantané"),
+    ("help.cache", "statistiques ou vidage du cache de compilation"),
+    ("help.logs", "logs des exécutions : list, last-failure, tail [id]"),
+    ("help.stats", "compare le dernier build aux n builds précédents (5 par défaut)"),
+    ("help.worker", "lance un worker de compilation distribuée"),
+    ("help.options", "Options :\n  -p, --project <dossier>   dossier du projet C (par défaut : dossier courant)\n  -t, --target <dossier>    dossier cible (par défaut : ./build)\n      --profile <nom>       profil de compilation (remplace PROFILE)\n      --no-cache            désactive le cache de compilation\n      --timings             affiche les sources les plus lentes et les durées du build\n      --time-report         mesure les phases de gcc (-ftime-report)\n  -q, --quiet               n'affiche que les diagnostics et les erreurs\n  -v, --verbose             affiche les lignes de commande complètes\n      --color <mode>        couleurs : auto (par défaut), always ou never\n      --message-format <f>  sortie : human (par défaut), short (fichier:ligne:colonne) ou json\n      --language <code>     langue des messages : fr ou en (remplace LANGUAGE)\n  -h, --help                affiche l'aide\n  -V, --version             affiche la version"),
+    ("help.clean_options", "Portées de clean (par défaut : tout ce que produit le build, logs et historique exclus) :\n      --objects             fichiers objets (output/<profil>)\n      --executables         exécutables (executable/<profil>)\n      --sources             sources copiées et ressources générées\n      --libraries           bibliothèques (dll, a)\n      --logs                logs des builds\n      --all                 toutes les portées, logs et historique compris\n      --profile <nom>       limite objets et exécutables à un profil\n      --older-than <durée>  seulement les fichiers plus anciens (30d, 12h, 2w)\n  -n, --dry-run             liste les fichiers sans les supprimer"),
+    ("help.usage", "Utilisation : main [options] {}"),
+    ("help.command_placeholder", "<commande> [arguments]"),
+    ("help.commands", "Commandes :"),
+    ("help.build_usage", "build"),
+    ("help.run_usage", "run [-- arguments]"),
+    ("help.test_usage", "test"),
+    ("help.clean_usage", "clean [portées]"),
+    ("help.check_usage", "check"),
+    ("help.init_usage", "init [modèle]"),
+    ("help.export_usage", "export <dossier>"),
+    ("help.history_usage", "history"),
+    ("help.diff_usage", "diff <a> <b>"),
+    ("help.restore_usage", "restore <id>"),
+    ("help.cache_usage", "cache [stats|clear]"),
+    ("help.logs_usage", "logs [action] [id]"),
+    ("help.stats_usage", "stats [n]"),
+    ("help.worker_usage", "worker [adresse]"),
+
+    ("report.title", "Rapport du build {} - {}"),
+    ("report.run", "exécution {}"),
+    ("report.success", "succès"),
+    ("report.failure", "échec (code {}) - {}"),
+    ("report.unfinished", "non terminé"),
+    ("report.duration", "Durée"),
+    ("report.profile", "Profil"),
+    ("report.project", "Projet"),
+    ("report.target", "Cible"),
+    ("report.summary", "Résumé"),
+    ("report.result", "Résultat"),
+    ("report.inventory", "Inventaire"),
+    ("report.includes", "Graphe des inclusions"),
+    ("report.commands", "Commandes"),
+    ("report.diagnostics", "Diagnostics"),
+    ("report.durations", "Durées"),
+    ("report.artifacts", "Fichiers produits"),
+    ("report.no_files", "Aucun fichier inventorié."),
+    ("report.no_sources", "Aucune source inventoriée."),
+    ("report.no_commands", "Aucune commande lancée."),
+    ("report.no_diagnostics", "Aucun diagnostic."),
+    ("report.no_compilation", "Aucune compilation."),
+    ("report.no_artifacts", "Aucun fichier produit."),
+    ("report.file_count", "{} fichier(s)"),
+    ("report.file", "Fichier"),
+    ("report.includes_column", "Inclut"),
+    ("report.included_by", "Inclus par"),
+    ("report.command", "Commande"),
+    ("report.status", "Statut"),
+    ("report.diagnostic_count", "{} diagnostic(s)"),
+    ("report.location", "ligne {}, colonne {}"),
+    ("report.other_messages", "autres messages"),
+    ("report.compile_total", "Compilation : {} source(s) en {}"),
+    ("report.link_total", "édition de liens : {}"),
+    ("report.source", "Source"),
+    ("report.size", "Taille"),
+    ("report.bytes", "{} o"),
+    ("report.total", "Total"),
+
+    ("worker.connect_failed", "connexion au worker '{}' impossible : {}"),
+    ("worker.send_failed", "envoi au worker '{}' impossible : {}"),
+    ("worker.unreadable_response", "réponse du worker '{}' illisible : {}"),
+    ("worker.truncated_response", "réponse du worker '{}' tronquée"),
+    ("worker.listen_failed", "écoute sur '{}' impossible : {}"),
+    ("worker.listening", "Worker en attente de tâches sur {}"),
+    ("worker.task_dropped", "Tâche de {} abandonnée : {}"),
+    ("worker.connection_refused", "Connexion refusée : {}"),
+    ("worker.unreadable_request", "requête illisible : {}"),
+    ("worker.truncated_request", "requête tronquée"),
+    ("worker.refused_option", "option refusée par le worker : {}"),
+    ("worker.gcc_not_found", "gcc introuvable sur le worker : {}"),
+    ("worker.result_failed", "envoi du résultat impossible : {}"),
+    ("worker.incomplete_message", "message incomplet"),
+    ("worker.unknown_format", "message de format inconnu"),
+    ("worker.not_found_error", "worker '{}' introuvable : {}"),
+    ("worker.not_found", "worker '{}' introuvable"),
+    ("worker.compiled", "{} : compilé"),
+    ("worker.failed", "{} : échec"),
+    ("worker.token_required", "écoute sur {} refusée sans jeton : définissez {} ou écoutez sur 127.0.0.1"),
+    ("worker.invalid_token", "jeton absent ou invalide"),
+    ("worker.busy", "connexion de {} refusée : {} tâches déjà en cours"),
+    ("worker.request_too_large", "requête de plus de {} octets refusée"),
+    ("worker.missing_field", "champ '{}' manquant dans le message"),
+
+    ("remote_cache.unsupported_url", "adresse de cache distant non prise en charge '{}' (http:// attendu)"),
+    ("remote_cache.invalid_port", "port invalide dans '{}'"),
+    ("remote_cache.missing_host", "hôte manquant dans '{}'"),
+    ("remote_cache.http_status", "réponse HTTP {} du cache distant"),
+    ("remote_cache.upload_status", "réponse HTTP {} du cache distant à l'envoi"),
+    ("remote_cache.connect_failed", "connexion à {} impossible : {}"),
+    ("remote_cache.send_failed", "envoi de la requête impossible : {}"),
+    ("remote_cache.read_failed", "lecture de la réponse impossible : {}"),
+    ("remote_cache.invalid_status_line", "ligne de statut HTTP invalide"),
+    ("remote_cache.invalid_chunk_size", "taille de bloc HTTP invalide"),
+    ("remote_cache.invalid_entry", "entrée du cache distant invalide"),
+    ("remote_cache.unknown_format", "format d'entrée du cache distant inconnu"),
+    ("remote_cache.wrong_size", "taille de l'entrée du cache distant incorrecte"),
+    ("remote_cache.invalid_hash", "empreinte de l'entrée du cache distant invalide"),
+    ("remote_cache.host_not_found", "hôte '{}' introuvable : {}"),
+    ("remote_cache.no_address", "hôte '{}' introuvable"),
+    ("remote_cache.incomplete_response", "réponse HTTP incomplète"),
+    ("remote_cache.truncated_response", "réponse HTTP tronquée"),
+    ("remote_cache.truncated_chunk", "bloc HTTP tronqué"),
+    ("remote_cache.unterminated_chunk", "bloc HTTP plus long que sa taille annoncée"),
+    ("remote_cache.invalid_chunk", "bloc HTTP invalide"),
+    ("remote_cache.missing_field", "champ '{}' manquant dans l'entrée du cache distant"),
+    ("remote_cache.wrong_key", "l'entrée reçue correspond à une autre clé que {}"),
+    ("remote_cache.disabled", "{} ; cache distant désactivé jusqu'à la fin du build"),
+];
+
+const ENGLISH: &[(&str, &str)] = &[
+    ("error.usage", "invalid usage: {}"),
+    ("error.config", "invalid configuration: {}"),
+    ("error.discovery", "file discovery failed: {}"),
+    ("error.link", "linking failed: {}"),
+    ("error.run", "run failed: {}"),
+    ("error.hook", "hook failed: {}"),
+    ("error.storage", "storage unavailable: {}"),
+    ("error.compile", "compilation failed for {} file(s)"),
+
+    ("timings.compile", "Compilation: {} source(s) in {} ({} from the cache)"),
+    ("timings.link", "Linking: {}"),
+    ("timings.slowest", "Slowest sources:"),
+    ("timings.cached", "cache"),
+    ("timings.phases", "Compiler phases (-ftime-report, cumulated wall time):"),
+
+    ("stats.compile", "Compilation"),
+    ("stats.link", "Linking"),
+    ("stats.total", "Total time"),
+    ("stats.warnings", "Warnings"),
+    ("stats.executable_size", "Executable size"),
+    ("stats.objects_size", "Object size"),
+    ("stats.sources", "Sources"),
+    ("stats.cached", "Cached sources"),
+
+    ("cache.remote_ignored", "Remote cache ignored: {}"),
+    ("cache.remote_read_failed", "Remote cache: cannot read {}: {}"),
+    ("cache.remote_not_kept", "Remote entry {} not kept: {}"),
+    ("cache.remote_upload_failed", "Cannot upload {} to the remote cache: {}"),
+
+    ("clean.failed", "cannot clean: {}"),
+    ("clean.target_contains_project", "clean refused: the target '{}' contains the project '{}'"),
+    ("clean.outside_target", "clean refused: '{}' is outside the target '{}'"),
+    ("clean.summary", "Target cleaned: {} file(s), {} bytes removed"),
+
+    ("context.journal_write_failed", "Cannot write to the event journal: {}"),
+    ("context.journal_open_failed", "Cannot open or create the event journal: {}"),
+    ("context.log_open_failed", "Cannot open or create the log file: {}"),
+    ("context.log_write_failed", "Cannot write to the log file: {}"),
+    ("context.run_reservation_failed", "cannot reserve the run folder: {}"),
+
+    ("hooks.started", "Hook {}: {}"),
+    ("hooks.launch_failed", "cannot start the {} hook '{}': {}"),
+    ("hooks.stdout", "Hook output:\n{}"),
+    ("hooks.stderr", "Hook errors:\n{}"),
+    ("hooks.failed", "the {} hook '{}' failed ({})"),
+    ("hooks.failed_log", "Failure: {}\nHook output:\n{}"),
+
+    ("ignore.settings_rule", "IGNORE '{}' from settings.txt"),
+    ("ignore.file_rule", "'{}' from {} (line {})"),
+    ("ignore.directive_rule", "// EXCLUDE: '{}' in {}"),
+
+    ("resources.up_to_date", "Resource up to date: {}"),
+    ("resources.unreadable", "Unreadable resource '{}': {}"),
+    ("resources.generated", "Resource generated: {} -> {} ({} bytes)"),
+    ("resources.symbol_collision", "'{}' and '{}' produce the same C symbol '{}': rename one of the files"),
+
+    ("scanner.project_unreachable", "project directory unreachable ({})"),
+    ("scanner.symlink_loop", "symbolic link to an already visited directory ({}), loop skipped"),
+    ("scanner.unresolvable", "cannot resolve the path ({})"),
+    ("scanner.unreadable_directory", "unreadable directory ({})"),
+    ("scanner.unreadable_entry", "unreadable entry ({})"),
+    ("scanner.unreachable_file", "unreachable file ({})"),
+
+    ("snapshots.not_found", "snapshot {} not found ({})"),
+    ("snapshots.invalid_manifest", "invalid manifest for snapshot {}"),
+    ("snapshots.missing_content", "content of '{}' missing from the store ({})"),
+
+    ("sync.summary", "Synchronization of {}: {} added, {} updated, {} removed"),
+
+    ("project.missing", "the project directory '{}' does not exist"),
+    ("project.target_not_directory", "the target '{}' is not a directory"),
+
+    ("console.compiling", "[{}/{}] Compiling {}"),
+    ("console.linking", "Linking {} object(s)"),
+
+    ("init.already_initialized", "'{}' already exists, the project is already initialized"),
+    ("init.duplicate_file", "The template writes '{}' twice"),
+
+    ("pipeline.logs_removed", "Logs: {} old run(s) removed"),
+    ("pipeline.logs_removal_failed", "Cannot remove old logs: {}"),
+    ("pipeline.skipped", "Skipped: {} (rule {})"),
+    ("pipeline.scan_error", "File discovery error: {}"),
+    ("pipeline.no_sources", "no .c or .o file found in '{}'"),
+    ("pipeline.resources_failed", "cannot generate resources: {}"),
+    ("pipeline.sync_disabled", "Source synchronization disabled (SYNC: off)"),
+    ("pipeline.snapshot_created", "Snapshot {} saved ({} files)"),
+    ("pipeline.snapshot_failed", "Cannot save the snapshot: {}"),
+    ("pipeline.build_times_log", "Elapsed time: {} seconds\nFiles processed: {}\n\nTotal elapsed time: {} seconds"),
+    ("pipeline.build_times", "{} file(s) processed in {} seconds, build finished in {} seconds"),
+    ("pipeline.record_saved", "Metrics of build {} saved"),
+    ("pipeline.record_failed", "Cannot save the build metrics: {}"),
+    ("pipeline.timings", "Build timings:\n{}"),
+    ("pipeline.gcc_not_found", "gcc not found: {}"),
+    ("pipeline.syntax_error", "Syntax error in {}:\n{}"),
+    ("pipeline.check_summary", "{} file(s) checked, {} with errors"),
+    ("pipeline.test_passed", "test {} ... ok"),
+    ("pipeline.test_failed", "test {} ... FAILED ({})"),
+    ("pipeline.test_summary", "{} test(s): {} passed, {} failed"),
+    ("pipeline.tests_failed", "failed tests: {}"),
+    ("pipeline.no_executable", "no executable in '{}', run the build command first"),
+    ("pipeline.export_failed", "cannot export to '{}': {}"),
+    ("pipeline.exported", "Exported {} file(s) to {}"),
+    ("pipeline.file_skipped", "File skipped: {} (rule {})"),
+    ("pipeline.missing_files", "Project name: {}\nCurrent date: {}\nAnalyzed file type: {}\n\nExpected files:\n\t{}\nFound files:\n\t{}\n"),
+    ("pipeline.no_current_dir", "Cannot get the current directory"),
+    ("pipeline.create_directory_failed", "Cannot create the directory '{}': {}"),
+    ("pipeline.sync_summary", "{}: {} added, {} updated, {} removed"),
+    ("pipeline.sync_failed", "Cannot synchronize '{}': {}"),
+    ("pipeline.collision", "\t{} and {} -> {}"),
+    ("pipeline.collisions", "several files produce the same object file:\n{}"),
+    ("pipeline.run_duration_log", "Run time of '{}': {}"),
+    ("pipeline.run_duration", "Run time: {}"),
+    ("pipeline.run_succeeded", "Executable '{}' ran successfully."),
+    ("pipeline.run_failed", "'{}' exited with an error ({})"),
+    ("pipeline.run_launch_failed", "cannot start '{}': {}"),
+    ("pipeline.create_failed", "cannot create '{}': {}"),
+    ("pipeline.compile_failed", "Compilation of {} failed:"),
+    ("pipeline.compile_failed_log", "Compilation of the file {} failed: {}"),
+    ("pipeline.cache_copy_failed", "Cannot copy from the cache: {}"),
+    ("pipeline.remote_cache", "remote cache"),
+    ("pipeline.local_cache", "cache"),
+    ("pipeline.cache_hit", "Cache: {} fetched from the {} ({})"),
+    ("pipeline.worker_object_failed", "Cannot write the received object: {}"),
+    ("pipeline.worker_compiled", "Worker {}: {} compiled"),
+    ("pipeline.worker_compile_failed", "Compilation failed on worker {}. Error: {}"),
+    ("pipeline.worker_unavailable", "Worker {} unavailable for {}, compiling locally: {}"),
+    ("pipeline.compile_error", "Compilation error: {}"),
+    ("pipeline.compile_output", "Compilation failed. Error:\n{}\nCommand output: {}"),
+    ("pipeline.cache_store_failed", "Cannot write to the cache: {}"),
+    ("pipeline.warnings", "Warnings for {}:\n{}"),
+    ("pipeline.link_command", "Command run to link the project:\n\t{}\n"),
+    ("pipeline.link_failed", "Linking failed:"),
+    ("pipeline.link_status", "linking failed ({})"),
+    ("pipeline.non_utf8_path", "non UTF-8 path, cannot be passed to gcc: {}"),
+    ("pipeline.snapshots_removed", "Snapshots: {} old snapshot(s) removed"),
+    ("pipeline.snapshots_removal_failed", "Cannot remove old snapshots: {}"),
+    ("pipeline.cache_finish_failed", "Cannot update the cache: {}"),
+
+    ("main.result_success", "Result: success in {} seconds"),
+    ("main.result_failure", "Result: failure in {} seconds (code {}) - {}"),
+    ("main.report", "Report: {}"),
+    ("main.report_failed", "Cannot write the HTML report: {}"),
+    ("main.created", "Created: {}"),
+    ("main.initialized", "Project initialized in '{}' ({} template)"),
+    ("main.init_failed", "cannot create the project: {}"),
+    ("main.project_hint", "{} (project directory: --project option)"),
+    ("main.executable", "Executable: {}"),
+    ("main.would_remove", "Would remove: {}"),
+    ("main.removed", "Removed: {}"),
+    ("main.clean_summary", "{} file(s), {} bytes"),
+    ("main.clean_dry_run", "{} file(s), {} bytes (dry run, nothing was removed)"),
+    ("main.exported", "Exported: {}"),
+    ("main.stats_read_failed", "cannot read the build metrics: {}"),
+    ("main.no_build", "No build recorded, run the build command first."),
+    ("main.stats_header", "Build {} on {} ({} profile), compared with the average of {} previous build(s)"),
+    ("main.stats_measure", "Measure"),
+    ("main.stats_current", "Current"),
+    ("main.stats_previous", "Previous"),
+    ("main.stats_change", "Change"),
+    ("main.bytes_unit", "B"),
+    ("main.regression", "regression"),
+    ("main.no_regression", "No regression."),
+    ("main.regressions", "{} regression(s): {}"),
+    ("main.log_read_failed", "cannot read '{}': {}"),
+    ("main.run_header", "Run {} of {} ({})"),
+    ("main.no_runs", "No run recorded."),
+    ("main.bytes", "{} bytes"),
+    ("main.no_failed_run", "No failed run."),
+    ("main.run_not_found", "run {} not found"),
+    ("main.no_runs_error", "no run recorded"),
+    ("main.status_success", "success"),
+    ("main.status_failure", "failure (code {})"),
+    ("main.status_unfinished", "unfinished"),
+    ("main.no_snapshots", "No snapshot recorded."),
+    ("main.snapshot_line", "{} files  {} bytes  executable {}"),
+    ("main.history_read_failed", "cannot read the history: {}"),
+    ("main.diff_summary", "{} added, {} removed, {} modified"),
+    ("main.restored_log", "Snapshot {} restored ({} files)"),
+    ("main.restored", "Snapshot {} of {} restored into {}"),
+    ("main.restore_failed", "cannot restore: {}"),
+    ("main.cache_disabled", "Compilation cache disabled (CACHE: off)."),
+    ("main.cache_directory", "Directory: {}"),
+    ("main.cache_entries", "Entries: {}"),
+    ("main.cache_size", "Size: {} / {} bytes"),
+    ("main.cache_hits", "Hits: {}  Misses: {}  ({} %)"),
+    ("main.cache_read_failed", "cannot read the cache: {}"),
+    ("main.cache_cleared", "Cache cleared: {} entries removed."),
+    ("main.cache_clear_failed", "cannot clear the cache: {}"),
+
+    ("cli.unknown_logs_action", "unknown logs action '{}' (list, last-failure, tail)"),
+    ("cli.unknown_cache_action", "unknown cache action '{}' (stats, clear)"),
+    ("cli.missing_value", "option {} expects a value"),
+    ("cli.quiet_and_verbose", "the --quiet and --verbose options are incompatible"),
+    ("cli.unknown_color", "unknown color mode '{}' (auto, always, never)"),
+    ("cli.invalid_age", "invalid age '{}' (examples: 30d, 12h, 2w)"),
+    ("cli.unknown_option", "unknown option '{}' (see main --help)"),
+    ("cli.clean_only", "option {} is only accepted by 'clean'"),
+    ("cli.run_only", "arguments after '--' are only accepted by 'run'"),
+    ("cli.missing_command", "missing command\n\n{}"),
+    ("cli.unexpected_argument", "unexpected argument '{}' for '{}'"),
+    ("cli.missing_arguments", "missing arguments, usage: main {}"),
+    ("cli.invalid_snapshot", "invalid snapshot id '{}'"),
+    ("cli.unknown_template", "unknown project template '{}' ({})"),
+    ("cli.invalid_run", "invalid run id '{}'"),
+    ("cli.invalid_build_count", "invalid build count '{}'"),
+    ("cli.unknown_command", "unknown command '{}' (see main --help)"),
+    ("cli.unknown_message_format", "unknown message format '{}' ({})"),
+    ("cli.unknown_language", "unknown language '{}' ({})"),
+
+    ("help.build", "compiles and links the project"),
+    ("help.run", "compiles the project then launches the executable"),
+    ("help.test", "compiles the project then runs the tests (TEST key of settings.txt)"),
+    ("help.clean", "removes the files produced in the target"),
+    ("help.check", "checks the syntax of the sources without producing any file"),
+    ("help.init", "creates a C project (executable, static, shared, tests)"),
+    ("help.export", "copies the executable and its libraries into a directory"),
+    ("help.history", "lists the snapshots of successful builds"),
+    ("help.diff", "compares two snapshots"),
+    ("help.restore", "puts the target back in the state of a snapshot"),
+    ("help.cache", "statistics or clearing of the compilation cache"),
+    ("help.logs", "logs of the runs: list, last-failure, tail [id]"),
+    ("help.stats", "compares the last build with the n previous builds (5 by default)"),
+    ("help.worker", "starts a distributed compilation worker"),
+    ("help.options", "Options:\n  -p, --project <dir>       C project directory (default: current directory)\n  -t, --target <dir>        target directory (default: ./build)\n      --profile <name>      compilation profile (overrides PROFILE)\n      --no-cache            disables the compilation cache\n      --timings             shows the slowest sources and the build durations\n      --time-report         measures the gcc phases (-ftime-report)\n  -q, --quiet               only shows diagnostics and errors\n  -v, --verbose             shows the full command lines\n      --color <mode>        colors: auto (default), always or never\n      --message-format <f>  output: human (default), short (file:line:col) or json\n      --language <code>     message language: fr or en (overrides LANGUAGE)\n  -h, --help                shows the help\n  -V, --version             shows the version"),
+    ("help.clean_options", "Clean scopes (default: everything the build produces, except logs and history):\n      --objects             object files (output/<profile>)\n      --executables         executables (executable/<profile>)\n      --sources             copied sources and generated resources\n      --libraries           libraries (dll, a)\n      --logs                build logs\n      --all                 every scope, logs and history included\n      --profile <name>      limits objects and executables to one profile\n      --older-than <age>    only files older than this (30d, 12h, 2w)\n  -n, --dry-run             lists the files without removing them"),
+    ("help.usage", "Usage: main [options] {}"),
+    ("help.command_placeholder", "<command> [arguments]"),
+    ("help.commands", "Commands:"),
+    ("help.build_usage", "build"),
+    ("help.run_usage", "run [-- arguments]"),
+    ("help.test_usage", "test"),
+    ("help.clean_usage", "clean [scopes]"),
+    ("help.check_usage", "check"),
+    ("help.init_usage", "init [template]"),
+    ("help.export_usage", "export <dir>"),
+    ("help.history_usage", "history"),
+    ("help.diff_usage", "diff <a> <b>"),
+    ("help.restore_usage", "restore <id>"),
+    ("help.cache_usage", "cache [stats|clear]"),
+    ("help.logs_usage", "logs [action] [id]"),
+    ("help.stats_usage", "stats [n]"),
+    ("help.worker_usage", "worker [address]"),
+
+    ("report.title", "Build report {} - {}"),
+    ("report.run", "run {}"),
+    ("report.success", "success"),
+    ("report.failure", "failure (code {}) - {}"),
+    ("report.unfinished", "unfinished"),
+    ("report.duration", "Duration"),
+    ("report.profile", "Profile"),
+    ("report.project", "Project"),
+    ("report.target", "Target"),
+    ("report.summary", "Summary"),
+    ("report.result", "Result"),
+    ("report.inventory", "Inventory"),
+    ("report.includes", "Include graph"),
+    ("report.commands", "Commands"),
+    ("report.diagnostics", "Diagnostics"),
+    ("report.durations", "Durations"),
+    ("report.artifacts", "Produced files"),
+    ("report.no_files", "No file inventoried."),
+    ("report.no_sources", "No source inventoried."),
+    ("report.no_commands", "No command launched."),
+    ("report.no_diagnostics", "No diagnostic."),
+    ("report.no_compilation", "No compilation."),
+    ("report.no_artifacts", "No file produced."),
+    ("report.file_count", "{} file(s)"),
+    ("report.file", "File"),
+    ("report.includes_column", "Includes"),
+    ("report.included_by", "Included by"),
+    ("report.command", "Command"),
+    ("report.status", "Status"),
+    ("report.diagnostic_count", "{} diagnostic(s)"),
+    ("report.location", "line {}, column {}"),
+    ("report.other_messages", "other messages"),
+    ("report.compile_total", "Compilation: {} source(s) in {}"),
+    ("report.link_total", "linking: {}"),
+    ("report.source", "Source"),
+    ("report.size", "Size"),
+    ("report.bytes", "{} B"),
+    ("report.total", "Total"),
+
+    ("worker.connect_failed", "cannot connect to worker '{}': {}"),
+    ("worker.send_failed", "cannot send to worker '{}': {}"),
+    ("worker.unreadable_response", "unreadable response from worker '{}': {}"),
+    ("worker.truncated_response", "truncated response from worker '{}'"),
+    ("worker.listen_failed", "cannot listen on '{}': {}"),
+    ("worker.listening", "Worker waiting for tasks on {}"),
+    ("worker.task_dropped", "Task from {} dropped: {}"),
+    ("worker.connection_refused", "Connection refused: {}"),
+    ("worker.unreadable_request", "unreadable request: {}"),
+    ("worker.truncated_request", "truncated request"),
+    ("worker.refused_option", "option refused by the worker: {}"),
+    ("worker.gcc_not_found", "gcc not found on the worker: {}"),
+    ("worker.result_failed", "cannot send the result: {}"),
+    ("worker.incomplete_message", "incomplete message"),
+    ("worker.unknown_format", "message in an unknown format"),
+    ("worker.not_found_error", "worker '{}' not found: {}"),
+    ("worker.not_found", "worker '{}' not found"),
+    ("worker.compiled", "{}: compiled"),
+    ("worker.failed", "{}: failed"),
+    ("worker.token_required", "refusing to listen on {} without a token: set {} or listen on 127.0.0.1"),
+    ("worker.invalid_token", "missing or invalid token"),
+    ("worker.busy", "connection from {} refused: {} jobs already running"),
+    ("worker.request_too_large", "request larger than {} bytes refused"),
+    ("worker.missing_field", "missing field '{}' in the message"),
+
+    ("remote_cache.unsupported_url", "unsupported remote cache address '{}' (http:// expected)"),
+    ("remote_cache.invalid_port", "invalid port in '{}'"),
+    ("remote_cache.missing_host", "missing host in '{}'"),
+    ("remote_cache.http_status", "HTTP response {} from the remote cache"),
+    ("remote_cache.upload_status", "HTTP response {} from the remote cache on upload"),
+    ("remote_cache.connect_failed", "cannot connect to {}: {}"),
+    ("remote_cache.send_failed", "cannot send the request: {}"),
+    ("remote_cache.read_failed", "cannot read the response: {}"),
+    ("remote_cache.invalid_status_line", "invalid HTTP status line"),
+    ("remote_cache.invalid_chunk_size", "invalid HTTP chunk size"),
+    ("remote_cache.invalid_entry", "invalid remote cache entry"),
+    ("remote_cache.unknown_format", "unknown remote cache entry format"),
+    ("remote_cache.wrong_size", "wrong remote cache entry size"),
+    ("remote_cache.invalid_hash", "invalid remote cache entry hash"),
+    ("remote_cache.host_not_found", "host '{}' not found: {}"),
+    ("remote_cache.no_address", "host '{}' not found"),
+    ("remote_cache.incomplete_response", "incomplete HTTP response"),
+    ("remote_cache.truncated_response", "truncated HTTP response"),
+    ("remote_cache.truncated_chunk", "truncated HTTP chunk"),
+    ("remote_cache.unterminated_chunk", "HTTP chunk longer than its announced size"),
+    ("remote_cache.invalid_chunk", "invalid HTTP chunk"),
+    ("remote_cache.missing_field", "missing field '{}' in the remote cache entry"),
+    ("remote_cache.wrong_key", "the received entry belongs to another key than {}"),
+    ("remote_cache.disabled", "{}; remote cache disabled until the end of the build"),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+
+    fn keys(catalog: &[(&'static str, &str)]) -> BTreeSet<&'static str> {
+        catalog.iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn every_key_exists_in_both_languages() {
+        let french: BTreeSet<&str> = keys(FRENCH);
+        let english: BTreeSet<&str> = keys(ENGLISH);
+
+        let missing_in_english: Vec<&&str> = french.difference(&english).collect();
+        let missing_in_french: Vec<&&str> = english.difference(&french).collect();
+        assert!(missing_in_english.is_empty(), "clés sans traduction anglaise : {:?}", missing_in_english);
+        assert!(missing_in_french.is_empty(), "clés sans traduction française : {:?}", missing_in_french);
+    }
+
+    #[test]
+    fn keys_are_unique() {
+        for catalog in [FRENCH, ENGLISH] {
+            assert_eq!(keys(catalog).len(), catalog.len(), "clé en double dans le catalogue");
+        }
+    }
+
+    #[test]
+    fn translations_have_the_same_parameters() {
+        for (key, french) in FRENCH {
+            let english: &str = Language::English.catalog()[key];
+            assert_eq!(french.matches("{}").count(), english.matches("{}").count(), "paramètres différents pour '{}'", key);
+            assert!(!french.trim().is_empty() && !english.trim().is_empty(), "message vide pour '{}'", key);
+        }
+    }
+
+    #[test]
+    fn every_key_used_in_the_sources_exists() {
+        let root: &Path = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let french: BTreeSet<&str> = keys(FRENCH);
+
+        for entry in fs::read_dir(root).unwrap().flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|extension| extension != "rs") {
+                continue;
+            }
+
+            let source: String = fs::read_to_string(&path).unwrap();
+            for pattern in ["tr!(\"", "text(\""] {
+                for (index, _) in source.match_indices(pattern) {
+                    let start: usize = index + pattern.len();
+                    let key: &str = &source[start..start + source[start..].find('"').unwrap()];
+                    assert!(french.contains(key), "clé '{}' utilisée dans {} absente du catalogue", key, path.display());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn scopes_restore_the_previous_language() {
+        let outer = LanguageScope::enter(Language::English);
+        assert_eq!(text("error.usage"), "invalid usage: {}");
+        {
+            let _inner = LanguageScope::enter(Language::French);
+            assert_eq!(language(), Language::French);
+            // Les autres threads ne voient pas la langue de celui-ci
+            std::thread::scope(|scope| {
+                scope.spawn(|| {
+                    let _other = LanguageScope::enter(Language::English);
+                    assert_eq!(language(), Language::English);
+                });
+            });
+            assert_eq!(language(), Language::French);
+        }
+        assert_eq!(language(), Language::English);
+        drop(outer);
+        assert_eq!(ACTIVE_LANGUAGE.get(), None);
+    }
+
+    #[test]
+    fn parameters_are_replaced_in_order() {
+        assert_eq!(format_message("test.unknown {} et {}", &[&1, &"deux"]), "test.unknown 1 et deux");
+        assert_eq!(Language::from_name("en_US.UTF-8"), Some(Language::English));
+        assert_eq!(Language::from_name("fr_FR"), Some(Language::French));
+        assert_eq!(Language::from_name("C"), None);
+    }
+}
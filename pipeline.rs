@@ -0,0 +1,971 @@
+//! Étapes du build : recherche des fichiers, compilation, édition de liens et exécution.
+//!
+//! Chaque étape reçoit le [`BuildContext`] du projet ; [`Project`](crate::Project) les enchaîne.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant, SystemTime};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use chrono::Local;
+
+use crate::cache::CompileCache;
+use crate::console::MessageFormat;
+use crate::context::BuildContext;
+use crate::diagnostics::gcc_command;
+use crate::error::BuildError;
+use crate::events::BuildEvent;
+use crate::hooks::{HookEnvironment, HookPhase, run_hooks, shell_command};
+use crate::ignore::IgnoreRules;
+use crate::logs::{RetentionPolicy, apply_retention};
+use crate::messages::{Language, LanguageScope, language};
+use crate::resources::{Resource, generate_resources, resources_from_settings};
+use crate::scanner::{ProjectInventory, scan_project};
+use crate::settings::Settings;
+use crate::snapshots::{create_snapshot, prune_snapshots, snapshots_to_keep};
+use crate::stats::{BuildRecord, append_record, count_warnings};
+use crate::sync::{SyncReport, sync_files};
+use crate::timings::{BuildTimings, FileTiming, format_duration, split_time_report, top_from_settings};
+use crate::tr;
+use crate::worker::{compile_on_worker, is_allowed_flag, worker_token, workers_from_settings};
+
+/// Fichiers attendus d'après les `#include`, classés par extension.
+#[derive(Default)]
+struct ExpectedFiles {
+    c_files: Vec<PathBuf>,
+    h_files: Vec<PathBuf>,
+    dll_files: Vec<PathBuf>,
+    a_files: Vec<PathBuf>,
+    o_files: Vec<PathBuf>,
+}
+
+/// Résultat de la compilation d'une source.
+#[derive(Debug, Clone, Default)]
+struct CompileOutcome {
+    /// Fichier objet récupéré depuis le cache.
+    cached: bool,
+    /// Phases relevées par `-ftime-report`.
+    phases: Vec<(String, Duration)>,
+    warnings: usize,
+}
+
+/// Échec de la compilation d'une source.
+#[derive(Debug)]
+struct CompileFailure {
+    /// Message complet, repris dans les logs et le récapitulatif.
+    message: String,
+    /// Sortie de gcc, lorsque c'est lui qui a refusé la source.
+    diagnostics: Option<String>,
+}
+
+impl From<String> for CompileFailure {
+    fn from(message: String) -> Self {
+        CompileFailure { message, diagnostics: None }
+    }
+}
+
+/// Prépare la cible puis recherche les fichiers du projet, en appliquant les règles d'exclusion.
+pub fn scan(context: &BuildContext) -> Result<ProjectInventory, BuildError> {
+
+    let project_path: &str = &context.project_path;
+
+    create_directories(context);
+
+    // Les exécutions les plus anciennes laissent la place à celle-ci
+    match apply_retention(Path::new(&context.logs_path), &RetentionPolicy::from_settings(&context.settings), context.run_id()) {
+        Ok(removed) if !removed.is_empty() => {
+            context.write_in_logs(tr!("pipeline.logs_removed", removed.len()));
+        }
+        Ok(_) => {}
+        Err(err) => context.console().error(&tr!("pipeline.logs_removal_failed", err)),
+    }
+
+    run_build_hooks(context, HookPhase::PreCollect)?;
+
+    // Règles d'exclusion : IGNORE de settings.txt et .compilercignore, puis directives // EXCLUDE: des sources
+    let mut ignore_rules: IgnoreRules = IgnoreRules::load(project_path, &context.settings);
+
+    // Un seul parcours du projet, sans descendre dans les dossiers cibles (racine et projet) ni dans les logs
+    let mut file_collections: ProjectInventory = scan_project(
+        project_path,
+        &[context.target_root.clone(), context.target_path.clone(), context.logs_path.clone()],
+        &ignore_rules,
+    );
+
+    for (skipped_path, rule) in &file_collections.skipped {
+        context.write_in_logs(tr!("pipeline.skipped", skipped_path.display(), rule));
+    }
+    for scan_error in &file_collections.errors {
+        context.console().error(&tr!("pipeline.scan_error", scan_error));
+        context.write_in_logs(tr!("pipeline.scan_error", scan_error));
+    }
+
+    ignore_rules.add_directives(get_exclude_list(&file_collections.c_files));
+    file_collections.retain(|file| !is_ignored(context, &ignore_rules, file));
+
+    if file_collections.c_files.is_empty() && file_collections.o_files.is_empty() {
+        return Err(BuildError::Discovery(tr!("pipeline.no_sources", project_path)));
+    }
+
+    for (kind, files) in [
+        ("c", &file_collections.c_files),
+        ("h", &file_collections.h_files),
+        ("dll", &file_collections.dll_files),
+        ("a", &file_collections.a_files),
+        ("o", &file_collections.o_files),
+    ] {
+        for file in files {
+            context.emit(BuildEvent::FileDiscovered { path: file.clone(), kind });
+        }
+    }
+
+    context.emit(BuildEvent::ScanFinished {
+        sources: file_collections.c_files.len(),
+        headers: file_collections.h_files.len(),
+        objects: file_collections.o_files.len(),
+        libraries: file_collections.dll_files.len() + file_collections.a_files.len(),
+    });
+
+    Ok(file_collections)
+
+}
+
+/// Compile et lie les fichiers trouvés puis enregistre un instantané ; renvoie le chemin de l'exécutable.
+pub async fn build(context: &BuildContext, mut file_collections: ProjectInventory) -> Result<PathBuf, BuildError> {
+
+    let project_path: &str = &context.project_path;
+    let settings: &Settings = &context.settings;
+
+    let start_time: SystemTime = SystemTime::now();
+
+    let unique_library_files: HashSet<String> = update_library_list(&file_collections.c_files);
+    let total_files: usize = file_collections.len() + unique_library_files.len();
+
+    // Génère les sources C des ressources à intégrer dans l'exécutable
+    let resources_path: String = context.resources_path();
+    let resources: Vec<Resource> = resources_from_settings(settings, project_path);
+    let mut sources_to_compile: Vec<PathBuf> = file_collections.c_files.clone();
+    let mut compile_include_paths: Vec<String> = Vec::new();
+
+    if !resources.is_empty() {
+        let resource_sources: Vec<PathBuf> = generate_resources(context, &resources, &resources_path)
+            .map_err(|err| BuildError::Config(tr!("pipeline.resources_failed", err)))?;
+        sources_to_compile.extend(resource_sources);
+        compile_include_paths.push(resources_path.clone());
+    }
+
+    // Deux fichiers ne doivent pas produire le même fichier objet
+    check_output_collisions(context, &sources_to_compile, &file_collections.o_files).map_err(BuildError::Config)?;
+
+    // Copie miroir du projet dans la cible, désactivable pour les builds qui compilent sur place
+    if settings.get_bool("SYNC", true) {
+        sync_files_to_directory(context, &[file_collections.h_files.clone(), file_collections.c_files.clone()].concat(), "source", true);
+        // Le dossier output contient aussi les objets compilés : aucune suppression
+        sync_files_to_directory(context, &file_collections.o_files, &format!("output/{}", context.profile()), false);
+        sync_files_to_directory(context, &file_collections.dll_files, "dll", true);
+        sync_files_to_directory(context, &file_collections.a_files, "a", true);
+    } else {
+        context.write_in_logs(tr!("pipeline.sync_disabled"));
+    }
+
+    // Les objets produits rejoignent la liste des objets à lier
+    let compile_cache: Option<CompileCache> = CompileCache::from_settings(settings);
+    let workers: Vec<String> = workers_from_settings(settings);
+    let compiled = build_source(context, &sources_to_compile, &compile_include_paths, compile_cache.as_ref(), &workers).await;
+
+    // Limite de taille et compteurs du cache, une fois par build, même si une compilation a échoué
+    if let Some(Err(err)) = compile_cache.as_ref().map(CompileCache::finish) {
+        context.console().error(&tr!("pipeline.cache_finish_failed", err));
+    }
+    let (object_files, mut timings): (Vec<PathBuf>, BuildTimings) = compiled?;
+
+    for object_file in object_files {
+        if !file_collections.o_files.contains(&object_file) {
+            file_collections.o_files.push(object_file);
+        }
+    }
+
+    run_build_hooks(context, HookPhase::PostCompile)?;
+    
+    // Divise unique_library_files en quatre listes en fonction de l'extension
+    let expected_files: ExpectedFiles = split_files_by_extension(&unique_library_files);
+
+    // Vérifie si les fichiers inclus sont présents dans les listes c_files, h_files, dll_files et a_files
+    check_all_files(context, "C", &file_collections.c_files, &expected_files.c_files);
+    check_all_files(context, "H", &file_collections.h_files, &expected_files.h_files);
+    check_all_files(context, "DLL", &file_collections.dll_files, &expected_files.dll_files);
+    check_all_files(context, "A", &file_collections.a_files, &expected_files.a_files);
+    check_all_files(context, "O", &file_collections.o_files, &expected_files.o_files);
+
+    let mut elapsed_files_secs: u64 = 0;    let mut elapsed_files_millis: u32 = 0;
+    let mut elapsed_compile_secs: u64 = 0;  let mut elapsed_compile_millis: u32 = 0;
+
+    if let Ok(elapsed_time) = start_time.elapsed() {    
+        (elapsed_files_secs, elapsed_files_millis) = extract_seconds_and_millis(elapsed_time);
+    }
+
+    let include_paths: Vec<String>  = extract_unique_paths(&file_collections.h_files);
+    let library_paths: Vec<String>  = extract_unique_paths(&file_collections.dll_files);
+    let libraries: Vec<String>      = extract_unique_file_names(&file_collections.dll_files);
+    let objects_size: u64           = file_collections.o_files.iter().filter_map(|file| file.metadata().ok()).map(|metadata| metadata.len()).sum();
+
+    timings.link = Some(build_execute(context, file_collections.o_files, include_paths, library_paths, libraries).await?);
+
+    run_build_hooks(context, HookPhase::PostLink)?;
+
+    // Seuls les builds réussis sont conservés dans l'historique
+    match create_snapshot(Path::new(&context.target_path)) {
+        Ok(snapshot) => {
+            context.write_in_logs(tr!("pipeline.snapshot_created", snapshot.id, snapshot.files.len()));
+            context.emit(BuildEvent::SnapshotCreated { id: snapshot.id });
+        }
+        Err(err) => context.console().error(&tr!("pipeline.snapshot_failed", err)),
+    }
+
+    if let Some(keep) = snapshots_to_keep(&context.settings) {
+        match prune_snapshots(Path::new(&context.target_path), keep) {
+            Ok(removed) if !removed.is_empty() => context.write_in_logs(tr!("pipeline.snapshots_removed", removed.len())),
+            Ok(_) => {}
+            Err(err) => context.console().error(&tr!("pipeline.snapshots_removal_failed", err)),
+        }
+    }
+
+    if let Ok(elapsed_time) = start_time.elapsed() {
+        (elapsed_compile_secs, elapsed_compile_millis) = extract_seconds_and_millis(elapsed_time);
+    }
+
+    let elapsed_files: String = format!("{}.{:03}", elapsed_files_secs, elapsed_files_millis);
+    let elapsed_compile: String = format!("{}.{:03}", elapsed_compile_secs, elapsed_compile_millis);
+
+    context.write_in_logs(tr!("pipeline.build_times_log", elapsed_files, total_files, elapsed_compile));
+    context.console().info(&tr!("pipeline.build_times", total_files, elapsed_files, elapsed_compile));
+
+    // Mesures conservées pour comparer les builds entre eux (commande stats)
+    let record: BuildRecord = BuildRecord {
+        date: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        profile: context.profile(),
+        sources: timings.files.len(),
+        cached: timings.files.iter().filter(|file| file.cached).count(),
+        files: total_files,
+        warnings: timings.files.iter().map(|file| file.warnings).sum(),
+        compile_ms: timings.compile_total().as_secs_f64() * 1000.0,
+        link_ms: timings.link.unwrap_or_default().as_secs_f64() * 1000.0,
+        total_ms: start_time.elapsed().unwrap_or_default().as_secs_f64() * 1000.0,
+        executable_size: fs::metadata(context.executable_path()).map(|metadata| metadata.len()).unwrap_or(0),
+        objects_size,
+        ..BuildRecord::default()
+    };
+    match append_record(Path::new(&context.target_path), record) {
+        Ok(record) => context.write_in_logs(tr!("pipeline.record_saved", record.id)),
+        Err(err) => context.console().error(&tr!("pipeline.record_failed", err)),
+    }
+
+    // Durées détaillées : toujours dans le log, affichées sur demande
+    let timing_report: Vec<String> = timings.report(top_from_settings(settings));
+    context.write_in_logs(tr!("pipeline.timings", timing_report.join("\n")));
+    if settings.get_bool("TIMINGS", false) {
+        context.console().info(&format!("\n{}", timing_report.join("\n")));
+    }
+
+    Ok(PathBuf::from(context.executable_path()))
+
+}
+
+/// Lance l'exécutable produit avec ses arguments puis les hooks de fin d'exécution.
+pub fn run(context: &BuildContext, program_args: &[String]) -> Result<(), BuildError> {
+
+    execute_main(context, program_args)?;
+
+    run_build_hooks(context, HookPhase::PostRun)
+
+}
+
+/// Vérifie la syntaxe des sources (`gcc -fsyntax-only`) sans produire de fichier objet.
+pub fn check(context: &BuildContext, file_collections: &ProjectInventory) -> Result<(), BuildError> {
+
+    // Les sources peuvent inclure les en-têtes des ressources, qui doivent donc exister
+    let resources_path: String = context.resources_path();
+    let resources: Vec<Resource> = resources_from_settings(&context.settings, &context.project_path);
+    generate_resources(context, &resources, &resources_path)
+        .map_err(|err| BuildError::Config(tr!("pipeline.resources_failed", err)))?;
+
+    check_output_collisions(context, &file_collections.c_files, &file_collections.o_files).map_err(BuildError::Config)?;
+
+    let mut failures: Vec<(PathBuf, String)> = Vec::new();
+
+    for c_file in &file_collections.c_files {
+        let mut command: Command = gcc_command();
+        command.arg("-fsyntax-only").arg(c_file).arg(format!("-I{}", resources_path));
+
+        let output: Output = run_command(context, &mut command)
+            .map_err(|err| BuildError::Compile(vec![(c_file.clone(), tr!("pipeline.gcc_not_found", err))]))?;
+
+        let diagnostics: String = String::from_utf8_lossy(&output.stderr).to_string();
+        if output.status.success() {
+            report_diagnostics(context, &c_file.to_string_lossy(), &diagnostics);
+        } else {
+            context.diagnostics(&diagnostics);
+            context.emit(BuildEvent::Diagnostics { source: c_file.clone(), text: diagnostics.clone() });
+            context.write_in_logs(tr!("pipeline.syntax_error", c_file.display(), diagnostics.trim_end()));
+            failures.push((c_file.clone(), diagnostics));
+        }
+    }
+
+    context.console().info(&tr!("pipeline.check_summary", file_collections.c_files.len(), failures.len()));
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(BuildError::Compile(failures))
+    }
+
+}
+
+/// Lance les tests déclarés par la clé `TEST`, ou l'exécutable lui-même si aucun test n'est déclaré.
+pub fn test(context: &BuildContext) -> Result<(), BuildError> {
+
+    let tests: Vec<String> = context.settings.get_all("TEST");
+
+    if tests.is_empty() {
+        return execute_main(context, &[]);
+    }
+
+    let environment: HookEnvironment = context.hook_environment();
+    let mut failures: Vec<String> = Vec::new();
+
+    for test in &tests {
+        let mut command: Command = shell_command(test);
+        command.envs(environment.variables("test")).current_dir(&context.project_path);
+
+        let start_time: Instant = Instant::now();
+        let status = command.status();
+        context.emit(BuildEvent::command_finished(&command, start_time.elapsed(), status.as_ref().ok()));
+
+        let failure: Option<String> = match status {
+            Ok(status) if status.success() => None,
+            Ok(status) => Some(status.to_string()),
+            Err(err) => Some(err.to_string()),
+        };
+
+        match failure {
+            None => {
+                context.console().info(&tr!("pipeline.test_passed", test));
+                context.write_in_logs(tr!("pipeline.test_passed", test));
+            }
+            Some(failure) => {
+                context.console().error(&tr!("pipeline.test_failed", test, failure));
+                context.write_in_logs(tr!("pipeline.test_failed", test, failure));
+                failures.push(test.clone());
+            }
+        }
+    }
+
+    context.console().info(&tr!("pipeline.test_summary", tests.len(), tests.len() - failures.len(), failures.len()));
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(BuildError::Run(tr!("pipeline.tests_failed", failures.join(", "))))
+    }
+
+}
+
+/// Copie l'exécutable et les bibliothèques dynamiques dans `destination` ; renvoie les fichiers copiés.
+pub fn export(context: &BuildContext, destination: &str) -> Result<Vec<PathBuf>, BuildError> {
+
+    let executable: PathBuf = PathBuf::from(context.executable_path());
+    if !executable.is_file() {
+        return Err(BuildError::Discovery(tr!("pipeline.no_executable", context.target_path)));
+    }
+
+    let mut files: Vec<PathBuf> = vec![executable];
+    if let Ok(entries) = fs::read_dir(Path::new(&context.target_path).join("dll")) {
+        files.extend(entries.flatten().map(|entry| entry.path()).filter(|path| path.is_file()));
+    }
+
+    let storage_error = |err: io::Error| BuildError::Storage(tr!("pipeline.export_failed", destination, err));
+    fs::create_dir_all(destination).map_err(storage_error)?;
+
+    // Les bibliothèques sont placées à côté de l'exécutable pour être trouvées au lancement
+    let mut exported: Vec<PathBuf> = Vec::new();
+    for file in files {
+        let exported_file: PathBuf = Path::new(destination).join(file.file_name().unwrap_or_default());
+        fs::copy(&file, &exported_file).map_err(storage_error)?;
+        exported.push(exported_file);
+    }
+
+    context.write_in_logs(tr!("pipeline.exported", exported.len(), destination));
+    Ok(exported)
+
+}
+
+/// Obtient la liste des fichiers à exclure déclarés par `// EXCLUDE:`, avec le fichier qui les déclare.
+fn get_exclude_list(c_files: &[PathBuf]) -> Vec<(PathBuf, String)> {
+    let mut exclude_list = Vec::new();
+
+    for c_file in c_files {
+        if let Ok(file) = File::open(c_file) {
+            let reader = io::BufReader::new(file);
+            for line in reader.lines().map_while(Result::ok) {
+                if line.starts_with("// EXCLUDE:") {
+                    let excluded_file = line.trim_start_matches("// EXCLUDE:").trim().to_string();
+                    exclude_list.push((c_file.clone(), excluded_file));
+                }
+            }
+        }
+    }
+
+    exclude_list
+}
+
+/// Indique si un fichier est exclu et log la règle responsable.
+fn is_ignored(context: &BuildContext, ignore_rules: &IgnoreRules, file: &Path) -> bool {
+    match ignore_rules.excluded_by(file, false) {
+        Some(rule) => {
+            context.write_in_logs(tr!("pipeline.file_skipped", file.display(), rule));
+            true
+        }
+        None => false,
+    }
+}
+
+fn extract_seconds_and_millis(elapsed_time: Duration) -> (u64, u32) {
+    (elapsed_time.as_secs(), elapsed_time.subsec_millis())
+}
+
+/// Parcours le contenu des fichiers ".c" en parallèle pour extraire les lignes contenant "#include ".
+fn update_library_list(c_files: &[PathBuf]) -> HashSet<String> {
+    let unique_lines_mutex: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
+    let mut handles: Vec<thread::JoinHandle<()>> = vec![];
+
+    for file_path in c_files {
+        let unique_lines_mutex: Arc<Mutex<HashSet<String>>> = Arc::clone(&unique_lines_mutex);
+
+        // Clonage du chemin de fichier pour que chaque thread possède sa propre copie
+        let file_path: PathBuf = file_path.clone();
+
+        let handle: thread::JoinHandle<()> = thread::spawn(move || {
+            if let Ok(file) = File::open(&file_path) {
+                let reader: io::BufReader<File> = io::BufReader::new(file);
+                for line in reader.lines().map_while(Result::ok) {
+                    if line.contains("#include ") {
+                        put_library(&line, &unique_lines_mutex);
+                    }
+                }
+            }
+        });
+
+        handles.push(handle);
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    Arc::try_unwrap(unique_lines_mutex).unwrap().into_inner().unwrap()
+}
+
+/// Traite une ligne contenant "#include " en extrayant le texte inclus.
+fn put_library(line: &str, unique_lines_mutex: &Arc<Mutex<HashSet<String>>>) {
+    // Trouve les positions des guillemets
+    let start_quote = line.find('"');
+    let end_quote = line.rfind('"');
+
+    // Si les guillemets sont présents, stocke le texte entre eux
+    if let (Some(start), Some(end)) = (start_quote, end_quote) {
+        let include_text = line[start + 1..end].to_string();
+        let mut unique_library_files = unique_lines_mutex.lock().unwrap();
+        unique_library_files.insert(include_text);
+    } else {
+        // Sinon, recherche les symboles '<' et '>'
+        let start_bracket = line.find('<');
+        let end_bracket = line.rfind('>');
+
+        // Si les symboles sont présents, stocke le texte entre eux
+        if let (Some(start), Some(end)) = (start_bracket, end_bracket) {
+            let include_text = line[start + 1..end].to_string();
+            let mut unique_library_files = unique_lines_mutex.lock().unwrap();
+            unique_library_files.insert(include_text);
+        }
+    }
+}
+
+
+/// Vérifie si les fichiers inclus sont présents dans la liste de fichiers et log les avertissements si nécessaire.
+fn check_all_files(context: &BuildContext, file_type: &str, file_list: &[PathBuf], expected_files: &[PathBuf]) {
+    // Convertit la liste de fichiers en HashSet pour une recherche plus rapide
+    let file_set: HashSet<_> = file_list.iter().collect();
+
+    // Liste des fichiers manquants pour cette extension
+    let mut missing_files: Vec<&PathBuf> = Vec::new();
+
+    // Vérifie si chaque fichier inclus est présent dans la liste
+    for include_file in expected_files {
+        if !file_set.contains(include_file) {
+            missing_files.push(include_file);
+        }
+    }
+
+    // Construit le message d'avertissement
+    if !missing_files.is_empty() {
+        let missing_files_str: Vec<_> = missing_files
+            .iter()
+            .map(|path_buf| path_buf.to_string_lossy().to_string())
+            .collect();
+
+        let formatted_time = context.formatted_time.clone();
+        let current_path: PathBuf = std::env::current_dir().expect(&tr!("pipeline.no_current_dir"));
+
+        let current_folder_name: Option<&str> = current_path.file_name().and_then(|n| n.to_str());
+        let current_folder_name_str: String = current_folder_name.unwrap_or_default().to_string();
+
+        // Construit le message de log complet
+        let log_message = tr!(
+            "pipeline.missing_files",
+            current_folder_name_str,
+            formatted_time,
+            file_type,
+            format!("{:?}", missing_files_str.join(", ")),
+            format!("{:?}", expected_files)
+        );
+
+        context.write_in_logs(log_message);
+    }
+}
+
+/// Divise les lignes uniques en quatre listes en fonction de l'extension.
+fn split_files_by_extension(unique_library_files: &HashSet<String>) -> ExpectedFiles {
+    let mut expected_files = ExpectedFiles::default();
+
+    for file in unique_library_files {
+        let file_extension = Path::new(&file)
+            .extension()
+            .map(|ext| ext.to_string_lossy())
+            .unwrap_or_default();
+
+        match file_extension.to_lowercase().as_str() {
+            "c" => expected_files.c_files.push(PathBuf::from(file)),
+            "h" => expected_files.h_files.push(PathBuf::from(file)),
+            "dll" => expected_files.dll_files.push(PathBuf::from(file)),
+            "a" => expected_files.a_files.push(PathBuf::from(file)),
+            "o" => expected_files.o_files.push(PathBuf::from(file)),
+            _ => (),
+        }
+    }
+
+    expected_files
+}
+
+/// Extrait les chemins uniques des fichiers.
+fn extract_unique_paths(paths: &[PathBuf]) -> Vec<String> {
+    let unique_paths: HashSet<_> = paths.iter().flat_map(|path| path.parent().map(|p| p.to_string_lossy().to_string())).collect();
+    unique_paths.into_iter().collect()
+}
+
+fn extract_unique_file_names(paths: &[PathBuf]) -> Vec<String> {
+    let unique_names: HashSet<_> = paths
+        .iter()
+        .filter_map(|path| path.file_name().and_then(|n| n.to_str()).map(|s| s.to_string()))
+        .collect();
+    unique_names.into_iter().collect()
+}
+
+fn create_directories(context: &BuildContext) {
+
+    let path: String = context.target_path.clone();
+
+    let directory_paths: Vec<String> = [
+
+        format!("{}/executable/{}", path, context.profile()),
+        format!("{}/source", path),
+        context.output_path(),
+        format!("{}/dll", path),
+        format!("{}/a", path),
+        context.resources_path(),
+        context.logs_path.clone(),
+
+    ].to_vec();
+
+    for directory_path in directory_paths {
+
+        if !Path::new(&directory_path).exists() {
+            if let Err(err) = fs::create_dir_all(&directory_path) {
+                context.console().error(&tr!("pipeline.create_directory_failed", directory_path, err));
+            }
+        }
+
+    }
+
+}
+
+/// Synchronise les fichiers dans un dossier de la cible en conservant leur arborescence relative.
+fn sync_files_to_directory(context: &BuildContext, files: &[PathBuf], destination_folder: &str, remove_stale: bool) {
+    let destination_path: PathBuf = Path::new(&context.target_path.clone()).join(destination_folder);
+    let files: Vec<(PathBuf, PathBuf)> = files.iter().map(|file| (file.clone(), context.relative_path(file))).collect();
+
+    let report: SyncReport = sync_files(&files, &destination_path, remove_stale);
+
+    if report.has_changes() || !report.errors.is_empty() {
+        context.console().info(&tr!("pipeline.sync_summary", destination_folder, report.added.len(), report.updated.len(), report.removed.len()));
+        context.write_in_logs(report.describe(destination_folder));
+    }
+
+    for error in &report.errors {
+        context.console().error(&tr!("pipeline.sync_failed", destination_folder, error));
+    }
+}
+
+/// Vérifie qu'aucun fichier objet (compilé ou copié) n'est produit deux fois au même endroit.
+fn check_output_collisions(context: &BuildContext, c_files: &[PathBuf], o_files: &[PathBuf]) -> Result<(), String> {
+    let mut destinations: HashMap<String, &PathBuf> = HashMap::new();
+    let mut collisions: Vec<String> = Vec::new();
+
+    let copied_objects = o_files.iter().map(|o_file| (Path::new(&context.output_path()).join(context.relative_path(o_file)), o_file));
+    let compiled_objects = c_files.iter().map(|c_file| (context.object_path(c_file), c_file));
+
+    for (destination, origin) in compiled_objects.chain(copied_objects) {
+        let mut key: String = destination.to_string_lossy().replace('\\', "/");
+        // Les systèmes de fichiers Windows ne distinguent pas la casse
+        if cfg!(windows) {
+            key = key.to_lowercase();
+        }
+
+        if let Some(previous) = destinations.insert(key, origin) {
+            if previous != origin {
+                collisions.push(tr!("pipeline.collision", previous.display(), origin.display(), destination.display()));
+            }
+        }
+    }
+
+    if collisions.is_empty() {
+        Ok(())
+    } else {
+        Err(tr!("pipeline.collisions", collisions.join("\n")))
+    }
+}
+
+/// Lance l'exécutable produit ; une fin en erreur est remontée comme échec d'exécution.
+fn execute_main(context: &BuildContext, program_args: &[String]) -> Result<(), BuildError> {
+
+    let path = context.executable_path();
+
+    let mut command = Command::new(path.clone());
+    command.args(program_args);
+
+    context.emit(BuildEvent::RunStarted { executable: PathBuf::from(&path) });
+
+    let start_time: Instant = Instant::now();
+    let status = command.status();
+    let duration: Duration = start_time.elapsed();
+    context.emit(BuildEvent::command_finished(&command, duration, status.as_ref().ok()));
+
+    context.write_in_logs(tr!("pipeline.run_duration_log", path, format_duration(duration)));
+    if context.settings.get_bool("TIMINGS", false) {
+        context.console().info(&tr!("pipeline.run_duration", format_duration(duration)));
+    }
+
+    match status {
+        Ok(status) if status.success() => {
+            context.console().info(&tr!("pipeline.run_succeeded", path));
+            Ok(())
+        }
+        Ok(status) => Err(BuildError::Run(tr!("pipeline.run_failed", path, status))),
+        Err(err) => Err(BuildError::Run(tr!("pipeline.run_launch_failed", path, err))),
+    }
+}
+
+/// Compile chaque source en fichier objet ; les échecs sont regroupés dans une seule erreur.
+async fn compile_source_to_output(context: &BuildContext, c_files: &[PathBuf], include_paths: &[String], cache: Option<&CompileCache>, workers: &[String]) -> Result<(Vec<PathBuf>, BuildTimings), BuildError> {
+    let mut jobs: Vec<(&PathBuf, PathBuf)> = Vec::new();
+
+    for c_file in c_files {
+        
+        let output_file: PathBuf = context.object_path(c_file);
+
+        if let Some(parent) = output_file.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| BuildError::Storage(tr!("pipeline.create_failed", parent.display(), err)))?;
+        }
+
+        jobs.push((c_file, output_file));
+    }
+
+    context.emit(BuildEvent::CompilePlanned { sources: jobs.len() });
+
+    let compile_job = |(c_file, output_file): &(&PathBuf, PathBuf), worker: Option<&str>| -> Result<(Duration, CompileOutcome), String> {
+        // gcc reçoit les chemins en texte : un chemin non UTF-8 ne peut pas lui être transmis tel quel
+        let (Some(c_file_str), Some(output_file_str)) = (c_file.to_str(), output_file.to_str()) else {
+            return Err(tr!("pipeline.non_utf8_path", c_file.display()));
+        };
+        let c_file_str: String = c_file_str.replace('\\', "/");
+        let output_file_str: String = output_file_str.replace('\\', "/");
+
+        context.emit(BuildEvent::CompileStarted { source: c_file.to_path_buf() });
+        let start_time: Instant = Instant::now();
+
+        match compile_single_source_to_output(context, &c_file_str, &output_file_str, include_paths, cache, worker) {
+            Ok(outcome) => {
+                let duration: Duration = start_time.elapsed();
+                context.emit(BuildEvent::CompileFinished { source: c_file.to_path_buf(), object: output_file.clone(), cached: outcome.cached, duration });
+                context.emit(BuildEvent::artifact(output_file));
+                Ok((duration, outcome))
+            }
+            Err(CompileFailure { message: err, diagnostics }) => {
+                match diagnostics {
+                    // Au format `short`, seule la sortie de gcc est affichée, pour rester lisible ligne par ligne
+                    Some(diagnostics) if context.console().format() == MessageFormat::Short => context.diagnostics(&diagnostics),
+                    _ => {
+                        context.console().error(&tr!("pipeline.compile_failed", context.relative_path(c_file).display()));
+                        context.diagnostics(&err);
+                    }
+                }
+                context.write_in_logs(tr!("pipeline.compile_failed_log", format!("{:?}", c_file), err));
+                context.emit(BuildEvent::CompileFailed { source: c_file.to_path_buf(), message: err.clone() });
+                Err(err)
+            }
+        }
+    };
+
+    let results: Vec<Result<(Duration, CompileOutcome), String>> = if workers.is_empty() {
+        jobs.iter().map(|job| compile_job(job, None)).collect()
+    } else {
+        // Un thread par worker : chacun prend la source suivante tant qu'il en reste
+        let next_job: AtomicUsize = AtomicUsize::new(0);
+        let results: Mutex<Vec<Result<(Duration, CompileOutcome), String>>> = Mutex::new(vec![Ok(Default::default()); jobs.len()]);
+        let language: Language = language();
+
+        thread::scope(|scope| {
+            for worker in workers {
+                scope.spawn(|| loop {
+                    let _language = LanguageScope::enter(language);
+                    let index: usize = next_job.fetch_add(1, Ordering::SeqCst);
+                    if index >= jobs.len() {
+                        break;
+                    }
+                    let result: Result<(Duration, CompileOutcome), String> = compile_job(&jobs[index], Some(worker));
+                    results.lock().unwrap()[index] = result;
+                });
+            }
+        });
+
+        results.into_inner().unwrap()
+    };
+
+    let mut output_files: Vec<PathBuf> = Vec::new();
+    let mut failures: Vec<(PathBuf, String)> = Vec::new();
+    let mut timings: BuildTimings = BuildTimings::default();
+
+    for ((c_file, output_file), result) in jobs.into_iter().zip(results) {
+        match result {
+            Ok((duration, outcome)) => {
+                timings.files.push(FileTiming { source: context.relative_path(c_file), duration, cached: outcome.cached, warnings: outcome.warnings });
+                timings.add_phases(&outcome.phases);
+                output_files.push(output_file);
+            }
+            Err(err) => failures.push((c_file.clone(), err)),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok((output_files, timings))
+    } else {
+        Err(BuildError::Compile(failures))
+    }
+}
+
+/// Compile une source, localement, sur un worker ou depuis le cache.
+fn compile_single_source_to_output(context: &BuildContext, source_file: &str, output_file: &str, include_paths: &[String], cache: Option<&CompileCache>, worker: Option<&str>) -> Result<CompileOutcome, CompileFailure> {
+    let mut flags: Vec<String> = vec!["-c".to_string()];
+
+    for include_path in include_paths {
+        flags.push(format!("-I{}", include_path));
+    }
+    if context.settings.get_bool("TIME_REPORT", false) {
+        flags.push("-ftime-report".to_string());
+    }
+
+    // Le cache et les workers travaillent sur le source préprocessé localement
+    let preprocessed_source: Option<Vec<u8>> = if cache.is_some() || worker.is_some() {
+        preprocess_source(context, source_file, include_paths)
+    } else {
+        None
+    };
+    let cache_key: Option<String> = cache.zip(preprocessed_source.as_ref()).map(|(cache, preprocessed)| cache.key(preprocessed, &flags, &context.project_path));
+
+    if let (Some(cache), Some(key)) = (cache, &cache_key) {
+        if let Some(entry) = cache.lookup(context, key) {
+            fs::copy(&entry.object, output_file)
+                .map_err(|err| tr!("pipeline.cache_copy_failed", err))?;
+            let origin: String = if entry.remote { tr!("pipeline.remote_cache") } else { tr!("pipeline.local_cache") };
+            context.write_in_logs(tr!("pipeline.cache_hit", source_file, origin, &key[..12]));
+            report_diagnostics(context, source_file, &entry.diagnostics);
+            return Ok(CompileOutcome { cached: true, phases: Vec::new(), warnings: count_warnings(&entry.diagnostics) });
+        }
+    }
+
+    if let (Some(worker), Some(preprocessed)) = (worker, &preprocessed_source) {
+        let source_name: &str = Path::new(source_file).file_name().and_then(|n| n.to_str()).unwrap_or(source_file);
+        let worker_flags: Vec<String> = flags.iter().filter(|flag| is_allowed_flag(flag)).cloned().collect();
+
+        let token: Option<String> = worker_token(&context.settings);
+
+        match compile_on_worker(worker, token.as_deref(), source_name, preprocessed, &worker_flags) {
+            Ok(result) if result.success => {
+                fs::write(output_file, &result.object)
+                    .map_err(|err| tr!("pipeline.worker_object_failed", err))?;
+                context.write_in_logs(tr!("pipeline.worker_compiled", worker, source_file));
+                let (diagnostics, phases) = split_time_report(&result.diagnostics);
+                record_compilation(context, source_file, output_file, &diagnostics, cache, &cache_key);
+                return Ok(CompileOutcome { cached: false, phases, warnings: count_warnings(&diagnostics) });
+            }
+            Ok(result) => {
+                let (diagnostics, _) = split_time_report(&result.diagnostics);
+                return Err(CompileFailure { message: tr!("pipeline.worker_compile_failed", worker, diagnostics), diagnostics: Some(diagnostics) });
+            }
+            Err(err) => {
+                // Worker injoignable : la source est compilée localement
+                context.write_in_logs(tr!("pipeline.worker_unavailable", worker, source_file, err));
+            }
+        }
+    }
+
+    let mut command: Command = gcc_command();
+    command.arg(source_file).args(&flags).args(["-o", output_file]);
+
+    let output: Output = run_command(context, &mut command).map_err(|err| tr!("pipeline.compile_error", err))?;
+
+    let (diagnostics, phases) = split_time_report(&String::from_utf8_lossy(&output.stderr));
+
+    if output.status.success() {
+        record_compilation(context, source_file, output_file, &diagnostics, cache, &cache_key);
+        Ok(CompileOutcome { cached: false, phases, warnings: count_warnings(&diagnostics) })
+    } else {
+        context.emit(BuildEvent::Diagnostics { source: PathBuf::from(source_file), text: diagnostics.clone() });
+        Err(CompileFailure { message: tr!("pipeline.compile_output", diagnostics, String::from_utf8_lossy(&output.stdout)), diagnostics: Some(diagnostics) })
+    }
+}
+
+/// Affiche les avertissements d'une compilation réussie et conserve le résultat dans le cache.
+fn record_compilation(context: &BuildContext, source_file: &str, output_file: &str, diagnostics: &str, cache: Option<&CompileCache>, cache_key: &Option<String>) {
+    report_diagnostics(context, source_file, diagnostics);
+
+    if let (Some(cache), Some(key)) = (cache, cache_key) {
+        if let Err(err) = cache.store(context, key, Path::new(output_file), diagnostics) {
+            context.console().error(&tr!("pipeline.cache_store_failed", err));
+        }
+    }
+}
+
+/// Préprocesse un source (`gcc -E`) ; `None` si le préprocesseur échoue.
+fn preprocess_source(context: &BuildContext, source_file: &str, include_paths: &[String]) -> Option<Vec<u8>> {
+    let mut command: Command = gcc_command();
+    command.args(["-E", source_file]);
+
+    for include_path in include_paths {
+        command.args(["-I", include_path]);
+    }
+
+    match run_command(context, &mut command) {
+        Ok(output) if output.status.success() => Some(output.stdout),
+        _ => None,
+    }
+}
+
+/// Affiche et log les avertissements d'une compilation réussie.
+fn report_diagnostics(context: &BuildContext, source_file: &str, diagnostics: &str) {
+    if !diagnostics.trim().is_empty() {
+        context.diagnostics(diagnostics);
+        context.write_in_logs(tr!("pipeline.warnings", source_file, diagnostics.trim_end()));
+        context.emit(BuildEvent::Diagnostics { source: PathBuf::from(source_file), text: diagnostics.to_string() });
+    }
+}
+
+/// Lance une commande en capturant ses sorties et publie sa durée et son code de sortie.
+fn run_command(context: &BuildContext, command: &mut Command) -> io::Result<Output> {
+    let start_time: Instant = Instant::now();
+    let output: io::Result<Output> = command.output();
+    context.emit(BuildEvent::command_finished(command, start_time.elapsed(), output.as_ref().ok().map(|output| &output.status)));
+    output
+}
+
+async fn compile_output_to_executable(context: &BuildContext, o_files: Vec<PathBuf>, include_paths: Vec<String>, library_paths: Vec<String>, libraries: Vec<String>) -> Result<Vec<u8>, std::io::Error> {
+
+    let mut command: Command = gcc_command();
+    let path_exe: String = context.executable_path();
+
+    command.args(["-o", &path_exe]).args(o_files);
+
+    for include_path in &include_paths {
+        command.args(["-I", include_path]);
+    }
+
+    // Ajouter les chemins des bibliothèques (-L)
+    for library_path in &library_paths {
+        command.args(["-L", library_path]);
+    }
+
+    // Ajouter les bibliothèques à lier (-l)
+    for library in &libraries {
+
+        let library_name = 
+            if library.ends_with(".dll") {  &library[3..library.len() - 4] } 
+            else {  library };
+
+        command.args(["-l", library_name]);
+    }
+
+    command.args(["-lm", "-Wall"]);
+    
+    context.write_in_logs(tr!("pipeline.link_command", format!("{:?}", command)));
+
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let output: Output = run_command(context, &mut command)?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !stdout.trim().is_empty() {
+        context.console().info(stdout.trim_end());
+    }
+
+    if !output.status.success() {
+        context.console().error(&tr!("pipeline.link_failed"));
+        context.diagnostics(&String::from_utf8_lossy(&output.stderr));
+        context.emit(BuildEvent::Diagnostics { source: PathBuf::from(&path_exe), text: String::from_utf8_lossy(&output.stderr).to_string() });
+        return Err(io::Error::other(tr!("pipeline.link_status", output.status)));
+    }
+
+    Ok(output.stdout)
+}
+
+async fn build_source(context: &BuildContext, c_files: &[PathBuf], include_paths: &[String], cache: Option<&CompileCache>, workers: &[String]) -> Result<(Vec<PathBuf>, BuildTimings), BuildError> {
+    compile_source_to_output(context, c_files, include_paths, cache, workers).await
+}
+
+
+/// Lance l'édition de liens de l'exécutable ; renvoie sa durée.
+async fn build_execute(context: &BuildContext, o_files: Vec<PathBuf>, include_paths: Vec<String>, library_paths: Vec<String>, libraries: Vec<String>) -> Result<Duration, BuildError> {
+
+    context.emit(BuildEvent::LinkStarted { objects: o_files.len() });
+    let start_time: Instant = Instant::now();
+
+    compile_output_to_executable(context, o_files, include_paths, library_paths, libraries).await
+        .map_err(|err| BuildError::Link(err.to_string()))?;
+
+    let duration: Duration = start_time.elapsed();
+    context.emit(BuildEvent::artifact(Path::new(&context.executable_path())));
+    context.emit(BuildEvent::LinkFinished { executable: PathBuf::from(context.executable_path()), duration });
+
+    Ok(duration)
+
+}
+
+/// Exécute les hooks d'une étape ; le premier échec interrompt le build.
+fn run_build_hooks(context: &BuildContext, phase: HookPhase) -> Result<(), BuildError> {
+    run_hooks(context, phase).map_err(BuildError::Hook)
+}
+
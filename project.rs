@@ -0,0 +1,135 @@
+//! Point d'entrée de la bibliothèque : un projet C ouvert, configuré puis compilé.
+//!
+//! ```no_run
+//! use compilerc::{BuildEvent, Project};
+//!
+//! # async fn example() -> Result<(), compilerc::BuildError> {
+//! let mut project = Project::open("exemples/demo", "build")?;
+//! project.settings_mut().set("CACHE", "off");
+//! project.on_event(|event: &BuildEvent| println!("{:?}", event));
+//!
+//! let inventory = project.scan()?;
+//! let executable = project.build(inventory).await?;
+//! println!("{}", executable.display());
+//! project.run(&[])?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::clean::{CleanOptions, CleanReport, clean_target};
+use crate::context::BuildContext;
+use crate::error::BuildError;
+use crate::events::{BuildEvent, EventHandler};
+use crate::pipeline;
+use crate::scanner::ProjectInventory;
+use crate::settings::Settings;
+use crate::tr;
+
+/// Projet C à compiler vers un dossier cible.
+#[derive(Debug, Clone)]
+pub struct Project {
+    context: BuildContext,
+}
+
+impl Project {
+    /// Ouvre un projet et charge son `settings.txt` ; les fichiers produits iront dans `<cible>/<projet>`.
+    ///
+    /// Chaque opération du projet affiche ses messages dans la langue de sa clé `LANGUAGE`.
+    pub fn open(project_path: &str, target_path: &str) -> Result<Project, BuildError> {
+        if !Path::new(project_path).is_dir() {
+            return Err(BuildError::Discovery(tr!("project.missing", project_path)));
+        }
+        if Path::new(target_path).exists() && !Path::new(target_path).is_dir() {
+            return Err(BuildError::Config(tr!("project.target_not_directory", target_path)));
+        }
+
+        Ok(Project { context: BuildContext::new(project_path, target_path) })
+    }
+
+    /// Contexte du build : chemins, fichier de log et configuration.
+    pub fn context(&self) -> &BuildContext {
+        &self.context
+    }
+
+    /// Configuration du projet, modifiable avant le build.
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.context.settings
+    }
+
+    /// Enregistre la fonction appelée à chaque étape du build.
+    pub fn on_event(&mut self, handler: impl Fn(&BuildEvent) + Send + Sync + 'static) {
+        self.context.events = EventHandler::new(handler);
+    }
+
+    /// Recherche les fichiers du projet.
+    pub fn scan(&self) -> Result<ProjectInventory, BuildError> {
+        let _language = self.context.language_scope();
+        pipeline::scan(&self.context)
+    }
+
+    /// Compile et lie les fichiers trouvés ; renvoie le chemin de l'exécutable.
+    pub async fn build(&self, inventory: ProjectInventory) -> Result<PathBuf, BuildError> {
+        // Le build ne rend jamais la main avant la fin : il reste sur le thread qui porte la langue
+        let _language = self.context.language_scope();
+        pipeline::build(&self.context, inventory).await
+    }
+
+    /// Vérifie la syntaxe des sources trouvées, sans compiler.
+    pub fn check(&self, inventory: &ProjectInventory) -> Result<(), BuildError> {
+        let _language = self.context.language_scope();
+        pipeline::check(&self.context, inventory)
+    }
+
+    /// Lance l'exécutable produit par le dernier build.
+    pub fn run(&self, program_args: &[String]) -> Result<(), BuildError> {
+        let _language = self.context.language_scope();
+        pipeline::run(&self.context, program_args)
+    }
+
+    /// Lance les tests du projet après un build.
+    pub fn test(&self) -> Result<(), BuildError> {
+        let _language = self.context.language_scope();
+        pipeline::test(&self.context)
+    }
+
+    /// Supprime les fichiers des portées demandées dans la cible ; renvoie les fichiers concernés.
+    pub fn clean(&self, options: &CleanOptions) -> Result<CleanReport, BuildError> {
+        let _language = self.context.language_scope();
+        clean_target(&self.context, options)
+    }
+
+    /// Copie l'exécutable et ses bibliothèques dans un dossier ; renvoie les fichiers copiés.
+    pub fn export(&self, destination: &str) -> Result<Vec<PathBuf>, BuildError> {
+        let _language = self.context.language_scope();
+        pipeline::export(&self.context, destination)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn operations_use_the_language_of_the_project() {
+        let folder: PathBuf = std::env::temp_dir().join(format!("compilerc-project-{}-language", std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(folder.join("projet")).unwrap();
+        fs::write(folder.join("projet").join("settings.txt"), "LANGUAGE : en\n").unwrap();
+
+        // La cible du projet est le dossier du projet : le nettoyage est refusé
+        let mut project: Project = Project::open(&folder.join("projet").to_string_lossy(), &folder.to_string_lossy()).unwrap();
+        let _french = crate::messages::LanguageScope::enter(crate::messages::Language::French);
+
+        let Err(BuildError::Config(message)) = project.clean(&CleanOptions::all()) else { panic!("nettoyage accepté") };
+        assert!(message.starts_with("clean refused"), "{}", message);
+
+        project.settings_mut().set("LANGUAGE", "fr");
+        let Err(BuildError::Config(message)) = project.clean(&CleanOptions::all()) else { panic!("nettoyage accepté") };
+        assert!(message.starts_with("nettoyage refusé"), "{}", message);
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,357 @@
+//! Cache de compilation distant, accessible en HTTP par de simples `GET` / `PUT`.
+//!
+//! Une entrée est stockée à l'adresse `<REMOTE_CACHE>/<clé>` sous la forme d'un en-tête texte
+//! suivi du fichier objet et des diagnostics. L'en-tête contient la clé et l'empreinte SHA-256 de l'entrée,
+//! vérifiées à chaque téléchargement : un simple serveur de fichiers suffit côté serveur.
+//!
+//! Un serveur injoignable désactive le cache distant pour le reste du build, pour ne pas attendre
+//! le délai de connexion à chaque source.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use crate::settings::Settings;
+use crate::tr;
+
+const ENTRY_MAGIC: &str = "COMPILERC-CACHE 2";
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Contenu d'une entrée du cache distant.
+#[derive(Debug, Clone)]
+pub struct RemoteEntry {
+    pub object: Vec<u8>,
+    pub diagnostics: String,
+}
+
+/// Serveur HTTP utilisé comme cache partagé entre les machines.
+#[derive(Debug)]
+pub struct RemoteCache {
+    host: String,
+    port: u16,
+    base_path: String,
+    pub read_only: bool,
+    /// Le serveur n'a pas pu être joint : les lectures et écritures suivantes sont ignorées.
+    unavailable: AtomicBool,
+}
+
+impl RemoteCache {
+    /// Construit le cache distant déclaré par `REMOTE_CACHE` (et `REMOTE_CACHE_READ_ONLY`).
+    pub fn from_settings(settings: &Settings) -> Option<Result<RemoteCache, String>> {
+        let url: &str = settings.get("REMOTE_CACHE")?;
+        let read_only: bool = settings.get_bool("REMOTE_CACHE_READ_ONLY", false);
+
+        Some(RemoteCache::parse_url(url).map(|(host, port, base_path)| RemoteCache { host, port, base_path, read_only, unavailable: AtomicBool::new(false) }))
+    }
+
+    /// Découpe une adresse `http://hote[:port]/chemin`.
+    fn parse_url(url: &str) -> Result<(String, u16, String), String> {
+        let rest: &str = url
+            .strip_prefix("http://")
+            .ok_or_else(|| tr!("remote_cache.unsupported_url", url))?;
+
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse::<u16>().map_err(|_| tr!("remote_cache.invalid_port", url))?),
+            None => (authority, 80),
+        };
+
+        if host.is_empty() {
+            return Err(tr!("remote_cache.missing_host", url));
+        }
+
+        Ok((host.to_string(), port, path.trim_end_matches('/').to_string()))
+    }
+
+    /// Télécharge une entrée ; `Ok(None)` si le serveur ne la connaît pas ou a été désactivé.
+    pub fn fetch(&self, key: &str) -> Result<Option<RemoteEntry>, String> {
+        if self.unavailable.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+
+        let (status, body) = self.request("GET", key, &[])?;
+
+        match status {
+            200 => {
+                // L'empreinte ne garantit que la cohérence de l'entrée : elle doit aussi être celle de la clé demandée
+                let (entry_key, entry) = decode_entry(&body)?;
+                if entry_key != key {
+                    return Err(tr!("remote_cache.wrong_key", &key[..key.len().min(12)]));
+                }
+                Ok(Some(entry))
+            }
+            404 => Ok(None),
+            _ => Err(tr!("remote_cache.http_status", status)),
+        }
+    }
+
+    /// Envoie une entrée ; sans effet en lecture seule ou si le serveur a été désactivé.
+    pub fn upload(&self, key: &str, object: &[u8], diagnostics: &str) -> Result<(), String> {
+        if self.read_only || self.unavailable.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let (status, _) = self.request("PUT", key, &encode_entry(key, object, diagnostics))?;
+
+        if (200..300).contains(&status) {
+            Ok(())
+        } else {
+            Err(tr!("remote_cache.upload_status", status))
+        }
+    }
+
+    fn request(&self, method: &str, key: &str, body: &[u8]) -> Result<(u16, Vec<u8>), String> {
+        let mut stream: TcpStream = self.connect().map_err(|err| {
+            self.unavailable.store(true, Ordering::Relaxed);
+            tr!("remote_cache.disabled", err)
+        })?;
+        let _ = stream.set_read_timeout(Some(TIMEOUT));
+        let _ = stream.set_write_timeout(Some(TIMEOUT));
+
+        let header: String = format!(
+            "{} {}/{} HTTP/1.1\r\nHost: {}:{}\r\nContent-Length: {}\r\nContent-Type: application/octet-stream\r\nConnection: close\r\n\r\n",
+            method,
+            self.base_path,
+            key,
+            self.host,
+            self.port,
+            body.len()
+        );
+
+        stream
+            .write_all(header.as_bytes())
+            .and_then(|_| stream.write_all(body))
+            .map_err(|err| tr!("remote_cache.send_failed", err))?;
+
+        let mut response: Vec<u8> = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .map_err(|err| tr!("remote_cache.read_failed", err))?;
+
+        parse_response(&response)
+    }
+
+    fn connect(&self) -> Result<TcpStream, String> {
+        let address = (self.host.as_str(), self.port)
+            .to_socket_addrs()
+            .map_err(|err| tr!("remote_cache.host_not_found", self.host, err))?
+            .next()
+            .ok_or_else(|| tr!("remote_cache.no_address", self.host))?;
+
+        TcpStream::connect_timeout(&address, TIMEOUT).map_err(|err| tr!("remote_cache.connect_failed", address, err))
+    }
+}
+
+/// Extrait le code de statut et le corps d'une réponse HTTP/1.1.
+fn parse_response(response: &[u8]) -> Result<(u16, Vec<u8>), String> {
+    let header_end: usize = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| tr!("remote_cache.incomplete_response"))?;
+
+    let header: String = String::from_utf8_lossy(&response[..header_end]).to_string();
+    let body: &[u8] = &response[header_end + 4..];
+
+    let status: u16 = header
+        .lines()
+        .next()
+        .and_then(|status_line| status_line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| tr!("remote_cache.invalid_status_line"))?;
+
+    let header_value = |name: &str| -> Option<String> {
+        header.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim().eq_ignore_ascii_case(name).then(|| value.trim().to_string())
+        })
+    };
+
+    if header_value("Transfer-Encoding").is_some_and(|value| value.eq_ignore_ascii_case("chunked")) {
+        return decode_chunked(body).map(|body| (status, body));
+    }
+
+    match header_value("Content-Length").and_then(|value| value.parse::<usize>().ok()) {
+        Some(length) if length <= body.len() => Ok((status, body[..length].to_vec())),
+        Some(_) => Err(tr!("remote_cache.truncated_response")),
+        None => Ok((status, body.to_vec())),
+    }
+}
+
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decoded: Vec<u8> = Vec::new();
+
+    loop {
+        let line_end: usize = body.windows(2).position(|window| window == b"\r\n").ok_or_else(|| tr!("remote_cache.invalid_chunk"))?;
+        let size_text: String = String::from_utf8_lossy(&body[..line_end]).to_string();
+        let size: usize = usize::from_str_radix(size_text.split(';').next().unwrap_or("").trim(), 16)
+            .map_err(|_| tr!("remote_cache.invalid_chunk_size"))?;
+
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Ok(decoded);
+        }
+        if size.checked_add(2).is_none_or(|chunk_end| body.len() < chunk_end) {
+            return Err(tr!("remote_cache.truncated_chunk"));
+        }
+        if &body[size..size + 2] != b"\r\n" {
+            return Err(tr!("remote_cache.unterminated_chunk"));
+        }
+
+        decoded.extend_from_slice(&body[..size]);
+        body = &body[size + 2..];
+    }
+}
+
+fn content_hash(key: &str, object: &[u8], diagnostics: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(key.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(object);
+    hasher.update(diagnostics);
+    format!("{:x}", hasher.finalize())
+}
+
+fn encode_entry(key: &str, object: &[u8], diagnostics: &str) -> Vec<u8> {
+    let mut entry: Vec<u8> = format!(
+        "{}\nkey : {}\nsha256 : {}\nobject : {}\ndiagnostics : {}\n\n",
+        ENTRY_MAGIC,
+        key,
+        content_hash(key, object, diagnostics.as_bytes()),
+        object.len(),
+        diagnostics.len()
+    )
+    .into_bytes();
+
+    entry.extend_from_slice(object);
+    entry.extend_from_slice(diagnostics.as_bytes());
+    entry
+}
+
+/// Décode une entrée et vérifie son empreinte ; renvoie la clé inscrite dans l'entrée avec son contenu.
+fn decode_entry(entry: &[u8]) -> Result<(String, RemoteEntry), String> {
+    let header_end: usize = entry
+        .windows(2)
+        .position(|window| window == b"\n\n")
+        .ok_or_else(|| tr!("remote_cache.invalid_entry"))?;
+
+    let header: String = String::from_utf8_lossy(&entry[..header_end]).to_string();
+    let payload: &[u8] = &entry[header_end + 2..];
+
+    if header.lines().next() != Some(ENTRY_MAGIC) {
+        return Err(tr!("remote_cache.unknown_format"));
+    }
+
+    let fields = Settings::parse(&header);
+    let size = |key: &str| -> Result<usize, String> {
+        fields.get(key).and_then(|value| value.parse().ok()).ok_or_else(|| tr!("remote_cache.missing_field", key))
+    };
+    let (object_size, diagnostics_size) = (size("OBJECT")?, size("DIAGNOSTICS")?);
+
+    if object_size.checked_add(diagnostics_size) != Some(payload.len()) {
+        return Err(tr!("remote_cache.wrong_size"));
+    }
+
+    let key: String = fields.get("KEY").ok_or_else(|| tr!("remote_cache.missing_field", "KEY"))?.to_string();
+    let (object, diagnostics) = payload.split_at(object_size);
+    if fields.get("SHA256") != Some(content_hash(&key, object, diagnostics).as_str()) {
+        return Err(tr!("remote_cache.invalid_hash"));
+    }
+
+    Ok((key, RemoteEntry { object: object.to_vec(), diagnostics: String::from_utf8_lossy(diagnostics).to_string() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunked_bodies_are_reassembled() {
+        assert_eq!(decode_chunked(b"4\r\nWiki\r\n6;ext=1\r\npedia \r\n0\r\n\r\n").unwrap(), b"Wikipedia ");
+        assert_eq!(decode_chunked(b"0\r\n\r\n").unwrap(), b"");
+    }
+
+    #[test]
+    fn malformed_chunks_are_rejected() {
+        // Taille non hexadécimale, bloc tronqué, bloc plus long que sa taille, taille sans fin de ligne
+        assert!(decode_chunked(b"zz\r\nabc\r\n0\r\n\r\n").is_err());
+        assert!(decode_chunked(b"a\r\nabc\r\n").is_err());
+        assert!(decode_chunked(b"3\r\nabcdef\r\n0\r\n\r\n").is_err());
+        assert!(decode_chunked(b"3").is_err());
+        assert!(decode_chunked(b"ffffffffffffffff\r\nabc\r\n").is_err());
+    }
+
+    #[test]
+    fn chunked_responses_are_decoded() {
+        let response: &[u8] = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n0\r\n\r\n";
+        assert_eq!(parse_response(response).unwrap(), (200, b"abc".to_vec()));
+
+        let response: &[u8] = b"HTTP/1.1 404 Not Found\r\nContent-Length: 10\r\n\r\nabc";
+        assert!(parse_response(response).is_err());
+    }
+
+    #[test]
+    fn entries_round_trip_and_corruption_is_detected() {
+        let entry: Vec<u8> = encode_entry("ab12", b"\x7fELF", "main.c:1:1: warning: x");
+        let (key, decoded) = decode_entry(&entry).unwrap();
+        assert_eq!(key, "ab12");
+        assert_eq!(decoded.object, b"\x7fELF");
+        assert_eq!(decoded.diagnostics, "main.c:1:1: warning: x");
+
+        let mut corrupted: Vec<u8> = entry.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(decode_entry(&corrupted).is_err());
+        assert!(decode_entry(&entry[..entry.len() - 1]).is_err());
+
+        let renamed: Vec<u8> = String::from_utf8_lossy(&entry).replacen("key : ab12", "key : cd34", 1).into_bytes();
+        assert!(decode_entry(&renamed).is_err());
+    }
+
+    /// Serveur local qui répond une fois `response` à chaque connexion ; renvoie son adresse.
+    fn serve(response: Vec<u8>, connections: usize) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address: String = format!("http://{}/cache", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(connections) {
+                let mut stream: TcpStream = stream.unwrap();
+                let mut request = [0u8; 4096];
+                let _ = stream.read(&mut request);
+                let _ = stream.write_all(&response);
+            }
+        });
+        address
+    }
+
+    fn remote_cache(url: &str) -> RemoteCache {
+        RemoteCache::from_settings(&Settings::parse(&format!("REMOTE_CACHE : {}", url))).unwrap().unwrap()
+    }
+
+    #[test]
+    fn an_entry_for_another_key_is_refused() {
+        let entry: Vec<u8> = encode_entry("bbbbbbbbbbbbbbbb", b"objet", "");
+        let mut response: Vec<u8> = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", entry.len()).into_bytes();
+        response.extend_from_slice(&entry);
+        let remote: RemoteCache = remote_cache(&serve(response, 2));
+
+        assert_eq!(remote.fetch("bbbbbbbbbbbbbbbb").unwrap().unwrap().object, b"objet");
+        assert!(remote.fetch("aaaaaaaaaaaaaaaa").is_err());
+    }
+
+    #[test]
+    fn an_unreachable_server_is_disabled_after_the_first_failure() {
+        // Port libéré aussitôt réservé : la connexion est refusée
+        let address = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let remote: RemoteCache = remote_cache(&format!("http://{}", address));
+
+        assert!(remote.fetch("aaaaaaaaaaaaaaaa").is_err());
+        assert!(remote.fetch("aaaaaaaaaaaaaaaa").unwrap().is_none());
+        assert!(remote.upload("aaaaaaaaaaaaaaaa", b"objet", "").is_ok());
+    }
+}
@@ -0,0 +1,454 @@
+//! Rapport HTML d'une exécution, écrit dans son dossier de logs (`logs/<id>_<date>_<heure>/report.html`).
+//!
+//! Le rapport est autonome (styles intégrés, aucune ressource externe) et reprend les événements de
+//! l'exécution : inventaire du projet, graphe des inclusions, commandes lancées, diagnostics regroupés
+//! par fichier avec un extrait des sources, durées et tailles des fichiers produits.
+//! Il est désactivé par `HTML_REPORT : off`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::context::BuildContext;
+use crate::diagnostics::{is_context_line, parse_diagnostic};
+use crate::events::BuildEvent;
+use crate::messages::language;
+use crate::timings::format_duration;
+use crate::tr;
+
+/// Nom du rapport dans le dossier de l'exécution.
+pub const REPORT_FILE_NAME: &str = "report.html";
+
+/// Lignes affichées avant et après la ligne d'un diagnostic.
+const SNIPPET_CONTEXT: usize = 2;
+
+/// Événements conservés pour le rapport, partagés entre les threads du build.
+#[derive(Debug, Clone, Default)]
+pub struct ReportRecorder(Arc<Mutex<Vec<BuildEvent>>>);
+
+impl ReportRecorder {
+    /// Conserve un événement.
+    pub fn record(&self, event: &BuildEvent) {
+        if let Ok(mut events) = self.0.lock() {
+            events.push(event.clone());
+        }
+    }
+
+    /// Événements conservés, dans l'ordre d'émission.
+    pub fn events(&self) -> Vec<BuildEvent> {
+        self.0.lock().map(|events| events.clone()).unwrap_or_default()
+    }
+}
+
+/// Écrit le rapport de l'exécution dans son dossier de logs et renvoie son chemin.
+pub fn write_report(context: &BuildContext) -> io::Result<PathBuf> {
+    let path: PathBuf = Path::new(context.log_path()).join(REPORT_FILE_NAME);
+    fs::create_dir_all(context.log_path())?;
+    fs::write(&path, render_report(context, &context.report.events()))?;
+    Ok(path)
+}
+
+/// Page HTML complète du rapport.
+pub fn render_report(context: &BuildContext, events: &[BuildEvent]) -> String {
+    let mut html: String = String::new();
+
+    html.push_str(&format!("<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n", language().code()));
+    html.push_str(&format!("<title>{}</title>\n", tr!("report.title", context.run_id(), escape(&context.project_name))));
+    html.push_str(STYLE);
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{} <small>{}</small></h1>\n", escape(&context.project_name), tr!("report.run", context.run_id())));
+
+    summary_section(&mut html, context, events);
+    inventory_section(&mut html, context, events);
+    include_graph_section(&mut html, context, events);
+    commands_section(&mut html, events);
+    diagnostics_section(&mut html, context, events);
+    timings_section(&mut html, context, events);
+    artifacts_section(&mut html, context, events);
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn summary_section(html: &mut String, context: &BuildContext, events: &[BuildEvent]) {
+    let finished: Option<&BuildEvent> = events.iter().rev().find(|event| matches!(event, BuildEvent::Finished { .. }));
+    let (status, class): (String, &str) = match finished {
+        Some(BuildEvent::Finished { success: true, .. }) => (tr!("report.success"), "success"),
+        Some(BuildEvent::Finished { exit_code, message, .. }) => (tr!("report.failure", exit_code, message), "failure"),
+        _ => (tr!("report.unfinished"), "failure"),
+    };
+    let duration: String = match finished {
+        Some(BuildEvent::Finished { duration, .. }) => format_duration(*duration),
+        _ => "-".to_string(),
+    };
+
+    html.push_str(&format!("<h2>{}</h2>\n<table>\n", tr!("report.summary")));
+    html.push_str(&format!("<tr><th>{}</th><td class=\"{}\">{}</td></tr>\n", tr!("report.result"), class, escape(&status)));
+    html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", tr!("report.duration"), duration));
+    html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", tr!("report.profile"), escape(&context.profile())));
+    html.push_str(&format!("<tr><th>{}</th><td><code>{}</code></td></tr>\n", tr!("report.project"), escape(&context.project_path)));
+    html.push_str(&format!("<tr><th>{}</th><td><code>{}</code></td></tr>\n", tr!("report.target"), escape(&context.target_path)));
+    html.push_str("</table>\n");
+}
+
+fn inventory_section(html: &mut String, context: &BuildContext, events: &[BuildEvent]) {
+    let mut files: BTreeMap<&str, Vec<&PathBuf>> = BTreeMap::new();
+    for event in events {
+        if let BuildEvent::FileDiscovered { path, kind } = event {
+            files.entry(kind).or_default().push(path);
+        }
+    }
+
+    html.push_str(&format!("<h2>{}</h2>\n", tr!("report.inventory")));
+    if files.is_empty() {
+        html.push_str(&format!("<p class=\"empty\">{}</p>\n", tr!("report.no_files")));
+        return;
+    }
+
+    for (kind, paths) in files {
+        html.push_str(&format!("<details><summary><code>.{}</code> : {}</summary>\n<ul>\n", escape(kind), tr!("report.file_count", paths.len())));
+        for path in paths {
+            html.push_str(&format!("<li><code>{}</code></li>\n", escape(&context.relative_path(path).to_string_lossy())));
+        }
+        html.push_str("</ul>\n</details>\n");
+    }
+}
+
+fn include_graph_section(html: &mut String, context: &BuildContext, events: &[BuildEvent]) {
+    let files: Vec<&PathBuf> = events
+        .iter()
+        .filter_map(|event| match event {
+            BuildEvent::FileDiscovered { path, kind: "c" | "h" } => Some(path),
+            _ => None,
+        })
+        .collect();
+
+    html.push_str(&format!("<h2>{}</h2>\n", tr!("report.includes")));
+    if files.is_empty() {
+        html.push_str(&format!("<p class=\"empty\">{}</p>\n", tr!("report.no_sources")));
+        return;
+    }
+
+    // Les inclusions entre guillemets sont cherchées à côté du fichier puis dans les dossiers des en-têtes
+    let include_directories: BTreeSet<PathBuf> = files
+        .iter()
+        .filter(|file| file.extension().is_some_and(|extension| extension == "h"))
+        .filter_map(|file| file.parent().map(Path::to_path_buf))
+        .collect();
+    let known_files: BTreeMap<PathBuf, &PathBuf> = files.iter().filter_map(|file| Some((fs::canonicalize(file).ok()?, *file))).collect();
+
+    let mut graph: BTreeMap<String, Vec<(String, bool)>> = BTreeMap::new();
+    let mut included_by: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for file in &files {
+        let name: String = context.relative_path(file).to_string_lossy().to_string();
+        let content: String = fs::read_to_string(file).unwrap_or_default();
+        let mut includes: Vec<(String, bool)> = Vec::new();
+
+        for (included, quoted) in content.lines().filter_map(parse_include) {
+            let resolved: Option<&PathBuf> = if quoted {
+                file.parent()
+                    .into_iter()
+                    .chain(include_directories.iter().map(PathBuf::as_path))
+                    .filter_map(|directory| fs::canonicalize(directory.join(&included)).ok())
+                    .find_map(|candidate| known_files.get(&candidate).copied())
+            } else {
+                None
+            };
+
+            match resolved {
+                Some(target) => {
+                    let target_name: String = context.relative_path(target).to_string_lossy().to_string();
+                    included_by.entry(target_name.clone()).or_default().push(name.clone());
+                    includes.push((target_name, true));
+                }
+                None => includes.push((included, false)),
+            }
+        }
+
+        graph.insert(name, includes);
+    }
+
+    html.push_str(&format!("<table>\n<tr><th>{}</th><th>{}</th><th>{}</th></tr>\n", tr!("report.file"), tr!("report.includes_column"), tr!("report.included_by")));
+    for (name, includes) in &graph {
+        let includes: Vec<String> = includes
+            .iter()
+            .map(|(included, project)| {
+                if *project {
+                    format!("<a href=\"#{}\"><code>{}</code></a>", anchor("file", included), escape(included))
+                } else {
+                    format!("<code class=\"external\">{}</code>", escape(included))
+                }
+            })
+            .collect();
+        let parents: Vec<String> = included_by
+            .get(name)
+            .map(|parents| parents.iter().map(|parent| format!("<a href=\"#{}\"><code>{}</code></a>", anchor("file", parent), escape(parent))).collect())
+            .unwrap_or_default();
+
+        html.push_str(&format!(
+            "<tr id=\"{}\"><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+            anchor("file", name),
+            escape(name),
+            includes.join(" "),
+            parents.join(" ")
+        ));
+    }
+    html.push_str("</table>\n");
+}
+
+fn commands_section(html: &mut String, events: &[BuildEvent]) {
+    html.push_str(&format!("<h2>{}</h2>\n", tr!("report.commands")));
+    let commands: Vec<&BuildEvent> = events.iter().filter(|event| matches!(event, BuildEvent::CommandFinished { .. })).collect();
+    if commands.is_empty() {
+        html.push_str(&format!("<p class=\"empty\">{}</p>\n", tr!("report.no_commands")));
+        return;
+    }
+
+    html.push_str(&format!("<table>\n<tr><th>{}</th><th>{}</th><th>{}</th></tr>\n", tr!("report.command"), tr!("report.duration"), tr!("report.status")));
+    for command in commands {
+        if let BuildEvent::CommandFinished { program, args, duration, status, success } = command {
+            let status: String = status.map(|code| code.to_string()).unwrap_or_else(|| "-".to_string());
+            html.push_str(&format!(
+                "<tr><td><code>{} {}</code></td><td class=\"number\">{}</td><td class=\"{}\">{}</td></tr>\n",
+                escape(program),
+                escape(&args.join(" ")),
+                format_duration(*duration),
+                if *success { "success" } else { "failure" },
+                status
+            ));
+        }
+    }
+    html.push_str("</table>\n");
+}
+
+fn diagnostics_section(html: &mut String, context: &BuildContext, events: &[BuildEvent]) {
+    html.push_str(&format!("<h2>{}</h2>\n", tr!("report.diagnostics")));
+
+    // Plusieurs événements peuvent reprendre le même diagnostic (compilation puis cache)
+    let mut by_file: BTreeMap<String, BTreeSet<(usize, usize, String, String)>> = BTreeMap::new();
+    let mut unlocated: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for event in events {
+        if let BuildEvent::Diagnostics { source, text } = event {
+            for line in text.lines() {
+                match parse_diagnostic(line) {
+                    Some(diagnostic) => {
+                        by_file.entry(diagnostic.file).or_default().insert((diagnostic.line, diagnostic.column, diagnostic.severity, diagnostic.message));
+                    }
+                    // Messages sans position, de l'éditeur de liens notamment
+                    None if !is_context_line(line) => {
+                        let messages: &mut Vec<String> = unlocated.entry(context.relative_path(source).to_string_lossy().to_string()).or_default();
+                        if !messages.iter().any(|message| message == line) {
+                            messages.push(line.to_string());
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    if by_file.is_empty() && unlocated.is_empty() {
+        html.push_str(&format!("<p class=\"empty\">{}</p>\n", tr!("report.no_diagnostics")));
+        return;
+    }
+
+    for (file, diagnostics) in &by_file {
+        let source: Option<String> = read_source(context, file);
+        let display_name: String = context.relative_path(Path::new(file)).to_string_lossy().to_string();
+        html.push_str(&format!("<h3><code>{}</code> : {}</h3>\n", escape(&display_name), tr!("report.diagnostic_count", diagnostics.len())));
+
+        for (line, column, severity, message) in diagnostics {
+            html.push_str(&format!(
+                "<div class=\"diagnostic\"><p><span class=\"{}\">{}</span> {} : {}</p>\n",
+                escape(severity),
+                escape(severity),
+                tr!("report.location", line, column),
+                escape(message)
+            ));
+            if let Some(source) = &source {
+                html.push_str(&snippet(source, *line));
+            }
+            html.push_str("</div>\n");
+        }
+    }
+
+    for (source, messages) in &unlocated {
+        html.push_str(&format!("<h3><code>{}</code> : {}</h3>\n<pre>", escape(source), tr!("report.other_messages")));
+        html.push_str(&escape(&messages.join("\n")));
+        html.push_str("</pre>\n");
+    }
+}
+
+fn timings_section(html: &mut String, context: &BuildContext, events: &[BuildEvent]) {
+    let mut files: Vec<(&PathBuf, Duration, bool)> = events
+        .iter()
+        .filter_map(|event| match event {
+            BuildEvent::CompileFinished { source, duration, cached, .. } => Some((source, *duration, *cached)),
+            _ => None,
+        })
+        .collect();
+    let link: Option<Duration> = events.iter().find_map(|event| match event {
+        BuildEvent::LinkFinished { duration, .. } => Some(*duration),
+        _ => None,
+    });
+
+    html.push_str(&format!("<h2>{}</h2>\n", tr!("report.durations")));
+    if files.is_empty() && link.is_none() {
+        html.push_str(&format!("<p class=\"empty\">{}</p>\n", tr!("report.no_compilation")));
+        return;
+    }
+
+    let compile_total: Duration = files.iter().map(|(_, duration, _)| *duration).sum();
+    html.push_str(&format!("<p>{}", tr!("report.compile_total", files.len(), format_duration(compile_total))));
+    if let Some(link) = link {
+        html.push_str(&format!(", {}", tr!("report.link_total", format_duration(link))));
+    }
+    html.push_str("</p>\n");
+
+    files.sort_by_key(|(_, duration, _)| std::cmp::Reverse(*duration));
+    let longest: f64 = files.first().map(|(_, duration, _)| duration.as_secs_f64()).unwrap_or(0.0);
+
+    html.push_str(&format!("<table>\n<tr><th>{}</th><th>{}</th><th></th></tr>\n", tr!("report.source"), tr!("report.duration")));
+    for (source, duration, cached) in files {
+        let width: f64 = if longest == 0.0 { 0.0 } else { duration.as_secs_f64() * 100.0 / longest };
+        html.push_str(&format!(
+            "<tr><td><code>{}</code>{}</td><td class=\"number\">{}</td><td class=\"bar\"><span style=\"width: {:.1}%\"></span></td></tr>\n",
+            escape(&context.relative_path(source).to_string_lossy()),
+            if cached { " <small>(cache)</small>" } else { "" },
+            format_duration(duration),
+            width
+        ));
+    }
+    html.push_str("</table>\n");
+}
+
+fn artifacts_section(html: &mut String, context: &BuildContext, events: &[BuildEvent]) {
+    // Un même fichier peut être produit plusieurs fois : seule sa dernière taille compte
+    let mut artifacts: BTreeMap<&PathBuf, u64> = BTreeMap::new();
+    for event in events {
+        if let BuildEvent::ArtifactProduced { path, size } = event {
+            artifacts.insert(path, *size);
+        }
+    }
+
+    html.push_str(&format!("<h2>{}</h2>\n", tr!("report.artifacts")));
+    if artifacts.is_empty() {
+        html.push_str(&format!("<p class=\"empty\">{}</p>\n", tr!("report.no_artifacts")));
+        return;
+    }
+
+    let total: u64 = artifacts.values().sum();
+    html.push_str(&format!("<table>\n<tr><th>{}</th><th>{}</th></tr>\n", tr!("report.file"), tr!("report.size")));
+    for (path, size) in artifacts {
+        let name: &Path = path.strip_prefix(&context.target_path).unwrap_or(path);
+        html.push_str(&format!(
+            "<tr><td><code>{}</code></td><td class=\"number\">{}</td></tr>\n",
+            escape(&name.to_string_lossy()),
+            tr!("report.bytes", size)
+        ));
+    }
+    html.push_str(&format!("<tr><th>{}</th><th class=\"number\">{}</th></tr>\n</table>\n", tr!("report.total"), tr!("report.bytes", total)));
+}
+
+/// Extrait le fichier d'une ligne `#include` et indique s'il est entre guillemets.
+fn parse_include(line: &str) -> Option<(String, bool)> {
+    let rest: &str = line.trim_start().strip_prefix('#')?.trim_start().strip_prefix("include")?.trim();
+    let (close, quoted): (char, bool) = match rest.chars().next()? {
+        '"' => ('"', true),
+        '<' => ('>', false),
+        _ => return None,
+    };
+    let end: usize = rest[1..].find(close)?;
+    Some((rest[1..1 + end].to_string(), quoted))
+}
+
+/// Contenu d'un fichier cité par gcc, relatif au dossier courant ou au projet.
+fn read_source(context: &BuildContext, file: &str) -> Option<String> {
+    fs::read_to_string(file).or_else(|_| fs::read_to_string(Path::new(&context.project_path).join(file))).ok()
+}
+
+/// Extrait numéroté des lignes autour de `line`, la ligne concernée mise en évidence.
+fn snippet(source: &str, line: usize) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    if line == 0 || line > lines.len() {
+        return String::new();
+    }
+
+    let first: usize = line.saturating_sub(SNIPPET_CONTEXT).max(1);
+    let last: usize = (line + SNIPPET_CONTEXT).min(lines.len());
+    let mut html: String = String::from("<pre class=\"snippet\">");
+    for number in first..=last {
+        let class: &str = if number == line { " class=\"highlight\"" } else { "" };
+        html.push_str(&format!("<span{}>{:>5} | {}</span>\n", class, number, escape(lines[number - 1])));
+    }
+    html.push_str("</pre>\n");
+    html
+}
+
+/// Identifiant HTML d'un élément, sans caractère spécial : chaque octet hors `[A-Za-z0-9]` devient `_xx`,
+/// si bien que deux noms différents (`a/b.h`, `a-b.h`) ont toujours des identifiants différents.
+fn anchor(prefix: &str, name: &str) -> String {
+    let mut anchor: String = format!("{}-", prefix);
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() {
+            anchor.push(byte as char);
+        } else {
+            anchor.push_str(&format!("_{:02x}", byte));
+        }
+    }
+    anchor
+}
+
+/// Échappe les caractères spéciaux du HTML.
+fn escape(text: &str) -> String {
+    let mut escaped: String = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const STYLE: &str = "<style>
+body { font-family: sans-serif; margin: 2em auto; max-width: 72em; color: #222; }
+h1 small { color: #777; font-weight: normal; font-size: 0.6em; }
+h2 { border-bottom: 1px solid #ccc; padding-bottom: 0.2em; margin-top: 1.5em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.25em 0.6em; border-bottom: 1px solid #eee; vertical-align: top; }
+td.number, th.number { text-align: right; white-space: nowrap; }
+td.bar { width: 30%; }
+td.bar span { display: block; height: 0.8em; background: #4a8fd6; }
+.success { color: #217a36; font-weight: bold; }
+.failure, .error, .fatal-error { color: #b3261e; font-weight: bold; }
+.warning { color: #a05a00; font-weight: bold; }
+.note { color: #356; font-weight: bold; }
+.empty, .external { color: #777; }
+.diagnostic { margin: 0.5em 0 1em; }
+pre { background: #f6f6f6; padding: 0.5em; overflow-x: auto; }
+pre.snippet span { display: block; }
+pre.snippet .highlight { background: #fff1c2; }
+code { font-size: 0.95em; }
+</style>
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distinct_names_have_distinct_anchors() {
+        assert_ne!(anchor("file", "a/b.h"), anchor("file", "a-b.h"));
+        assert_ne!(anchor("file", "a_2fb.h"), anchor("file", "a/b.h"));
+        assert_eq!(anchor("file", "src/main.c"), "file-src_2fmain_2ec");
+    }
+}
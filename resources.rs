@@ -0,0 +1,179 @@
+//! Intégration de fichiers binaires (certificats, pages HTML, polices...) dans l'exécutable.
+//!
+//! Chaque ressource déclarée par la clé `RESOURCE` de `settings.txt` est convertie en une paire
+//! `.c` / `.h` exposant `const unsigned char <nom>[]` et `const size_t <nom>_len`.
+//! Les fichiers générés ne sont réécrits que lorsque la ressource a changé.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::context::BuildContext;
+use crate::settings::Settings;
+use crate::tr;
+
+/// Nombre d'octets écrits par ligne dans le tableau généré.
+const BYTES_PER_LINE: usize = 12;
+
+/// Ressource à intégrer dans l'exécutable.
+#[derive(Debug, Clone)]
+pub struct Resource {
+    pub source: PathBuf,
+    pub symbol: String,
+}
+
+/// Construit la liste des ressources déclarées dans la configuration.
+pub fn resources_from_settings(settings: &Settings, project_path: &str) -> Vec<Resource> {
+    settings
+        .get_list("RESOURCE")
+        .iter()
+        .map(|relative_path| Resource {
+            source: Path::new(project_path).join(relative_path),
+            symbol: symbol_name(relative_path),
+        })
+        .collect()
+}
+
+/// Transforme un chemin relatif en identifiant C valide (`assets/logo.png` -> `assets_logo_png`).
+fn symbol_name(relative_path: &str) -> String {
+    let mut symbol: String = relative_path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+
+    if symbol.starts_with(|c: char| c.is_ascii_digit()) {
+        symbol.insert(0, '_');
+    }
+
+    symbol
+}
+
+/// Vérifie que deux ressources distinctes ne produisent pas le même symbole C (`a-b.png` et `a_b.png`,
+/// `Img.png` et `img.png`) ; une ressource déclarée deux fois n'est retenue qu'une fois.
+pub fn unique_resources(resources: &[Resource]) -> Result<Vec<&Resource>, String> {
+    let mut symbols: HashMap<&str, &Path> = HashMap::new();
+    let mut unique: Vec<&Resource> = Vec::new();
+    let mut collisions: Vec<String> = Vec::new();
+
+    for resource in resources {
+        match symbols.insert(&resource.symbol, &resource.source) {
+            None => unique.push(resource),
+            Some(previous) if previous == resource.source => {}
+            Some(previous) => collisions.push(tr!("resources.symbol_collision", previous.display(), resource.source.display(), resource.symbol)),
+        }
+    }
+
+    if collisions.is_empty() {
+        Ok(unique)
+    } else {
+        Err(collisions.join("\n"))
+    }
+}
+
+/// Génère les paires `.c` / `.h` dans `output_dir` et renvoie les fichiers `.c` à compiler.
+pub fn generate_resources(context: &BuildContext, resources: &[Resource], output_dir: &str) -> Result<Vec<PathBuf>, io::Error> {
+    let resources: Vec<&Resource> = unique_resources(resources).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let mut generated_sources: Vec<PathBuf> = Vec::new();
+
+    for resource in resources {
+        let source_path: PathBuf = Path::new(output_dir).join(format!("{}.c", resource.symbol));
+        let header_path: PathBuf = Path::new(output_dir).join(format!("{}.h", resource.symbol));
+
+        if is_up_to_date(&resource.source, &source_path) && is_up_to_date(&resource.source, &header_path) {
+            context.write_in_logs(tr!("resources.up_to_date", resource.source.display()));
+        } else {
+            let content: Vec<u8> = fs::read(&resource.source).map_err(|err| {
+                io::Error::new(err.kind(), tr!("resources.unreadable", resource.source.display(), err))
+            })?;
+
+            fs::write(&header_path, render_header(resource))?;
+            fs::write(&source_path, render_source(resource, &content))?;
+
+            context.write_in_logs(tr!("resources.generated", resource.source.display(), source_path.display(), content.len()));
+        }
+
+        generated_sources.push(source_path);
+    }
+
+    Ok(generated_sources)
+}
+
+/// Indique si le fichier généré existe et est plus récent que la ressource.
+fn is_up_to_date(resource_path: &Path, generated_path: &Path) -> bool {
+    let modified = |path: &Path| -> Option<SystemTime> { fs::metadata(path).and_then(|m| m.modified()).ok() };
+
+    match (modified(resource_path), modified(generated_path)) {
+        (Some(resource_time), Some(generated_time)) => generated_time >= resource_time,
+        _ => false,
+    }
+}
+
+fn render_header(resource: &Resource) -> String {
+    let guard: String = format!("RESOURCE_{}_H", resource.symbol.to_uppercase());
+
+    format!(
+        "/* Fichier généré à partir de {} : ne pas modifier. */\n\
+         #ifndef {guard}\n\
+         #define {guard}\n\n\
+         #include <stddef.h>\n\n\
+         extern const unsigned char {symbol}[];\n\
+         extern const size_t {symbol}_len;\n\n\
+         #endif\n",
+        resource.source.display(),
+        guard = guard,
+        symbol = resource.symbol
+    )
+}
+
+fn render_source(resource: &Resource, content: &[u8]) -> String {
+    let mut body = String::new();
+
+    for chunk in content.chunks(BYTES_PER_LINE) {
+        body.push_str("    ");
+        for byte in chunk {
+            body.push_str(&format!("0x{:02x}, ", byte));
+        }
+        body.push('\n');
+    }
+
+    // Octet nul final : le tableau n'est jamais vide et les ressources texte restent des chaînes C valides
+    format!(
+        "/* Fichier généré à partir de {} : ne pas modifier. */\n\
+         #include \"{symbol}.h\"\n\n\
+         const unsigned char {symbol}[] = {{\n{body}    0x00\n}};\n\n\
+         const size_t {symbol}_len = {len};\n",
+        resource.source.display(),
+        symbol = resource.symbol,
+        body = body,
+        len = content.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(path: &str) -> Resource {
+        Resource { source: PathBuf::from(path), symbol: symbol_name(path) }
+    }
+
+    #[test]
+    fn symbol_names_are_valid_c_identifiers() {
+        assert_eq!(symbol_name("assets/logo.png"), "assets_logo_png");
+        assert_eq!(symbol_name("3d/model.obj"), "_3d_model_obj");
+    }
+
+    #[test]
+    fn resources_with_the_same_symbol_are_rejected() {
+        assert!(unique_resources(&[resource("a-b.png"), resource("a_b.png")]).is_err());
+        assert!(unique_resources(&[resource("Img.png"), resource("img.png")]).is_err());
+    }
+
+    #[test]
+    fn a_resource_declared_twice_is_kept_once() {
+        let resources: Vec<Resource> = vec![resource("logo.png"), resource("logo.png"), resource("font.ttf")];
+        assert_eq!(unique_resources(&resources).unwrap().len(), 2);
+    }
+}
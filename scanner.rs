@@ -0,0 +1,407 @@
+//! Recherche des fichiers du projet en un seul parcours parallèle.
+//!
+//! Les dossiers sont distribués entre plusieurs threads via une file partagée.
+//! Chaque fichier est classé selon son extension dans un [`ProjectInventory`] réutilisable.
+//! Les dossiers illisibles et les liens symboliques qui bouclent sont signalés dans `errors` ;
+//! un dossier atteint par plusieurs chemins (liens symboliques vers la même cible) n'est parcouru qu'une fois.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use crate::ignore::IgnoreRules;
+use crate::messages::{Language, LanguageScope, language};
+use crate::tr;
+
+/// Représente les types de fichiers que nous recherchons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileType {
+    C,
+    H,
+    Dll,
+    A,
+    O,
+}
+
+impl FileType {
+    /// Retrouve le type d'un fichier à partir de son extension.
+    pub fn from_path(path: &Path) -> Option<FileType> {
+        let extension: String = path.extension()?.to_string_lossy().to_lowercase();
+
+        match extension.as_str() {
+            "c" => Some(FileType::C),
+            "h" => Some(FileType::H),
+            "dll" => Some(FileType::Dll),
+            "a" => Some(FileType::A),
+            "o" => Some(FileType::O),
+            _ => None,
+        }
+    }
+}
+
+/// Erreur rencontrée pendant le parcours, sans interrompre la recherche.
+#[derive(Debug, Clone)]
+pub struct ScanError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} : {}", self.path.display(), self.message)
+    }
+}
+
+/// Inventaire des fichiers d'un projet, classés par type.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectInventory {
+    pub c_files: Vec<PathBuf>,
+    pub h_files: Vec<PathBuf>,
+    pub dll_files: Vec<PathBuf>,
+    pub a_files: Vec<PathBuf>,
+    pub o_files: Vec<PathBuf>,
+    /// Fichiers et dossiers écartés, avec la règle d'exclusion responsable.
+    pub skipped: Vec<(PathBuf, String)>,
+    pub errors: Vec<ScanError>,
+}
+
+impl ProjectInventory {
+    /// Renvoie la liste correspondant à un type de fichier.
+    pub fn files_mut(&mut self, file_type: FileType) -> &mut Vec<PathBuf> {
+        match file_type {
+            FileType::C => &mut self.c_files,
+            FileType::H => &mut self.h_files,
+            FileType::Dll => &mut self.dll_files,
+            FileType::A => &mut self.a_files,
+            FileType::O => &mut self.o_files,
+        }
+    }
+
+    /// Nombre total de fichiers inventoriés.
+    pub fn len(&self) -> usize {
+        self.c_files.len() + self.h_files.len() + self.dll_files.len() + self.a_files.len() + self.o_files.len()
+    }
+
+    /// Indique si aucun fichier n'a été trouvé.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Conserve uniquement les fichiers pour lesquels `keep` renvoie `true`.
+    pub fn retain<F: FnMut(&Path) -> bool>(&mut self, mut keep: F) {
+        for file_type in [FileType::C, FileType::H, FileType::Dll, FileType::A, FileType::O] {
+            self.files_mut(file_type).retain(|file| keep(file));
+        }
+    }
+
+    fn merge(&mut self, other: ProjectInventory) {
+        self.c_files.extend(other.c_files);
+        self.h_files.extend(other.h_files);
+        self.dll_files.extend(other.dll_files);
+        self.a_files.extend(other.a_files);
+        self.o_files.extend(other.o_files);
+        self.skipped.extend(other.skipped);
+        self.errors.extend(other.errors);
+    }
+
+    fn sort(&mut self) {
+        for file_type in [FileType::C, FileType::H, FileType::Dll, FileType::A, FileType::O] {
+            self.files_mut(file_type).sort();
+        }
+        self.skipped.sort();
+    }
+}
+
+/// État partagé entre les threads du parcours.
+struct ScanState {
+    queue: VecDeque<PathBuf>,
+    active_workers: usize,
+    visited: HashSet<PathBuf>,
+    inventory: ProjectInventory,
+}
+
+/// Parcourt `root_path` en parallèle, sans descendre dans `skipped_directories`.
+pub fn scan_project(root_path: &str, skipped_directories: &[String], ignore_rules: &IgnoreRules) -> ProjectInventory {
+    let root: PathBuf = PathBuf::from(root_path);
+    let skipped_directories: Arc<HashSet<PathBuf>> = Arc::new(
+        skipped_directories
+            .iter()
+            .filter_map(|directory| fs::canonicalize(directory).ok())
+            .collect(),
+    );
+
+    let mut initial_state = ScanState {
+        queue: VecDeque::new(),
+        active_workers: 0,
+        visited: HashSet::new(),
+        inventory: ProjectInventory::default(),
+    };
+
+    match fs::canonicalize(&root) {
+        Ok(canonical_root) => {
+            initial_state.visited.insert(canonical_root);
+            initial_state.queue.push_back(root);
+        }
+        Err(err) => initial_state.inventory.errors.push(ScanError {
+            path: root,
+            message: tr!("scanner.project_unreachable", err),
+        }),
+    }
+
+    let state: Arc<(Mutex<ScanState>, Condvar)> = Arc::new((Mutex::new(initial_state), Condvar::new()));
+    let worker_count: usize = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let mut handles: Vec<thread::JoinHandle<()>> = vec![];
+    let language: Language = language();
+
+    for _ in 0..worker_count {
+        let state: Arc<(Mutex<ScanState>, Condvar)> = Arc::clone(&state);
+        let skipped_directories: Arc<HashSet<PathBuf>> = Arc::clone(&skipped_directories);
+        let ignore_rules: IgnoreRules = ignore_rules.clone();
+
+        handles.push(thread::spawn(move || {
+            let _language = LanguageScope::enter(language);
+            scan_worker(&state, &skipped_directories, &ignore_rules)
+        }));
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let (mutex, _) = Arc::try_unwrap(state).ok().unwrap();
+    let mut inventory: ProjectInventory = mutex.into_inner().unwrap().inventory;
+    inventory.sort();
+    inventory
+}
+
+/// Boucle d'un thread : prend un dossier dans la file, le lit, puis publie ses résultats.
+fn scan_worker(state: &(Mutex<ScanState>, Condvar), skipped_directories: &HashSet<PathBuf>, ignore_rules: &IgnoreRules) {
+    let (mutex, condvar) = state;
+
+    loop {
+        let directory: PathBuf = {
+            let mut guard = mutex.lock().unwrap();
+            loop {
+                if let Some(directory) = guard.queue.pop_front() {
+                    guard.active_workers += 1;
+                    break directory;
+                }
+                if guard.active_workers == 0 {
+                    condvar.notify_all();
+                    return;
+                }
+                guard = condvar.wait(guard).unwrap();
+            }
+        };
+
+        let (found, subdirectories) = read_directory(&directory, skipped_directories, ignore_rules);
+
+        let mut guard = mutex.lock().unwrap();
+        guard.inventory.merge(found);
+
+        for (subdirectory, is_symlink) in subdirectories {
+            match fs::canonicalize(&subdirectory) {
+                Ok(canonical) => {
+                    if guard.visited.insert(canonical.clone()) {
+                        guard.queue.push_back(subdirectory);
+                    } else if is_symlink && is_ancestor(&canonical, &directory) {
+                        // Un lien vers un dossier parent ferait reparcourir le dossier à l'infini
+                        guard.inventory.errors.push(ScanError {
+                            path: subdirectory,
+                            message: tr!("scanner.symlink_loop", canonical.display()),
+                        });
+                    }
+                    // Sinon le dossier est déjà parcouru par un autre chemin : ses fichiers ne sont pas repris
+                }
+                Err(err) => guard.inventory.errors.push(ScanError {
+                    path: subdirectory,
+                    message: tr!("scanner.unresolvable", err),
+                }),
+            }
+        }
+
+        guard.active_workers -= 1;
+        condvar.notify_all();
+    }
+}
+
+/// Indique si `canonical` est `directory` lui-même ou l'un de ses dossiers parents.
+fn is_ancestor(canonical: &Path, directory: &Path) -> bool {
+    fs::canonicalize(directory).map(|directory| directory.starts_with(canonical)).unwrap_or(false)
+}
+
+/// Lit un dossier : classe ses fichiers et renvoie ses sous-dossiers (avec un indicateur de lien symbolique).
+fn read_directory(directory: &Path, skipped_directories: &HashSet<PathBuf>, ignore_rules: &IgnoreRules) -> (ProjectInventory, Vec<(PathBuf, bool)>) {
+    let mut found = ProjectInventory::default();
+    let mut subdirectories: Vec<(PathBuf, bool)> = Vec::new();
+
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(err) => {
+            found.errors.push(ScanError { path: directory.to_path_buf(), message: tr!("scanner.unreadable_directory", err) });
+            return (found, subdirectories);
+        }
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                found.errors.push(ScanError { path: directory.to_path_buf(), message: tr!("scanner.unreadable_entry", err) });
+                continue;
+            }
+        };
+
+        let entry_path: PathBuf = entry.path();
+        let is_symlink: bool = entry.file_type().map(|t| t.is_symlink()).unwrap_or(false);
+
+        // Suit les liens symboliques pour connaître la nature de la cible
+        let metadata = match fs::metadata(&entry_path) {
+            Ok(metadata) => metadata,
+            Err(err) => {
+                found.errors.push(ScanError { path: entry_path, message: tr!("scanner.unreachable_file", err) });
+                continue;
+            }
+        };
+
+        if metadata.is_dir() {
+            let is_skipped_directory: bool = fs::canonicalize(&entry_path)
+                .map(|canonical| skipped_directories.contains(&canonical))
+                .unwrap_or(false);
+
+            if is_skipped_directory {
+                continue;
+            }
+
+            match ignore_rules.excluded_by(&entry_path, true) {
+                Some(rule) => found.skipped.push((entry_path, rule.to_string())),
+                None => subdirectories.push((entry_path, is_symlink)),
+            }
+        } else if let Some(file_type) = FileType::from_path(&entry_path) {
+            match ignore_rules.excluded_by(&entry_path, false) {
+                Some(rule) => found.skipped.push((entry_path, rule.to_string())),
+                None => found.files_mut(file_type).push(entry_path),
+            }
+        }
+    }
+
+    (found, subdirectories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+
+    /// Projet temporaire propre au test, vidé au préalable.
+    fn test_project(name: &str) -> PathBuf {
+        let project: PathBuf = std::env::temp_dir().join(format!("compilerc-scanner-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&project);
+        write(&project.join("main.c"));
+        write(&project.join("src/calcul.c"));
+        write(&project.join("include/calcul.h"));
+        write(&project.join("lib/libz.a"));
+        write(&project.join("notes.txt"));
+        project
+    }
+
+    fn write(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+
+    fn scan(project: &Path, skipped_directories: &[String]) -> ProjectInventory {
+        scan_project(&project.to_string_lossy(), skipped_directories, &IgnoreRules::default())
+    }
+
+    #[test]
+    fn files_are_sorted_by_type() {
+        let project: PathBuf = test_project("types");
+
+        let inventory: ProjectInventory = scan(&project, &[]);
+        assert_eq!(inventory.c_files, [project.join("main.c"), project.join("src/calcul.c")]);
+        assert_eq!(inventory.h_files, [project.join("include/calcul.h")]);
+        assert_eq!(inventory.a_files, [project.join("lib/libz.a")]);
+        assert_eq!(inventory.len(), 4);
+        assert!(inventory.errors.is_empty());
+        fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn a_nested_target_directory_is_not_scanned() {
+        let project: PathBuf = test_project("target");
+        write(&project.join("build/projet/source/main.c"));
+        write(&project.join("build/projet/output/debug/main.o"));
+
+        // Le dossier cible est reconnu quelle que soit l'écriture de son chemin
+        let target: String = project.join("src/../build").to_string_lossy().to_string();
+        let inventory: ProjectInventory = scan(&project, &[target]);
+        assert_eq!(inventory.c_files, [project.join("main.c"), project.join("src/calcul.c")]);
+        assert!(inventory.o_files.is_empty());
+        fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn ignored_directories_are_reported_with_their_rule() {
+        let project: PathBuf = test_project("ignore");
+        write(&project.join("legacy/ancien.c"));
+
+        let rules: IgnoreRules = IgnoreRules::load(&project.to_string_lossy(), &Settings::parse("IGNORE : legacy/"));
+        let inventory: ProjectInventory = scan_project(&project.to_string_lossy(), &[], &rules);
+        assert!(!inventory.c_files.contains(&project.join("legacy/ancien.c")));
+        assert_eq!(inventory.skipped.len(), 1);
+        assert_eq!(inventory.skipped[0].0, project.join("legacy"));
+        fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn an_unreachable_project_is_an_error() {
+        let inventory: ProjectInventory = scan(&std::env::temp_dir().join("compilerc-scanner-absent"), &[]);
+        assert!(inventory.is_empty());
+        assert_eq!(inventory.errors.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_symlink_loop_is_reported_once() {
+        let project: PathBuf = test_project("loop");
+        std::os::unix::fs::symlink(&project, project.join("src/boucle")).unwrap();
+
+        let inventory: ProjectInventory = scan(&project, &[]);
+        assert_eq!(inventory.c_files, [project.join("main.c"), project.join("src/calcul.c")]);
+        assert_eq!(inventory.errors.len(), 1);
+        assert_eq!(inventory.errors[0].path, project.join("src/boucle"));
+        fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_directory_reached_twice_is_scanned_once_without_error() {
+        let project: PathBuf = test_project("duplicate");
+        std::os::unix::fs::symlink(project.join("src"), project.join("alias")).unwrap();
+
+        let inventory: ProjectInventory = scan(&project, &[]);
+        assert_eq!(inventory.c_files.len(), 2);
+        assert!(inventory.c_files.contains(&project.join("main.c")));
+        assert!(inventory.errors.is_empty());
+        fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_symlink_to_the_target_directory_is_not_scanned() {
+        let project: PathBuf = test_project("target-link");
+        let target: PathBuf = std::env::temp_dir().join(format!("compilerc-scanner-{}-target-link-build", std::process::id()));
+        let _ = fs::remove_dir_all(&target);
+        write(&target.join("projet/source/main.c"));
+        std::os::unix::fs::symlink(&target, project.join("build")).unwrap();
+
+        let inventory: ProjectInventory = scan(&project, &[target.to_string_lossy().to_string()]);
+        assert_eq!(inventory.c_files, [project.join("main.c"), project.join("src/calcul.c")]);
+        fs::remove_dir_all(&project).unwrap();
+        fs::remove_dir_all(&target).unwrap();
+    }
+}
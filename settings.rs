@@ -0,0 +1,93 @@
+//! Lecture du fichier de configuration `settings.txt` du projet.
+//!
+//! Chaque ligne a la forme `CLE : valeur`. Une clé peut être répétée et une valeur
+//! peut contenir plusieurs éléments séparés par des virgules ou des points-virgules.
+
+use std::fs;
+use std::path::Path;
+
+/// Nom du fichier de configuration recherché à la racine du projet.
+pub const SETTINGS_FILE_NAME: &str = "settings.txt";
+
+/// Configuration du projet, conservée dans l'ordre du fichier.
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    entries: Vec<(String, String)>,
+}
+
+impl Settings {
+    /// Charge `settings.txt` depuis la racine du projet ; une configuration vide est renvoyée si le fichier est absent.
+    pub fn load(project_path: &str) -> Settings {
+        let settings_path = Path::new(project_path).join(SETTINGS_FILE_NAME);
+
+        match fs::read_to_string(&settings_path) {
+            Ok(content) => Settings::parse(&content),
+            Err(_) => Settings::default(),
+        }
+    }
+
+    /// Analyse le contenu d'un fichier de configuration.
+    pub fn parse(content: &str) -> Settings {
+        let mut entries: Vec<(String, String)> = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // La clé s'arrête au premier ':' pour conserver les chemins Windows (C:\...)
+            if let Some((key, value)) = line.split_once(':') {
+                entries.push((key.trim().to_uppercase(), value.trim().to_string()));
+            }
+        }
+
+        Settings { entries }
+    }
+
+    /// Remplace les valeurs d'une clé, pour configurer un projet sans modifier son `settings.txt`.
+    pub fn set(&mut self, key: &str, value: &str) {
+        let key: String = key.to_uppercase();
+        self.entries.retain(|(entry_key, _)| *entry_key != key);
+        self.entries.push((key, value.to_string()));
+    }
+
+    /// Renvoie la dernière valeur associée à une clé.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(entry_key, _)| entry_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Interprète une valeur booléenne (`true`/`false`, `on`/`off`, `oui`/`non`, `1`/`0`).
+    pub fn get_bool(&self, key: &str, default: bool) -> bool {
+        match self.get(key).map(|value| value.to_lowercase()) {
+            Some(value) if ["true", "on", "yes", "oui", "1"].contains(&value.as_str()) => true,
+            Some(value) if ["false", "off", "no", "non", "0"].contains(&value.as_str()) => false,
+            _ => default,
+        }
+    }
+
+    /// Renvoie toutes les valeurs d'une clé répétée, sans découpage (utile pour les commandes).
+    pub fn get_all(&self, key: &str) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|(entry_key, _)| entry_key == key)
+            .map(|(_, value)| value.clone())
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+
+    /// Renvoie toutes les valeurs d'une clé, en découpant les listes séparées par ',' ou ';'.
+    pub fn get_list(&self, key: &str) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|(entry_key, _)| entry_key == key)
+            .flat_map(|(_, value)| value.split([',', ';']))
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    }
+}
@@ -0,0 +1,480 @@
+//! Historique des builds réussis sous forme d'instantanés adressés par contenu.
+//!
+//! Après chaque build réussi, les fichiers des dossiers de la cible (sources copiées, objets,
+//! bibliothèques, exécutable) sont enregistrés dans `snapshots/objects/<empreinte>` et décrits
+//! par un manifeste `snapshots/manifests/<id>.txt`. Un fichier identique d'un build à l'autre
+//! n'est stocké qu'une seule fois. Seuls les `SNAPSHOT_KEEP` derniers instantanés sont conservés,
+//! avec les contenus qu'ils référencent.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::Local;
+
+use crate::settings::Settings;
+use crate::sync::hash_file;
+use crate::tr;
+
+/// Dossiers de la cible enregistrés dans chaque instantané.
+pub const SNAPSHOT_FOLDERS: [&str; 6] = ["source", "output", "resources", "dll", "a", "executable"];
+
+/// Nombre d'instantanés conservés sans clé `SNAPSHOT_KEEP`.
+const DEFAULT_KEEP_SNAPSHOTS: usize = 20;
+
+/// Âge minimal d'un contenu non référencé avant sa suppression : un build concurrent
+/// peut l'avoir stocké sans avoir encore écrit son manifeste.
+const UNREFERENCED_GRACE_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// Fichier enregistré dans un instantané, chemin relatif au dossier cible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotFile {
+    pub hash: String,
+    pub size: u64,
+    pub path: PathBuf,
+}
+
+/// Instantané d'un build réussi.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub id: u32,
+    pub date: String,
+    pub files: Vec<SnapshotFile>,
+}
+
+impl Snapshot {
+    /// Taille cumulée des fichiers de l'instantané.
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+
+    fn to_manifest(&self) -> String {
+        let mut manifest: String = format!("id : {}\ndate : {}\n", self.id, self.date);
+        for file in &self.files {
+            manifest.push_str(&format!(
+                "file : {} {} {}\n",
+                file.hash,
+                file.size,
+                file.path.to_string_lossy().replace('\\', "/")
+            ));
+        }
+        manifest
+    }
+
+    fn from_manifest(content: &str) -> Option<Snapshot> {
+        let mut snapshot = Snapshot { id: 0, date: String::new(), files: Vec::new() };
+
+        for line in content.lines() {
+            let (key, value) = line.split_once(" : ")?;
+            match key {
+                "id" => snapshot.id = value.parse().ok()?,
+                "date" => snapshot.date = value.to_string(),
+                "file" => {
+                    let mut parts = value.splitn(3, ' ');
+                    snapshot.files.push(SnapshotFile {
+                        hash: parts.next()?.to_string(),
+                        size: parts.next()?.parse().ok()?,
+                        path: PathBuf::from(parts.next()?),
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        // Manifeste réservé par un build en cours, pas encore écrit
+        if snapshot.id == 0 {
+            return None;
+        }
+
+        Some(snapshot)
+    }
+}
+
+/// Différences entre deux instantanés.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotDiff {
+    pub added: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+}
+
+fn snapshots_path(target_path: &Path) -> PathBuf {
+    target_path.join("snapshots")
+}
+
+fn manifest_path(target_path: &Path, id: u32) -> PathBuf {
+    snapshots_path(target_path).join("manifests").join(format!("{}.txt", id))
+}
+
+fn object_path(target_path: &Path, hash: &str) -> PathBuf {
+    snapshots_path(target_path).join("objects").join(&hash[..2]).join(hash)
+}
+
+/// Enregistre le contenu actuel des dossiers de la cible comme nouvel instantané.
+pub fn create_snapshot(target_path: &Path) -> Result<Snapshot, io::Error> {
+    let mut files: Vec<SnapshotFile> = Vec::new();
+
+    for folder in SNAPSHOT_FOLDERS {
+        collect_snapshot_files(target_path, &target_path.join(folder), &mut files)?;
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    for file in &files {
+        let object: PathBuf = object_path(target_path, &file.hash);
+        if object.exists() {
+            // Un contenu repris est rajeuni pour ne pas être supprimé avant l'écriture du manifeste
+            File::options().write(true).open(&object)?.set_modified(SystemTime::now())?;
+        } else {
+            fs::create_dir_all(object.parent().unwrap())?;
+            fs::copy(target_path.join(&file.path), &object)?;
+        }
+    }
+
+    let id: u32 = reserve_snapshot_id(target_path)?;
+    let snapshot = Snapshot { id, date: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(), files };
+
+    // Le manifeste complet remplace d'un coup le manifeste réservé
+    let manifest: PathBuf = manifest_path(target_path, id);
+    let written_manifest: PathBuf = manifest.with_extension("txt.tmp");
+    fs::write(&written_manifest, snapshot.to_manifest())?;
+    fs::rename(&written_manifest, &manifest)?;
+
+    Ok(snapshot)
+}
+
+/// Réserve l'identifiant suivant en créant son manifeste vide : la création échoue si un build
+/// concurrent l'a déjà pris, et l'identifiant suivant est alors essayé.
+fn reserve_snapshot_id(target_path: &Path) -> Result<u32, io::Error> {
+    fs::create_dir_all(snapshots_path(target_path).join("manifests"))?;
+    let mut id: u32 = list_snapshots(target_path)?.last().map(|snapshot| snapshot.id + 1).unwrap_or(1);
+
+    loop {
+        match OpenOptions::new().write(true).create_new(true).open(manifest_path(target_path, id)) {
+            Ok(_) => return Ok(id),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => id += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Nombre d'instantanés conservés d'après `SNAPSHOT_KEEP` (20 par défaut) ; `None` avec `SNAPSHOT_KEEP : 0`.
+pub fn snapshots_to_keep(settings: &Settings) -> Option<usize> {
+    let keep: usize = settings.get("SNAPSHOT_KEEP").and_then(|value| value.parse().ok()).unwrap_or(DEFAULT_KEEP_SNAPSHOTS);
+    if keep == 0 { None } else { Some(keep) }
+}
+
+/// Supprime les instantanés les plus anciens au-delà de `keep`, puis les contenus qui ne sont plus référencés.
+pub fn prune_snapshots(target_path: &Path, keep: usize) -> Result<Vec<Snapshot>, io::Error> {
+    let mut removed: Vec<Snapshot> = list_snapshots(target_path)?;
+    let kept: Vec<Snapshot> = removed.split_off(removed.len().saturating_sub(keep));
+    if removed.is_empty() {
+        return Ok(removed);
+    }
+
+    for snapshot in &removed {
+        remove_if_present(&manifest_path(target_path, snapshot.id))?;
+    }
+
+    let referenced: HashSet<&str> = kept.iter().flat_map(|snapshot| &snapshot.files).map(|file| file.hash.as_str()).collect();
+    let oldest: Option<SystemTime> = SystemTime::now().checked_sub(UNREFERENCED_GRACE_PERIOD);
+
+    for prefix in fs::read_dir(snapshots_path(target_path).join("objects"))?.flatten() {
+        // Le dossier a pu être vidé et supprimé par un build concurrent
+        let Ok(objects) = fs::read_dir(prefix.path()) else { continue };
+        for object in objects.flatten() {
+            let hash: String = object.file_name().to_string_lossy().to_string();
+            let modified: Option<SystemTime> = object.metadata().and_then(|metadata| metadata.modified()).ok();
+
+            if !referenced.contains(hash.as_str()) && modified.zip(oldest).is_some_and(|(modified, oldest)| modified < oldest) {
+                remove_if_present(&object.path())?;
+            }
+        }
+        // Échoue sans conséquence si le dossier n'est pas vide
+        let _ = fs::remove_dir(prefix.path());
+    }
+
+    Ok(removed)
+}
+
+fn collect_snapshot_files(target_path: &Path, directory: &Path, files: &mut Vec<SnapshotFile>) -> Result<(), io::Error> {
+    if !directory.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(directory)? {
+        let entry_path: PathBuf = entry?.path();
+        if entry_path.is_dir() {
+            collect_snapshot_files(target_path, &entry_path, files)?;
+        } else {
+            files.push(SnapshotFile {
+                hash: hash_file(&entry_path)?,
+                size: fs::metadata(&entry_path)?.len(),
+                path: entry_path.strip_prefix(target_path).unwrap_or(&entry_path).to_path_buf(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Supprime un fichier, sans erreur s'il a déjà été supprimé par un build concurrent.
+fn remove_if_present(path: &Path) -> Result<(), io::Error> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Liste les instantanés enregistrés, du plus ancien au plus récent.
+pub fn list_snapshots(target_path: &Path) -> Result<Vec<Snapshot>, io::Error> {
+    let manifests_path: PathBuf = snapshots_path(target_path).join("manifests");
+    let mut snapshots: Vec<Snapshot> = Vec::new();
+
+    if !manifests_path.is_dir() {
+        return Ok(snapshots);
+    }
+
+    for entry in fs::read_dir(&manifests_path)? {
+        let path: PathBuf = entry?.path();
+        // Les manifestes en cours d'écriture portent l'extension `.tmp`
+        if path.extension().is_none_or(|extension| extension != "txt") {
+            continue;
+        }
+        let content: String = fs::read_to_string(path)?;
+        if let Some(snapshot) = Snapshot::from_manifest(&content) {
+            snapshots.push(snapshot);
+        }
+    }
+
+    snapshots.sort_by_key(|snapshot| snapshot.id);
+    Ok(snapshots)
+}
+
+/// Charge un instantané par son identifiant.
+pub fn load_snapshot(target_path: &Path, id: u32) -> Result<Snapshot, io::Error> {
+    let content: String = fs::read_to_string(manifest_path(target_path, id))
+        .map_err(|err| io::Error::new(err.kind(), tr!("snapshots.not_found", id, err)))?;
+
+    Snapshot::from_manifest(&content)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, tr!("snapshots.invalid_manifest", id)))
+}
+
+/// Compare deux instantanés fichier par fichier.
+pub fn diff_snapshots(old: &Snapshot, new: &Snapshot) -> SnapshotDiff {
+    let old_files: BTreeMap<&PathBuf, &str> = old.files.iter().map(|file| (&file.path, file.hash.as_str())).collect();
+    let new_files: BTreeMap<&PathBuf, &str> = new.files.iter().map(|file| (&file.path, file.hash.as_str())).collect();
+    let mut diff = SnapshotDiff::default();
+
+    for (path, hash) in &new_files {
+        match old_files.get(path) {
+            None => diff.added.push((*path).clone()),
+            Some(old_hash) if old_hash != hash => diff.modified.push((*path).clone()),
+            Some(_) => (),
+        }
+    }
+    for path in old_files.keys() {
+        if !new_files.contains_key(path) {
+            diff.removed.push((*path).clone());
+        }
+    }
+
+    diff
+}
+
+/// Remet les dossiers de la cible dans l'état exact de l'instantané.
+pub fn restore_snapshot(target_path: &Path, snapshot: &Snapshot) -> Result<(), io::Error> {
+    // Vérifie que tous les objets sont présents avant de toucher à la cible
+    for file in &snapshot.files {
+        let object: PathBuf = object_path(target_path, &file.hash);
+        if !object.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                tr!("snapshots.missing_content", file.path.display(), file.hash),
+            ));
+        }
+    }
+
+    for folder in SNAPSHOT_FOLDERS {
+        let folder_path: PathBuf = target_path.join(folder);
+        if folder_path.exists() {
+            fs::remove_dir_all(&folder_path)?;
+        }
+        fs::create_dir_all(&folder_path)?;
+    }
+
+    for file in &snapshot.files {
+        let destination: PathBuf = target_path.join(&file.path);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(object_path(target_path, &file.hash), &destination)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dossier cible temporaire propre au test, vidé au préalable.
+    fn test_target(name: &str) -> PathBuf {
+        let target: PathBuf = std::env::temp_dir().join(format!("compilerc-snapshots-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&target);
+        fs::create_dir_all(&target).unwrap();
+        target
+    }
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn file(path: &str, hash: &str) -> SnapshotFile {
+        SnapshotFile { hash: hash.to_string(), size: 4, path: PathBuf::from(path) }
+    }
+
+    fn objects(target: &Path) -> usize {
+        fs::read_dir(snapshots_path(target).join("objects")).unwrap().flatten().map(|prefix| fs::read_dir(prefix.path()).unwrap().count()).sum()
+    }
+
+    /// Vieillit un contenu au-delà du délai de grâce.
+    fn age(path: &Path) {
+        let old: SystemTime = SystemTime::now() - UNREFERENCED_GRACE_PERIOD - Duration::from_secs(60);
+        File::options().write(true).open(path).unwrap().set_modified(old).unwrap();
+    }
+
+    #[test]
+    fn manifests_round_trip() {
+        let snapshot = Snapshot { id: 12, date: "2024-05-02 14:03:11".to_string(), files: vec![file("source/main.c", "ab12"), file("executable/debug/mon programme", "cd34")] };
+
+        let read: Snapshot = Snapshot::from_manifest(&snapshot.to_manifest()).unwrap();
+        assert_eq!(read.id, 12);
+        assert_eq!(read.date, snapshot.date);
+        assert_eq!(read.files, snapshot.files);
+        assert_eq!(read.total_size(), 8);
+    }
+
+    #[test]
+    fn reserved_or_invalid_manifests_are_skipped() {
+        assert!(Snapshot::from_manifest("").is_none());
+        assert!(Snapshot::from_manifest("id : 3\nfile : ab12 taille source/main.c\n").is_none());
+        assert!(Snapshot::from_manifest("id : trois\n").is_none());
+    }
+
+    #[test]
+    fn differences_are_sorted_by_kind() {
+        let old = Snapshot { id: 1, date: String::new(), files: vec![file("a.o", "11"), file("b.o", "22"), file("c.o", "33")] };
+        let new = Snapshot { id: 2, date: String::new(), files: vec![file("a.o", "11"), file("b.o", "99"), file("d.o", "44")] };
+
+        let diff: SnapshotDiff = diff_snapshots(&old, &new);
+        assert_eq!(diff.added, [PathBuf::from("d.o")]);
+        assert_eq!(diff.removed, [PathBuf::from("c.o")]);
+        assert_eq!(diff.modified, [PathBuf::from("b.o")]);
+
+        let same: SnapshotDiff = diff_snapshots(&old, &old);
+        assert!(same.added.is_empty() && same.removed.is_empty() && same.modified.is_empty());
+    }
+
+    #[test]
+    fn identical_contents_are_stored_once() {
+        let target: PathBuf = test_target("dedup");
+        write(&target.join("source/a.c"), "int a;");
+        write(&target.join("source/copie/a.c"), "int a;");
+        write(&target.join("output/debug/a.o"), "objet");
+
+        let first: Snapshot = create_snapshot(&target).unwrap();
+        let second: Snapshot = create_snapshot(&target).unwrap();
+        assert_eq!((first.id, second.id), (1, 2));
+        assert_eq!(first.files.len(), 3);
+        assert_eq!(objects(&target), 2);
+        fs::remove_dir_all(&target).unwrap();
+    }
+
+    #[test]
+    fn a_snapshot_is_restored_exactly() {
+        let target: PathBuf = test_target("restore");
+        write(&target.join("source/main.c"), "int main(void) { return 0; }");
+        write(&target.join("executable/debug/main"), "v1");
+        write(&target.join("logs/build.log"), "log");
+        let snapshot: Snapshot = create_snapshot(&target).unwrap();
+
+        write(&target.join("executable/debug/main"), "v2");
+        write(&target.join("source/nouveau.c"), "int x;");
+        fs::remove_file(target.join("source/main.c")).unwrap();
+
+        restore_snapshot(&target, &load_snapshot(&target, snapshot.id).unwrap()).unwrap();
+        assert_eq!(fs::read_to_string(target.join("executable/debug/main")).unwrap(), "v1");
+        assert_eq!(fs::read_to_string(target.join("source/main.c")).unwrap(), "int main(void) { return 0; }");
+        assert!(!target.join("source/nouveau.c").exists());
+        // Les dossiers hors instantané ne sont pas touchés
+        assert!(target.join("logs/build.log").exists());
+        fs::remove_dir_all(&target).unwrap();
+    }
+
+    #[test]
+    fn a_snapshot_with_missing_content_leaves_the_target_untouched() {
+        let target: PathBuf = test_target("missing");
+        write(&target.join("source/main.c"), "int main(void) { return 0; }");
+        let snapshot: Snapshot = create_snapshot(&target).unwrap();
+        fs::remove_file(object_path(&target, &snapshot.files[0].hash)).unwrap();
+        write(&target.join("source/main.c"), "modifié");
+
+        let err: io::Error = restore_snapshot(&target, &snapshot).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(fs::read_to_string(target.join("source/main.c")).unwrap(), "modifié");
+        fs::remove_dir_all(&target).unwrap();
+    }
+
+    #[test]
+    fn reserved_identifiers_are_skipped() {
+        let target: PathBuf = test_target("reserve");
+        fs::create_dir_all(snapshots_path(&target).join("manifests")).unwrap();
+        // Identifiant réservé par un build concurrent qui n'a pas encore écrit son manifeste
+        fs::write(manifest_path(&target, 1), "").unwrap();
+
+        assert_eq!(create_snapshot(&target).unwrap().id, 2);
+
+        let mut ids: Vec<u32> = std::thread::scope(|scope| {
+            let builds: Vec<_> = (0..4).map(|_| scope.spawn(|| reserve_snapshot_id(&target).unwrap())).collect();
+            builds.into_iter().map(|build| build.join().unwrap()).collect()
+        });
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 4);
+        fs::remove_dir_all(&target).unwrap();
+    }
+
+    #[test]
+    fn pruning_keeps_the_latest_snapshots_and_their_contents() {
+        let target: PathBuf = test_target("prune");
+        for version in ["v1", "v2", "v3"] {
+            write(&target.join("executable/debug/main"), version);
+            create_snapshot(&target).unwrap();
+        }
+        let first: Snapshot = load_snapshot(&target, 1).unwrap();
+        let second: Snapshot = load_snapshot(&target, 2).unwrap();
+        // Seul le contenu du premier instantané a dépassé le délai de grâce
+        age(&object_path(&target, &first.files[0].hash));
+
+        let removed: Vec<Snapshot> = prune_snapshots(&target, 1).unwrap();
+        assert_eq!(removed.iter().map(|snapshot| snapshot.id).collect::<Vec<u32>>(), [1, 2]);
+        assert_eq!(list_snapshots(&target).unwrap().iter().map(|snapshot| snapshot.id).collect::<Vec<u32>>(), [3]);
+        assert!(!object_path(&target, &first.files[0].hash).exists());
+        // Un contenu récent peut appartenir à un build en cours : il est gardé
+        assert!(object_path(&target, &second.files[0].hash).exists());
+
+        restore_snapshot(&target, &load_snapshot(&target, 3).unwrap()).unwrap();
+        assert_eq!(fs::read_to_string(target.join("executable/debug/main")).unwrap(), "v3");
+        fs::remove_dir_all(&target).unwrap();
+    }
+
+    #[test]
+    fn snapshot_keep_zero_keeps_everything() {
+        assert_eq!(snapshots_to_keep(&Settings::parse("")), Some(DEFAULT_KEEP_SNAPSHOTS));
+        assert_eq!(snapshots_to_keep(&Settings::parse("SNAPSHOT_KEEP : 5")), Some(5));
+        assert_eq!(snapshots_to_keep(&Settings::parse("SNAPSHOT_KEEP : 0")), None);
+    }
+}
@@ -0,0 +1,272 @@
+//! Historique des mesures de chaque build réussi, pour repérer les régressions.
+//!
+//! Chaque build ajoute une ligne à `stats/history.txt` dans le dossier cible du projet : la date puis
+//! des champs `cle=valeur` séparés par des tabulations (durées en millisecondes, tailles en octets) :
+//!
+//! ```text
+//! 2024-05-02 14:03:11  id=12  profile=debug  sources=87  cached=80  files=240  warnings=3  compile_ms=912.4  link_ms=88.1  total_ms=1203.0  executable_size=182344  objects_size=901220
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::messages::text;
+
+/// Nombre de builds précédents comparés par défaut.
+pub const DEFAULT_COMPARED_BUILDS: usize = 5;
+
+/// Hausse relative d'une durée signalée comme régression.
+const TIME_REGRESSION: f64 = 0.20;
+/// Hausse relative d'une taille signalée comme régression.
+const SIZE_REGRESSION: f64 = 0.05;
+
+/// Mesures d'un build.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuildRecord {
+    pub id: u32,
+    pub date: String,
+    pub profile: String,
+    /// Sources compilées, y compris celles récupérées du cache.
+    pub sources: usize,
+    pub cached: usize,
+    /// Fichiers traités : sources, en-têtes, objets, bibliothèques et fichiers inclus.
+    pub files: usize,
+    pub warnings: usize,
+    pub compile_ms: f64,
+    pub link_ms: f64,
+    pub total_ms: f64,
+    pub executable_size: u64,
+    pub objects_size: u64,
+}
+
+impl BuildRecord {
+    fn to_line(&self) -> String {
+        format!(
+            "{}\tid={}\tprofile={}\tsources={}\tcached={}\tfiles={}\twarnings={}\tcompile_ms={:.1}\tlink_ms={:.1}\ttotal_ms={:.1}\texecutable_size={}\tobjects_size={}",
+            self.date,
+            self.id,
+            self.profile,
+            self.sources,
+            self.cached,
+            self.files,
+            self.warnings,
+            self.compile_ms,
+            self.link_ms,
+            self.total_ms,
+            self.executable_size,
+            self.objects_size
+        )
+    }
+
+    /// Lit une ligne de l'historique ; les champs inconnus ou absents sont ignorés.
+    fn from_line(line: &str) -> Option<BuildRecord> {
+        let mut parts = line.split('\t');
+        let date: String = parts.next()?.to_string();
+        let fields: BTreeMap<&str, &str> = parts.filter_map(|part| part.split_once('=')).collect();
+
+        let number = |key: &str| -> f64 { fields.get(key).and_then(|value| value.parse().ok()).unwrap_or(0.0) };
+
+        Some(BuildRecord {
+            id: fields.get("id")?.parse().ok()?,
+            date,
+            profile: fields.get("profile").unwrap_or(&"debug").to_string(),
+            sources: number("sources") as usize,
+            cached: number("cached") as usize,
+            files: number("files") as usize,
+            warnings: number("warnings") as usize,
+            compile_ms: number("compile_ms"),
+            link_ms: number("link_ms"),
+            total_ms: number("total_ms"),
+            executable_size: number("executable_size") as u64,
+            objects_size: number("objects_size") as u64,
+        })
+    }
+}
+
+/// Comparaison d'une mesure avec la moyenne des builds précédents.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    /// Nom de la mesure, traduit.
+    pub label: &'static str,
+    pub unit: &'static str,
+    pub current: f64,
+    pub previous: f64,
+    pub regression: bool,
+}
+
+impl Comparison {
+    /// Écart relatif en pourcentage, `None` si la moyenne précédente est nulle.
+    pub fn change(&self) -> Option<f64> {
+        if self.previous == 0.0 {
+            None
+        } else {
+            Some((self.current - self.previous) * 100.0 / self.previous)
+        }
+    }
+}
+
+fn history_path(target_path: &Path) -> PathBuf {
+    target_path.join("stats").join("history.txt")
+}
+
+/// Ajoute un build à l'historique ; son identifiant est attribué ici.
+pub fn append_record(target_path: &Path, mut record: BuildRecord) -> Result<BuildRecord, io::Error> {
+    record.id = load_history(target_path)?.last().map(|last| last.id + 1).unwrap_or(1);
+
+    let path: PathBuf = history_path(target_path);
+    fs::create_dir_all(path.parent().unwrap())?;
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", record.to_line())?;
+
+    Ok(record)
+}
+
+/// Builds enregistrés, du plus ancien au plus récent.
+pub fn load_history(target_path: &Path) -> Result<Vec<BuildRecord>, io::Error> {
+    match fs::read_to_string(history_path(target_path)) {
+        Ok(content) => Ok(content.lines().filter_map(BuildRecord::from_line).collect()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Compare un build à la moyenne des builds précédents ; une mesure sans seuil n'est jamais une régression.
+pub fn compare(current: &BuildRecord, previous: &[&BuildRecord]) -> Vec<Comparison> {
+    let average = |value: fn(&BuildRecord) -> f64| -> f64 {
+        if previous.is_empty() {
+            0.0
+        } else {
+            previous.iter().map(|record| value(record)).sum::<f64>() / previous.len() as f64
+        }
+    };
+
+    let measure = |label: &'static str, unit: &'static str, value: fn(&BuildRecord) -> f64, threshold: Option<f64>| -> Comparison {
+        let current_value: f64 = value(current);
+        let previous_value: f64 = average(value);
+        let regression: bool = threshold.is_some_and(|threshold| !previous.is_empty() && current_value > previous_value * (1.0 + threshold));
+        Comparison { label, unit, current: current_value, previous: previous_value, regression }
+    };
+
+    vec![
+        measure(text("stats.compile"), "ms", |record| record.compile_ms, Some(TIME_REGRESSION)),
+        measure(text("stats.link"), "ms", |record| record.link_ms, Some(TIME_REGRESSION)),
+        measure(text("stats.total"), "ms", |record| record.total_ms, Some(TIME_REGRESSION)),
+        measure(text("stats.warnings"), "", |record| record.warnings as f64, Some(0.0)),
+        measure(text("stats.executable_size"), "o", |record| record.executable_size as f64, Some(SIZE_REGRESSION)),
+        measure(text("stats.objects_size"), "o", |record| record.objects_size as f64, Some(SIZE_REGRESSION)),
+        measure(text("stats.sources"), "", |record| record.sources as f64, None),
+        measure(text("stats.cached"), "", |record| record.cached as f64, None),
+    ]
+}
+
+/// Nombre d'avertissements dans la sortie de gcc, lancé sans traduction par [`gcc_command`](crate::diagnostics::gcc_command).
+pub fn count_warnings(diagnostics: &str) -> usize {
+    diagnostics.lines().filter(|line| line.contains(": warning:")).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(total_ms: f64, warnings: usize, executable_size: u64) -> BuildRecord {
+        BuildRecord { total_ms, warnings, executable_size, ..BuildRecord::default() }
+    }
+
+    fn regressions(current: &BuildRecord, previous: &[&BuildRecord]) -> Vec<&'static str> {
+        compare(current, previous).into_iter().filter(|comparison| comparison.regression).map(|comparison| comparison.label).collect()
+    }
+
+    #[test]
+    fn records_round_trip_through_the_history_line() {
+        let record = BuildRecord {
+            id: 12,
+            date: "2024-05-02 14:03:11".to_string(),
+            profile: "release".to_string(),
+            sources: 87,
+            cached: 80,
+            files: 240,
+            warnings: 3,
+            compile_ms: 912.4,
+            link_ms: 88.1,
+            total_ms: 1203.0,
+            executable_size: 182344,
+            objects_size: 901220,
+        };
+
+        assert_eq!(BuildRecord::from_line(&record.to_line()), Some(record));
+    }
+
+    #[test]
+    fn unknown_or_missing_fields_are_ignored() {
+        let record: BuildRecord = BuildRecord::from_line("2024-05-02 14:03:11\tid=3\tfuture=1\ttotal_ms=12.5").unwrap();
+        assert_eq!((record.id, record.profile.as_str(), record.total_ms, record.sources), (3, "debug", 12.5, 0));
+
+        assert_eq!(BuildRecord::from_line("2024-05-02 14:03:11\ttotal_ms=12.5"), None);
+        assert_eq!(BuildRecord::from_line(""), None);
+    }
+
+    #[test]
+    fn the_history_keeps_the_order_of_the_builds() {
+        let target: PathBuf = std::env::temp_dir().join(format!("compilerc-stats-{}-history", std::process::id()));
+        let _ = fs::remove_dir_all(&target);
+
+        assert!(load_history(&target).unwrap().is_empty());
+        assert_eq!(append_record(&target, record(100.0, 0, 10)).unwrap().id, 1);
+        assert_eq!(append_record(&target, record(120.0, 1, 10)).unwrap().id, 2);
+        let history: Vec<BuildRecord> = load_history(&target).unwrap();
+        assert_eq!(history.iter().map(|record| (record.id, record.total_ms)).collect::<Vec<(u32, f64)>>(), [(1, 100.0), (2, 120.0)]);
+        fs::remove_dir_all(&target).unwrap();
+    }
+
+    #[test]
+    fn durations_regress_beyond_twenty_percent() {
+        let previous: BuildRecord = record(1000.0, 0, 1000);
+
+        assert!(regressions(&record(1200.0, 0, 1000), &[&previous]).is_empty());
+        assert_eq!(regressions(&record(1201.0, 0, 1000), &[&previous]), [text("stats.total")]);
+        // La moyenne des builds précédents sert de référence
+        assert!(regressions(&record(1400.0, 0, 1000), &[&record(1000.0, 0, 1000), &record(1400.0, 0, 1000)]).is_empty());
+    }
+
+    #[test]
+    fn sizes_regress_beyond_five_percent() {
+        let previous: BuildRecord = record(1000.0, 0, 1000);
+
+        assert!(regressions(&record(1000.0, 0, 1050), &[&previous]).is_empty());
+        assert_eq!(regressions(&record(1000.0, 0, 1051), &[&previous]), [text("stats.executable_size")]);
+    }
+
+    #[test]
+    fn any_new_warning_is_a_regression() {
+        assert!(regressions(&record(1000.0, 0, 1000), &[&record(1000.0, 0, 1000)]).is_empty());
+        assert_eq!(regressions(&record(1000.0, 1, 1000), &[&record(1000.0, 0, 1000)]), [text("stats.warnings")]);
+        assert!(regressions(&record(1000.0, 2, 1000), &[&record(1000.0, 2, 1000)]).is_empty());
+        assert_eq!(regressions(&record(1000.0, 2, 1000), &[&record(1000.0, 1, 1000), &record(1000.0, 2, 1000)]), [text("stats.warnings")]);
+    }
+
+    #[test]
+    fn without_previous_builds_nothing_regresses() {
+        let comparisons: Vec<Comparison> = compare(&record(1000.0, 5, 1000), &[]);
+        assert!(comparisons.iter().all(|comparison| !comparison.regression && comparison.change().is_none()));
+    }
+
+    #[test]
+    fn measures_without_threshold_never_regress() {
+        let current = BuildRecord { sources: 100, cached: 100, ..BuildRecord::default() };
+        let previous = BuildRecord { sources: 1, cached: 1, ..BuildRecord::default() };
+        assert!(regressions(&current, &[&previous]).is_empty());
+    }
+
+    #[test]
+    fn the_change_is_relative_to_the_average() {
+        let comparison: Comparison = compare(&record(1500.0, 0, 0), &[&record(1000.0, 0, 0)]).into_iter().find(|comparison| comparison.label == text("stats.total")).unwrap();
+        assert_eq!(comparison.change(), Some(50.0));
+    }
+
+    #[test]
+    fn warnings_are_counted_in_untranslated_output() {
+        assert_eq!(count_warnings("a.c:1:2: warning: x [-Wcpp]\na.c:2:3: error: y\nb.c:4:1: warning: z\n"), 2);
+    }
+}
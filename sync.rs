@@ -0,0 +1,241 @@
+//! Synchronisation miroir des fichiers du projet vers les dossiers de la cible.
+//!
+//! Un fichier est recopié lorsque sa date de modification ou sa taille diffère et que son
+//! contenu (empreinte SHA-256) a réellement changé. Les fichiers qui n'existent plus dans le
+//! projet peuvent être supprimés de la destination.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::tr;
+
+/// Bilan d'une synchronisation, chemins relatifs au dossier de destination.
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    pub added: Vec<PathBuf>,
+    pub updated: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+    pub errors: Vec<String>,
+}
+
+impl SyncReport {
+    /// Indique si la synchronisation a modifié la destination.
+    pub fn has_changes(&self) -> bool {
+        !self.added.is_empty() || !self.updated.is_empty() || !self.removed.is_empty()
+    }
+
+    /// Résumé d'une ligne, suivi du détail des fichiers modifiés.
+    pub fn describe(&self, destination_folder: &str) -> String {
+        let mut description: String = tr!("sync.summary", destination_folder, self.added.len(), self.updated.len(), self.removed.len());
+
+        for (label, files) in [("+", &self.added), ("~", &self.updated), ("-", &self.removed)] {
+            for file in files {
+                description.push_str(&format!("\n\t{} {}", label, file.display()));
+            }
+        }
+        for error in &self.errors {
+            description.push_str(&format!("\n\t! {}", error));
+        }
+
+        description
+    }
+}
+
+/// Synchronise `files` vers `destination`, chaque fichier étant placé à son chemin relatif.
+///
+/// `files` associe le fichier source à son chemin relatif. Si `remove_stale` est vrai,
+/// les fichiers de la destination absents de `files` sont supprimés.
+pub fn sync_files(files: &[(PathBuf, PathBuf)], destination: &Path, remove_stale: bool) -> SyncReport {
+    let mut report = SyncReport::default();
+    let mut expected: HashSet<PathBuf> = HashSet::new();
+
+    for (source_file, relative_path) in files {
+        let destination_file: PathBuf = destination.join(relative_path);
+        expected.insert(destination_file.clone());
+
+        match sync_file(source_file, &destination_file) {
+            Ok(SyncAction::Added) => report.added.push(relative_path.clone()),
+            Ok(SyncAction::Updated) => report.updated.push(relative_path.clone()),
+            Ok(SyncAction::Unchanged) => (),
+            Err(err) => report.errors.push(format!("{} : {}", source_file.display(), err)),
+        }
+    }
+
+    if remove_stale {
+        remove_stale_files(destination, destination, &expected, &mut report);
+    }
+
+    report
+}
+
+enum SyncAction {
+    Added,
+    Updated,
+    Unchanged,
+}
+
+fn sync_file(source_file: &Path, destination_file: &Path) -> Result<SyncAction, io::Error> {
+    let source_metadata = fs::metadata(source_file)?;
+
+    let action: SyncAction = match fs::metadata(destination_file) {
+        Err(_) => SyncAction::Added,
+        Ok(destination_metadata) => {
+            let same_size: bool = source_metadata.len() == destination_metadata.len();
+            let same_time: bool = source_metadata.modified().ok() == destination_metadata.modified().ok();
+
+            if same_size && same_time {
+                return Ok(SyncAction::Unchanged);
+            }
+
+            if same_size && hash_file(source_file)? == hash_file(destination_file)? {
+                // Contenu identique : aligne seulement la date pour éviter de recalculer l'empreinte
+                File::options().write(true).open(destination_file)?.set_modified(source_metadata.modified()?)?;
+                return Ok(SyncAction::Unchanged);
+            }
+
+            SyncAction::Updated
+        }
+    };
+
+    if let Some(parent) = destination_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(source_file, destination_file)?;
+    File::options().write(true).open(destination_file)?.set_modified(source_metadata.modified()?)?;
+
+    Ok(action)
+}
+
+/// Supprime les fichiers non attendus puis les dossiers devenus vides.
+fn remove_stale_files(root: &Path, directory: &Path, expected: &HashSet<PathBuf>, report: &mut SyncReport) {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(err) => {
+            report.errors.push(format!("{} : {}", directory.display(), err));
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let entry_path: PathBuf = entry.path();
+        let is_dir: bool = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+
+        if is_dir {
+            remove_stale_files(root, &entry_path, expected, report);
+            // Échoue sans conséquence si le dossier n'est pas vide
+            let _ = fs::remove_dir(&entry_path);
+        } else if !expected.contains(&entry_path) {
+            match fs::remove_file(&entry_path) {
+                Ok(_) => report.removed.push(entry_path.strip_prefix(root).unwrap_or(&entry_path).to_path_buf()),
+                Err(err) => report.errors.push(format!("{} : {}", entry_path.display(), err)),
+            }
+        }
+    }
+}
+
+/// Calcule l'empreinte SHA-256 d'un fichier, en hexadécimal.
+pub fn hash_file(path: &Path) -> Result<String, io::Error> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read: usize = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    /// Dossier temporaire propre au test, vidé au préalable.
+    fn test_folder(name: &str) -> PathBuf {
+        let folder: PathBuf = std::env::temp_dir().join(format!("compilerc-sync-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(folder.join("source")).unwrap();
+        folder
+    }
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn new_files_are_added_then_left_unchanged() {
+        let folder: PathBuf = test_folder("added");
+        write(&folder.join("source/src/main.c"), "int main(void) { return 0; }");
+        let files: Vec<(PathBuf, PathBuf)> = vec![(folder.join("source/src/main.c"), PathBuf::from("src/main.c"))];
+
+        let report: SyncReport = sync_files(&files, &folder.join("copy"), false);
+        assert_eq!(report.added, vec![PathBuf::from("src/main.c")]);
+        assert_eq!(fs::read_to_string(folder.join("copy/src/main.c")).unwrap(), "int main(void) { return 0; }");
+
+        assert!(!sync_files(&files, &folder.join("copy"), false).has_changes());
+        fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn only_a_changed_content_is_copied_again() {
+        let folder: PathBuf = test_folder("updated");
+        let source_file: PathBuf = folder.join("source/a.c");
+        let files: Vec<(PathBuf, PathBuf)> = vec![(source_file.clone(), PathBuf::from("a.c"))];
+        write(&source_file, "int a;");
+        sync_files(&files, &folder.join("copy"), false);
+
+        // Même contenu, date différente : seule la date de la copie est alignée
+        let later: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(2_000_000_000);
+        File::options().write(true).open(&source_file).unwrap().set_modified(later).unwrap();
+        assert!(!sync_files(&files, &folder.join("copy"), false).has_changes());
+        assert_eq!(fs::metadata(folder.join("copy/a.c")).unwrap().modified().unwrap(), later);
+
+        write(&source_file, "int b;");
+        let report: SyncReport = sync_files(&files, &folder.join("copy"), false);
+        assert_eq!(report.updated, vec![PathBuf::from("a.c")]);
+        assert_eq!(fs::read_to_string(folder.join("copy/a.c")).unwrap(), "int b;");
+        fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn stale_files_are_removed_only_on_request() {
+        let folder: PathBuf = test_folder("stale");
+        write(&folder.join("source/kept.c"), "int kept;");
+        write(&folder.join("copy/old/stale.c"), "int stale;");
+        let files: Vec<(PathBuf, PathBuf)> = vec![(folder.join("source/kept.c"), PathBuf::from("kept.c"))];
+
+        assert!(sync_files(&files, &folder.join("copy"), false).removed.is_empty());
+        assert!(folder.join("copy/old/stale.c").exists());
+
+        let report: SyncReport = sync_files(&files, &folder.join("copy"), true);
+        assert_eq!(report.removed, vec![PathBuf::from("old/stale.c")]);
+        assert!(!folder.join("copy/old").exists());
+        assert!(folder.join("copy/kept.c").exists());
+        fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn a_missing_source_is_reported_without_stopping_the_sync() {
+        let folder: PathBuf = test_folder("missing");
+        write(&folder.join("source/present.c"), "int present;");
+        let files: Vec<(PathBuf, PathBuf)> = vec![
+            (folder.join("source/missing.c"), PathBuf::from("missing.c")),
+            (folder.join("source/present.c"), PathBuf::from("present.c")),
+        ];
+
+        let report: SyncReport = sync_files(&files, &folder.join("copy"), false);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.added, vec![PathBuf::from("present.c")]);
+        fs::remove_dir_all(folder).unwrap();
+    }
+}
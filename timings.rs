@@ -0,0 +1,206 @@
+//! Durées du build : compilation de chaque source, édition de liens et phases du compilateur.
+//!
+//! Le rapport est toujours écrit dans le log ; il est affiché avec `TIMINGS : on` (option `--timings`).
+//! Avec `TIME_REPORT : on` (option `--time-report`), les sources sont compilées avec `-ftime-report`
+//! et le temps réel de chaque phase de gcc est cumulé sur l'ensemble des sources.
+
+use std::cmp::Reverse;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::settings::Settings;
+use crate::tr;
+
+/// Nombre de sources listées par défaut dans le rapport.
+const DEFAULT_TOP: usize = 10;
+
+/// Durée de compilation d'une source.
+#[derive(Debug, Clone)]
+pub struct FileTiming {
+    pub source: PathBuf,
+    pub duration: Duration,
+    pub cached: bool,
+    /// Avertissements émis par gcc pour la source.
+    pub warnings: usize,
+}
+
+/// Durées mesurées pendant un build.
+#[derive(Debug, Clone, Default)]
+pub struct BuildTimings {
+    pub files: Vec<FileTiming>,
+    /// Temps réel cumulé de chaque phase de gcc, dans l'ordre d'apparition.
+    pub phases: Vec<(String, Duration)>,
+    pub link: Option<Duration>,
+}
+
+impl BuildTimings {
+    /// Ajoute les phases d'une compilation aux phases cumulées.
+    pub fn add_phases(&mut self, phases: &[(String, Duration)]) {
+        for (name, duration) in phases {
+            match self.phases.iter_mut().find(|(phase, _)| phase == name) {
+                Some((_, total)) => *total += *duration,
+                None => self.phases.push((name.clone(), *duration)),
+            }
+        }
+    }
+
+    /// Temps total passé à compiler les sources.
+    pub fn compile_total(&self) -> Duration {
+        self.files.iter().map(|file| file.duration).sum()
+    }
+
+    /// Les `count` sources les plus lentes à compiler.
+    pub fn slowest(&self, count: usize) -> Vec<&FileTiming> {
+        let mut files: Vec<&FileTiming> = self.files.iter().collect();
+        files.sort_by_key(|file| Reverse(file.duration));
+        files.truncate(count);
+        files
+    }
+
+    /// Lignes du rapport, avec les `top` sources les plus lentes.
+    pub fn report(&self, top: usize) -> Vec<String> {
+        let cached: usize = self.files.iter().filter(|file| file.cached).count();
+        let mut lines: Vec<String> = vec![tr!("timings.compile", self.files.len(), format_duration(self.compile_total()), cached)];
+
+        if let Some(link) = self.link {
+            lines.push(tr!("timings.link", format_duration(link)));
+        }
+
+        if !self.files.is_empty() {
+            lines.push(tr!("timings.slowest"));
+            for file in self.slowest(top) {
+                let origin: String = if file.cached { format!("  ({})", tr!("timings.cached")) } else { String::new() };
+                lines.push(format!("  {:>10}  {}{}", format_duration(file.duration), file.source.display(), origin));
+            }
+        }
+
+        if !self.phases.is_empty() {
+            let total: Duration = self.phases.iter().map(|(_, duration)| *duration).sum();
+            let mut phases: Vec<&(String, Duration)> = self.phases.iter().collect();
+            phases.sort_by_key(|(_, duration)| Reverse(*duration));
+
+            lines.push(tr!("timings.phases"));
+            for (name, duration) in phases {
+                let share: f64 = if total.is_zero() { 0.0 } else { duration.as_secs_f64() * 100.0 / total.as_secs_f64() };
+                lines.push(format!("  {:>10}  {:>5.1} %  {}", format_duration(*duration), share, name));
+            }
+        }
+
+        lines
+    }
+}
+
+/// Nombre de sources listées dans le rapport (`TIMINGS_TOP`).
+pub fn top_from_settings(settings: &Settings) -> usize {
+    settings.get("TIMINGS_TOP").and_then(|value| value.parse().ok()).unwrap_or(DEFAULT_TOP)
+}
+
+/// Sépare les diagnostics de gcc du rapport `-ftime-report` ; renvoie les diagnostics et les phases (`phase parsing`, ...).
+pub fn split_time_report(stderr: &str) -> (String, Vec<(String, Duration)>) {
+    let (diagnostics, report): (&str, &str) = match stderr.find("Time variable") {
+        Some(start) => (&stderr[..start], &stderr[start..]),
+        None => return (stderr.to_string(), Vec::new()),
+    };
+
+    let mut phases: Vec<(String, Duration)> = Vec::new();
+    for line in report.lines() {
+        let Some((name, values)) = line.split_once(':') else { continue };
+        let name: &str = name.trim();
+        if !name.starts_with("phase ") {
+            continue;
+        }
+
+        // Colonnes : utilisateur, système, réel, mémoire ; les pourcentages entre parenthèses sont ignorés
+        let mut columns: Vec<&str> = Vec::new();
+        let mut in_parentheses: bool = false;
+        for token in values.split_whitespace() {
+            if token.starts_with('(') {
+                in_parentheses = true;
+            }
+            if !in_parentheses {
+                columns.push(token);
+            }
+            if token.ends_with(')') {
+                in_parentheses = false;
+            }
+        }
+
+        if let Some(wall) = columns.get(2).and_then(|wall| wall.parse::<f64>().ok()) {
+            phases.push((name.to_string(), Duration::from_secs_f64(wall)));
+        }
+    }
+
+    (diagnostics.trim_end().to_string(), phases)
+}
+
+/// Durée en secondes avec trois décimales (`0.042 s`).
+pub fn format_duration(duration: Duration) -> String {
+    format!("{:.3} s", duration.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sortie de `gcc -c -ftime-report` (gcc 12.2) pour une source avec un avertissement.
+    const GCC_OUTPUT: &str = "tr.c:1:2: warning: #warning w [-Wcpp]
+    1 | #warning w
+      |  ^~~~~~~
+
+Time variable                                   usr           sys          wall           GGC
+ phase setup                        :   0.00 (  0%)   0.00 (  0%)   0.00 (  0%)  1326k ( 87%)
+ phase parsing                      :   0.00 (  0%)   0.00 (  0%)   0.01 (100%)   138k (  9%)
+ phase opt and generate             :   0.01 (100%)   0.00 (  0%)   0.00 (  0%)    58k (  4%)
+ parser (global)                    :   0.00 (  0%)   0.00 (  0%)   0.01 (100%)   128k (  8%)
+ initialize rtl                     :   0.01 (100%)   0.00 (  0%)   0.00 (  0%)    12k (  1%)
+ TOTAL                              :   0.01          0.00          0.01         1524k
+";
+
+    #[test]
+    fn the_time_report_is_split_from_the_diagnostics() {
+        let (diagnostics, phases) = split_time_report(GCC_OUTPUT);
+
+        assert_eq!(diagnostics, "tr.c:1:2: warning: #warning w [-Wcpp]\n    1 | #warning w\n      |  ^~~~~~~");
+        assert_eq!(
+            phases,
+            [
+                ("phase setup".to_string(), Duration::ZERO),
+                ("phase parsing".to_string(), Duration::from_millis(10)),
+                ("phase opt and generate".to_string(), Duration::ZERO),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_wall_column_is_read_after_percentages() {
+        // Pourcentages collés à la parenthèse, comme dans d'autres versions de gcc
+        let (_, phases) = split_time_report("Time variable  usr  sys  wall  GGC\n phase parsing  :   0.25 (25%)   0.05 (5%)   1.50 (60%)   138k (9%)\n");
+        assert_eq!(phases, [("phase parsing".to_string(), Duration::from_millis(1500))]);
+    }
+
+    #[test]
+    fn output_without_report_is_left_unchanged() {
+        let stderr: &str = "main.c:3:5: warning: unused variable 'x' [-Wunused-variable]\n";
+        assert_eq!(split_time_report(stderr), (stderr.to_string(), Vec::new()));
+    }
+
+    #[test]
+    fn phases_are_added_up_across_sources() {
+        let mut timings: BuildTimings = BuildTimings::default();
+        timings.add_phases(&[("phase parsing".to_string(), Duration::from_millis(10)), ("phase setup".to_string(), Duration::from_millis(1))]);
+        timings.add_phases(&[("phase parsing".to_string(), Duration::from_millis(5))]);
+
+        assert_eq!(timings.phases, [("phase parsing".to_string(), Duration::from_millis(15)), ("phase setup".to_string(), Duration::from_millis(1))]);
+    }
+
+    #[test]
+    fn the_slowest_sources_come_first() {
+        let file = |name: &str, millis: u64| FileTiming { source: PathBuf::from(name), duration: Duration::from_millis(millis), cached: false, warnings: 0 };
+        let timings = BuildTimings { files: vec![file("a.c", 20), file("b.c", 90), file("c.c", 50)], ..BuildTimings::default() };
+
+        let slowest: Vec<&str> = timings.slowest(2).iter().map(|file| file.source.to_str().unwrap()).collect();
+        assert_eq!(slowest, ["b.c", "c.c"]);
+        assert_eq!(timings.compile_total(), Duration::from_millis(160));
+        assert_eq!(format_duration(Duration::from_millis(1234)), "1.234 s");
+    }
+}
@@ -0,0 +1,388 @@
+//! Compilation distribuée sur des machines de travail, à la manière de distcc.
+//!
+//! Le client préprocesse chaque source localement et envoie l'unité de traduction à un
+//! worker (`main worker [adresse]`) qui la compile avec son propre gcc et renvoie le fichier
+//! objet et les diagnostics. Une connexion transporte une seule tâche :
+//! le client envoie sa requête puis ferme l'écriture, le worker répond puis ferme la connexion.
+//!
+//! Le worker écoute par défaut sur la boucle locale. Sur une autre adresse, il exige un jeton partagé
+//! (`COMPILERC_WORKER_TOKEN`) que les clients transmettent avec chaque tâche (`WORKER_TOKEN`).
+
+use std::env;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::process::Output;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::diagnostics::gcc_command;
+use crate::settings::Settings;
+use crate::tr;
+
+/// Adresse d'écoute par défaut du worker, limitée à la machine locale.
+pub const DEFAULT_WORKER_ADDRESS: &str = "127.0.0.1:7878";
+
+/// Variable d'environnement contenant le jeton partagé entre le worker et ses clients.
+pub const WORKER_TOKEN_VARIABLE: &str = "COMPILERC_WORKER_TOKEN";
+
+const JOB_MAGIC: &str = "COMPILERC-JOB 1";
+const RESULT_MAGIC: &str = "COMPILERC-RESULT 1";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const JOB_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Délai maximal entre deux lectures d'une requête reçue par le worker.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Taille maximale d'une requête (en-tête et source préprocessé).
+const MAX_REQUEST_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Options acceptées telles quelles par le worker : le source est déjà préprocessé, seules les
+/// options de génération de code et d'avertissement ont un sens. Aucune n'écrit de fichier ni ne charge de code.
+const ALLOWED_FLAGS: [&str; 34] = [
+    "-w",
+    "-pedantic",
+    "-pedantic-errors",
+    "-ansi",
+    "-fPIC",
+    "-fpic",
+    "-fPIE",
+    "-fpie",
+    "-fcommon",
+    "-fno-common",
+    "-fstrict-aliasing",
+    "-fno-strict-aliasing",
+    "-fomit-frame-pointer",
+    "-fno-omit-frame-pointer",
+    "-fstack-protector",
+    "-fstack-protector-strong",
+    "-fstack-protector-all",
+    "-fno-stack-protector",
+    "-fwrapv",
+    "-fno-builtin",
+    "-ffunction-sections",
+    "-fdata-sections",
+    "-fsigned-char",
+    "-funsigned-char",
+    "-fshort-enums",
+    "-fno-plt",
+    "-finline-functions",
+    "-fno-inline",
+    "-funroll-loops",
+    "-ffast-math",
+    "-fno-math-errno",
+    "-fasynchronous-unwind-tables",
+    "-fno-asynchronous-unwind-tables",
+    "-ftime-report",
+];
+
+/// Résultat d'une compilation effectuée par un worker.
+#[derive(Debug, Clone)]
+pub struct WorkerResult {
+    pub success: bool,
+    pub object: Vec<u8>,
+    pub diagnostics: String,
+}
+
+/// Adresses des workers déclarées par la clé `WORKERS`.
+pub fn workers_from_settings(settings: &Settings) -> Vec<String> {
+    settings.get_list("WORKERS")
+}
+
+/// Jeton transmis aux workers : clé `WORKER_TOKEN`, sinon variable `COMPILERC_WORKER_TOKEN`.
+pub fn worker_token(settings: &Settings) -> Option<String> {
+    settings.get("WORKER_TOKEN").map(str::to_string).or_else(|| env::var(WORKER_TOKEN_VARIABLE).ok()).filter(|token| !token.is_empty())
+}
+
+/// Indique si une option peut être transmise à un worker.
+///
+/// En plus de `ALLOWED_FLAGS`, sont acceptés les niveaux `-O` et `-g`, `-std=`, et les options
+/// `-W` et `-m` nommées ; une virgule est toujours refusée (`-Wa,`, `-Wl,`, `-Wp,` transmettent des options à d'autres outils).
+pub fn is_allowed_flag(flag: &str) -> bool {
+    let is_name = |name: &str| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '=');
+
+    if ALLOWED_FLAGS.contains(&flag) {
+        return true;
+    }
+    if let Some(level) = flag.strip_prefix("-O") {
+        return ["", "0", "1", "2", "3", "s", "g", "z", "fast"].contains(&level);
+    }
+    if let Some(level) = flag.strip_prefix("-g") {
+        return ["", "0", "1", "2", "3", "gdb", "gdb1", "gdb2", "gdb3"].contains(&level);
+    }
+    if let Some(standard) = flag.strip_prefix("-std=") {
+        return !standard.is_empty() && standard.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == ':');
+    }
+    if let Some(value) = flag.strip_prefix("-fvisibility=") {
+        return ["default", "hidden", "protected", "internal"].contains(&value);
+    }
+    if let Some(value) = flag.strip_prefix("-fdiagnostics-color=") {
+        return ["auto", "always", "never"].contains(&value);
+    }
+    if let Some(count) = flag.strip_prefix("-fmax-errors=") {
+        return !count.is_empty() && count.chars().all(|c| c.is_ascii_digit());
+    }
+
+    match flag.strip_prefix("-W").or_else(|| flag.strip_prefix("-m")) {
+        Some(name) => is_name(name),
+        None => false,
+    }
+}
+
+/// Envoie une unité de traduction préprocessée à un worker.
+///
+/// Une erreur signifie que le worker n'a pas pu traiter la tâche (connexion, protocole) ;
+/// une erreur de compilation est renvoyée dans un `WorkerResult` dont `success` vaut `false`.
+pub fn compile_on_worker(address: &str, token: Option<&str>, source_name: &str, preprocessed_source: &[u8], flags: &[String]) -> Result<WorkerResult, String> {
+    let socket_address = address
+        .to_socket_addrs()
+        .map_err(|err| tr!("worker.not_found_error", address, err))?
+        .next()
+        .ok_or_else(|| tr!("worker.not_found", address))?;
+
+    let mut stream: TcpStream = TcpStream::connect_timeout(&socket_address, CONNECT_TIMEOUT)
+        .map_err(|err| tr!("worker.connect_failed", address, err))?;
+    let _ = stream.set_read_timeout(Some(JOB_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(JOB_TIMEOUT));
+
+    let mut request: String = format!("{}\nname : {}\n", JOB_MAGIC, source_name);
+    if let Some(token) = token {
+        request.push_str(&format!("token : {}\n", token));
+    }
+    for flag in flags {
+        request.push_str(&format!("flag : {}\n", flag));
+    }
+    request.push_str(&format!("source : {}\n\n", preprocessed_source.len()));
+
+    stream
+        .write_all(request.as_bytes())
+        .and_then(|_| stream.write_all(preprocessed_source))
+        .and_then(|_| stream.shutdown(Shutdown::Write))
+        .map_err(|err| tr!("worker.send_failed", address, err))?;
+
+    let mut response: Vec<u8> = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|err| tr!("worker.unreadable_response", address, err))?;
+
+    parse_result(&response, address)
+}
+
+/// Lit la réponse d'un worker ; les tailles annoncées doivent couvrir exactement le contenu reçu.
+fn parse_result(response: &[u8], address: &str) -> Result<WorkerResult, String> {
+    let (fields, payload) = split_message(response, RESULT_MAGIC)?;
+    let object_size: usize = size_field(&fields, "OBJECT")?;
+    let diagnostics_size: usize = size_field(&fields, "DIAGNOSTICS")?;
+
+    if object_size.checked_add(diagnostics_size) != Some(payload.len()) {
+        return Err(tr!("worker.truncated_response", address));
+    }
+
+    let (object, diagnostics) = payload.split_at(object_size);
+    Ok(WorkerResult {
+        success: fields.get("STATUS") == Some("ok"),
+        object: object.to_vec(),
+        diagnostics: String::from_utf8_lossy(diagnostics).to_string(),
+    })
+}
+
+/// Lance le worker : chaque connexion est traitée dans son propre thread, dans la limite d'une tâche
+/// par cœur. Une adresse autre que la boucle locale exige un jeton (`COMPILERC_WORKER_TOKEN`).
+pub fn run_worker(address: &str, token: Option<String>) -> Result<(), String> {
+    let listener: TcpListener = TcpListener::bind(address).map_err(|err| tr!("worker.listen_failed", address, err))?;
+    let is_local: bool = listener.local_addr().is_ok_and(|local_address| local_address.ip().is_loopback());
+    if !is_local && token.is_none() {
+        return Err(tr!("worker.token_required", address, WORKER_TOKEN_VARIABLE));
+    }
+    println!("{}", tr!("worker.listening", address));
+
+    let token: Arc<Option<String>> = Arc::new(token);
+    let max_jobs: usize = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let running_jobs: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let peer: String = stream.peer_addr().map(|peer| peer.to_string()).unwrap_or_default();
+                if running_jobs.fetch_add(1, Ordering::SeqCst) >= max_jobs {
+                    running_jobs.fetch_sub(1, Ordering::SeqCst);
+                    eprintln!("{}", tr!("worker.busy", peer, max_jobs));
+                    continue;
+                }
+
+                let token: Arc<Option<String>> = Arc::clone(&token);
+                let running_jobs: Arc<AtomicUsize> = Arc::clone(&running_jobs);
+                thread::spawn(move || {
+                    if let Err(err) = handle_job(stream, token.as_deref()) {
+                        eprintln!("{}", tr!("worker.task_dropped", peer, err));
+                    }
+                    running_jobs.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+            Err(err) => eprintln!("{}", tr!("worker.connection_refused", err)),
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_job(mut stream: TcpStream, token: Option<&str>) -> Result<(), String> {
+    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(REQUEST_TIMEOUT));
+
+    let mut request: Vec<u8> = Vec::new();
+    (&mut stream)
+        .take(MAX_REQUEST_SIZE + 1)
+        .read_to_end(&mut request)
+        .map_err(|err| tr!("worker.unreadable_request", err))?;
+    if request.len() as u64 > MAX_REQUEST_SIZE {
+        return Err(tr!("worker.request_too_large", MAX_REQUEST_SIZE));
+    }
+
+    let (fields, payload) = split_message(&request, JOB_MAGIC)?;
+    if let Some(token) = token {
+        if !fields.get("TOKEN").is_some_and(|received| same_token(received, token)) {
+            return Err(tr!("worker.invalid_token"));
+        }
+    }
+    if payload.len() != size_field(&fields, "SOURCE")? {
+        return Err(tr!("worker.truncated_request"));
+    }
+
+    let source_name: String = fields.get("NAME").unwrap_or("source.c").to_string();
+    let flags: Vec<String> = fields.get_all("FLAG");
+    if let Some(flag) = flags.iter().find(|flag| !is_allowed_flag(flag)) {
+        return send_result(&mut stream, false, &[], &format!("{}\n", tr!("worker.refused_option", flag)));
+    }
+
+    let (success, object, diagnostics) = compile_job(payload, &flags)?;
+    println!("{}", tr!(if success { "worker.compiled" } else { "worker.failed" }, source_name));
+
+    send_result(&mut stream, success, &object, &diagnostics)
+}
+
+/// Compare deux jetons en un temps indépendant de la position de la première différence.
+fn same_token(received: &str, expected: &str) -> bool {
+    received.len() == expected.len() && received.bytes().zip(expected.bytes()).fold(0, |difference, (a, b)| difference | (a ^ b)) == 0
+}
+
+/// Compile l'unité de traduction dans un dossier temporaire propre à la tâche.
+fn compile_job(preprocessed_source: &[u8], flags: &[String]) -> Result<(bool, Vec<u8>, String), String> {
+    let job_directory = env::temp_dir().join(format!("compilerc-worker-{}-{:?}", std::process::id(), thread::current().id()));
+    fs::create_dir_all(&job_directory).map_err(|err| err.to_string())?;
+
+    let source_path = job_directory.join("source.i");
+    let object_path = job_directory.join("source.o");
+    fs::write(&source_path, preprocessed_source).map_err(|err| err.to_string())?;
+
+    let output: Result<Output, String> = gcc_command()
+        .args(["-x", "cpp-output", "-c"])
+        .arg(&source_path)
+        .args(flags)
+        .arg("-o")
+        .arg(&object_path)
+        .output()
+        .map_err(|err| tr!("worker.gcc_not_found", err));
+
+    let result = output.map(|output| {
+        let object: Vec<u8> = if output.status.success() { fs::read(&object_path).unwrap_or_default() } else { Vec::new() };
+        (output.status.success(), object, String::from_utf8_lossy(&output.stderr).to_string())
+    });
+
+    let _ = fs::remove_dir_all(&job_directory);
+    result
+}
+
+fn send_result(stream: &mut TcpStream, success: bool, object: &[u8], diagnostics: &str) -> Result<(), String> {
+    let header: String = format!(
+        "{}\nstatus : {}\nobject : {}\ndiagnostics : {}\n\n",
+        RESULT_MAGIC,
+        if success { "ok" } else { "error" },
+        object.len(),
+        diagnostics.len()
+    );
+
+    stream
+        .write_all(header.as_bytes())
+        .and_then(|_| stream.write_all(object))
+        .and_then(|_| stream.write_all(diagnostics.as_bytes()))
+        .map_err(|err| tr!("worker.result_failed", err))
+}
+
+/// Sépare l'en-tête (`CLE : valeur`) du contenu binaire d'un message.
+fn split_message<'a>(message: &'a [u8], magic: &str) -> Result<(Settings, &'a [u8]), String> {
+    let header_end: usize = message.windows(2).position(|window| window == b"\n\n").ok_or_else(|| tr!("worker.incomplete_message"))?;
+    let header: String = String::from_utf8_lossy(&message[..header_end]).to_string();
+
+    if header.lines().next() != Some(magic) {
+        return Err(tr!("worker.unknown_format"));
+    }
+
+    Ok((Settings::parse(&header), &message[header_end + 2..]))
+}
+
+fn size_field(fields: &Settings, key: &str) -> Result<usize, String> {
+    fields
+        .get(key)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| tr!("worker.missing_field", key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_generation_and_warning_flags_are_allowed() {
+        for flag in ["-O2", "-Os", "-g", "-ggdb3", "-std=gnu11", "-Wall", "-Wno-unused", "-Werror=format", "-m64", "-march=x86-64-v2", "-fPIC", "-ftime-report", "-fvisibility=hidden"] {
+            assert!(is_allowed_flag(flag), "{}", flag);
+        }
+    }
+
+    #[test]
+    fn pass_through_and_file_writing_flags_are_refused() {
+        for flag in [
+            "-Wa,-o,/tmp/x",
+            "-Wl,-T,script",
+            "-Wp,-MD,/tmp/x",
+            "-W",
+            "-fplugin=/tmp/x.so",
+            "-fdump-tree-all=/tmp/x",
+            "-fprofile-generate=/tmp",
+            "-fstack-usage",
+            "-fvisibility=x",
+            "-O9",
+            "-o",
+            "-specs=/tmp/x",
+            "-B/tmp",
+            "@/tmp/options",
+        ] {
+            assert!(!is_allowed_flag(flag), "{}", flag);
+        }
+    }
+
+    #[test]
+    fn a_result_is_split_into_object_and_diagnostics() {
+        let result: WorkerResult = parse_result(b"COMPILERC-RESULT 1\nstatus : ok\nobject : 3\ndiagnostics : 2\n\nobjok", "w").unwrap();
+        assert!(result.success);
+        assert_eq!(result.object, b"obj");
+        assert_eq!(result.diagnostics, "ok");
+    }
+
+    #[test]
+    fn overflowing_sizes_are_refused() {
+        let response: String = format!("COMPILERC-RESULT 1\nstatus : ok\nobject : {}\ndiagnostics : 1\n\n", usize::MAX);
+        assert!(parse_result(response.as_bytes(), "w").is_err());
+
+        let response: String = format!("COMPILERC-RESULT 1\nstatus : ok\nobject : 1\ndiagnostics : {}\n\nx", usize::MAX);
+        assert!(parse_result(response.as_bytes(), "w").is_err());
+    }
+
+    #[test]
+    fn tokens_must_match_exactly() {
+        assert!(same_token("secret", "secret"));
+        assert!(!same_token("secret", "secreT"));
+        assert!(!same_token("secret", "secret2"));
+    }
+}